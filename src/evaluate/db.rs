@@ -59,9 +59,12 @@ impl DB {
 #[cfg(test)]
 mod test {
     use super::DB;
+    use crate::storage::json::JsonValue;
     use crate::storage::storage_manager::AttributeName;
     use crate::storage::tuple_serde::StorageTupleValue;
     use crate::storage::tuple_serde::StorageTupleValue::Integer;
+    use crate::storage::types::EnumLabel;
+    use crate::storage::uuid::Uuid;
     use std::collections::HashMap;
 
     fn execute_and_discard_result(db: &mut DB, stmt: Vec<&str>) {
@@ -201,4 +204,2013 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn exec_outer_joins() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar primary key, age integer);",
+                "insert into person (name, age) values ('a', 1);",
+                "insert into person (name, age) values ('b', 2);",
+                "create table employee (id varchar primary key, salary integer);",
+                "insert into employee (id, salary) values ('a', 10);",
+                "insert into employee (id, salary) values ('c', 30);",
+            ],
+        );
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let row = |name: StorageTupleValue, id: StorageTupleValue| {
+            vec![
+                (AttributeName("name".to_owned()), name),
+                (AttributeName("id".to_owned()), id),
+            ]
+        };
+
+        assert_tuples(
+            vec![
+                row(text("a"), text("a")),
+                row(text("b"), StorageTupleValue::Null),
+            ],
+            db.execute("select name, id from person left join employee on name = id;")
+                .unwrap(),
+        );
+        assert_tuples(
+            vec![
+                row(text("a"), text("a")),
+                row(text("b"), StorageTupleValue::Null),
+            ],
+            db.execute("select name, id from person left outer join employee on name = id;")
+                .unwrap(),
+        );
+        assert_tuples(
+            vec![
+                row(text("a"), text("a")),
+                row(StorageTupleValue::Null, text("c")),
+            ],
+            db.execute("select name, id from person right join employee on name = id;")
+                .unwrap(),
+        );
+        assert_eq!(
+            db.execute(
+                "select name, id from person full outer join employee on name = id order by name;"
+            )
+            .unwrap(),
+            vec![
+                row(text("a"), text("a")),
+                row(text("b"), StorageTupleValue::Null),
+                row(StorageTupleValue::Null, text("c")),
+            ]
+        );
+
+        assert_tuples(
+            vec![
+                row(text("a"), StorageTupleValue::Null),
+                row(text("b"), StorageTupleValue::Null),
+            ],
+            db.execute(
+                "select name, id from person left join employee on name = id and salary > 20;",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            db.execute("select salary + 1 as bonus from person left join employee on name = id order by bonus;")
+                .unwrap(),
+            vec![
+                vec![(AttributeName("bonus".to_owned()), Integer(11))],
+                vec![(AttributeName("bonus".to_owned()), StorageTupleValue::Null)],
+            ]
+        );
+
+        // Aggregates of an expression skip NULL.
+        assert_eq!(
+            db.execute("select count(*), count(salary), sum(salary) from person full join employee on name = id;")
+                .unwrap(),
+            vec![vec![
+                (AttributeName("count(*)".to_owned()), Integer(3)),
+                (AttributeName("count(salary)".to_owned()), Integer(2)),
+                (AttributeName("sum(salary)".to_owned()), Integer(40)),
+            ]]
+        );
+    }
+
+    #[test]
+    fn exec_multi_joins() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar primary key, city varchar);",
+                "insert into person (name, city) values ('a', 'x');",
+                "insert into person (name, city) values ('b', 'y');",
+                "create table employee (id varchar primary key, department varchar);",
+                "insert into employee (id, department) values ('a', 'sales');",
+                "insert into employee (id, department) values ('b', 'product');",
+                "create table office (location varchar primary key, floor integer);",
+                "insert into office (location, floor) values ('x', 1);",
+                "insert into office (location, floor) values ('z', 3);",
+            ],
+        );
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let row = |name: &str, department: &str, floor: StorageTupleValue| {
+            vec![
+                (AttributeName("name".to_owned()), text(name)),
+                (AttributeName("department".to_owned()), text(department)),
+                (AttributeName("floor".to_owned()), floor),
+            ]
+        };
+
+        assert_tuples(
+            vec![row("a", "sales", Integer(1))],
+            db.execute(
+                "select name, department, floor from person join employee on name = id \
+                 join office on city = location;",
+            )
+            .unwrap(),
+        );
+        assert_tuples(
+            vec![
+                row("a", "sales", Integer(1)),
+                row("b", "product", StorageTupleValue::Null),
+            ],
+            db.execute(
+                "select name, department, floor from person join employee on name = id \
+                 left join office on city = location;",
+            )
+            .unwrap(),
+        );
+        assert_tuples(
+            vec![
+                row("a", "sales", Integer(1)),
+                vec![
+                    (AttributeName("name".to_owned()), text("b")),
+                    (
+                        AttributeName("department".to_owned()),
+                        StorageTupleValue::Null,
+                    ),
+                    (AttributeName("floor".to_owned()), StorageTupleValue::Null),
+                ],
+            ],
+            db.execute(
+                "select name, department, floor from person \
+                 left join (employee join office on department = 'sales') on name = id \
+                 and city = location;",
+            )
+            .unwrap(),
+        );
+        assert_tuples(
+            vec![row("a", "sales", Integer(1))],
+            db.execute(
+                "select name, department, floor from person, employee, office \
+                 where name = id and city = location;",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            db.execute("select count(*) from person cross join employee cross join office;")
+                .unwrap(),
+            vec![vec![(AttributeName("count(*)".to_owned()), Integer(8))]]
+        );
+        assert_eq!(
+            db.execute("select count(*) from person, employee join office on true;")
+                .unwrap(),
+            vec![vec![(AttributeName("count(*)".to_owned()), Integer(8))]]
+        );
+        assert!(db
+            .execute("select * from person join person on true;")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_table_aliases() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar primary key, age integer);",
+                "insert into person (name, age) values ('a', 1);",
+                "insert into person (name, age) values ('b', 2);",
+                "create table employee (id varchar primary key, name varchar);",
+                "insert into employee (id, name) values ('a', 'sales');",
+                "insert into employee (id, name) values ('b', 'product');",
+            ],
+        );
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let name = |attr_name: &str| AttributeName(attr_name.to_owned());
+
+        assert_tuples(
+            vec![
+                vec![(name("p.name"), text("a")), (name("e.name"), text("sales"))],
+                vec![
+                    (name("p.name"), text("b")),
+                    (name("e.name"), text("product")),
+                ],
+            ],
+            db.execute(
+                "select p.name, e.name from person p inner join employee as e on p.name = e.id;",
+            )
+            .unwrap(),
+        );
+        assert_tuples(
+            vec![vec![
+                (name("person.name"), text("b")),
+                (name("age"), Integer(2)),
+            ]],
+            db.execute(
+                "select person.name, age from person join employee on person.name = id \
+                 where employee.name = 'product';",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            db.execute("select p1.name, p2.name from person p1 join person p2 on p1.age < p2.age;")
+                .unwrap(),
+            vec![vec![
+                (name("p1.name"), text("a")),
+                (name("p2.name"), text("b"))
+            ]]
+        );
+        assert_eq!(
+            db.execute("select p.name from person p where p.age > 1 order by p.age;")
+                .unwrap(),
+            vec![vec![(name("p.name"), text("b"))]]
+        );
+        assert_eq!(
+            db.execute("select * from (select name from person) al where al.name = 'a';")
+                .unwrap(),
+            vec![vec![(name("al.name"), text("a"))]]
+        );
+        assert_eq!(
+            db.execute(
+                "select e.name, count(*) from person p join employee e on p.name = e.id \
+                 group by e.name order by e.name;"
+            )
+            .unwrap(),
+            vec![
+                vec![
+                    (name("e.name"), text("product")),
+                    (name("count(*)"), Integer(1))
+                ],
+                vec![
+                    (name("e.name"), text("sales")),
+                    (name("count(*)"), Integer(1))
+                ],
+            ]
+        );
+
+        // An unqualified name of attributes of several tables is ambiguous.
+        assert!(db
+            .execute("select name from person join employee on person.name = id;")
+            .is_err());
+        assert!(db
+            .execute("select p1.name from person p1 join person p2 on name = p2.name;")
+            .is_err());
+        assert!(db
+            .execute("select * from person join person on true;")
+            .is_err());
+        assert!(db.execute("select person.name from person p;").is_err());
+    }
+
+    #[test]
+    fn exec_join_using() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (id integer primary key, name varchar);",
+                "insert into person (id, name) values (1, 'a');",
+                "insert into person (id, name) values (2, 'b');",
+                "create table salary (id integer primary key, amount integer);",
+                "insert into salary (id, amount) values (2, 20);",
+                "insert into salary (id, amount) values (3, 30);",
+                "create table bonus (amount integer primary key, id integer);",
+                "insert into bonus (amount, id) values (20, 2);",
+            ],
+        );
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let name = |attr_name: &str| AttributeName(attr_name.to_owned());
+
+        // The attributes USING merges are selected once by `*`.
+        assert_eq!(
+            db.execute("select * from person join salary using (id);")
+                .unwrap(),
+            vec![vec![
+                (name("id"), Integer(2)),
+                (name("person.name"), text("b")),
+                (name("salary.amount"), Integer(20)),
+            ]]
+        );
+        assert_eq!(
+            db.execute("select * from person natural join salary;")
+                .unwrap(),
+            db.execute("select * from person join salary using (id);")
+                .unwrap(),
+        );
+        assert_eq!(
+            db.execute(
+                "select id, name, amount from person left join salary using (id) order by id;"
+            )
+            .unwrap(),
+            vec![
+                vec![
+                    (name("id"), Integer(1)),
+                    (name("name"), text("a")),
+                    (name("amount"), StorageTupleValue::Null),
+                ],
+                vec![
+                    (name("id"), Integer(2)),
+                    (name("name"), text("b")),
+                    (name("amount"), Integer(20)),
+                ],
+            ]
+        );
+
+        // The qualified names refer to the attributes of each side.
+        assert_eq!(
+            db.execute(
+                "select id, person.id, s.id from person full join salary s using (id) order by id;"
+            )
+            .unwrap(),
+            vec![
+                vec![
+                    (name("id"), Integer(1)),
+                    (name("person.id"), Integer(1)),
+                    (name("s.id"), StorageTupleValue::Null),
+                ],
+                vec![
+                    (name("id"), Integer(2)),
+                    (name("person.id"), Integer(2)),
+                    (name("s.id"), Integer(2)),
+                ],
+                vec![
+                    (name("id"), Integer(3)),
+                    (name("person.id"), StorageTupleValue::Null),
+                    (name("s.id"), Integer(3)),
+                ],
+            ]
+        );
+
+        // Joining on all the attributes of the same names.
+        assert_eq!(
+            db.execute("select * from salary natural join bonus;")
+                .unwrap(),
+            vec![vec![
+                (name("id"), Integer(2)),
+                (name("amount"), Integer(20))
+            ]]
+        );
+        assert_eq!(
+            db.execute(
+                "select * from person join salary using (id) join bonus using (id) \
+                 where bonus.amount = 20;"
+            )
+            .unwrap(),
+            vec![vec![
+                (name("id"), Integer(2)),
+                (name("person.name"), text("b")),
+                (name("salary.amount"), Integer(20)),
+                (name("bonus.amount"), Integer(20)),
+            ]]
+        );
+
+        assert!(db
+            .execute("select * from person join salary using (name);")
+            .is_err());
+        assert!(db
+            .execute("select * from person join salary using (id, id);")
+            .is_err());
+        assert!(db
+            .execute("select * from person join salary using (person.id);")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_set_operations() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar primary key, age integer);",
+                "insert into person (name, age) values ('a', 1);",
+                "insert into person (name, age) values ('b', 2);",
+                "insert into person (name, age) values ('c', 2);",
+                "create table employee (id varchar primary key, age integer);",
+                "insert into employee (id, age) values ('b', 2);",
+                "insert into employee (id, age) values ('d', 4);",
+            ],
+        );
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let name = |attr_name: &str| AttributeName(attr_name.to_owned());
+        let ages = |ages: Vec<i32>| {
+            ages.into_iter()
+                .map(|age| vec![(name("age"), Integer(age))])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            db.execute("select age from person union select age from employee order by age;")
+                .unwrap(),
+            ages(vec![1, 2, 4])
+        );
+        assert_eq!(
+            db.execute(
+                "select age from person union all select age from employee order by age desc;"
+            )
+            .unwrap(),
+            ages(vec![4, 2, 2, 2, 1])
+        );
+        assert_eq!(
+            db.execute("select age from person intersect select age from employee;")
+                .unwrap(),
+            ages(vec![2])
+        );
+        assert_eq!(
+            db.execute("select age from person except select age from employee;")
+                .unwrap(),
+            ages(vec![1])
+        );
+        assert_eq!(
+            db.execute("select age from person except all select age from employee order by age;")
+                .unwrap(),
+            ages(vec![1, 2])
+        );
+
+        // The attributes are named after those of the left query.
+        assert_eq!(
+            db.execute(
+                "select name, age from person where age = 1 \
+                 union select id, age from employee order by name limit 2;"
+            )
+            .unwrap(),
+            vec![
+                vec![(name("name"), text("a")), (name("age"), Integer(1))],
+                vec![(name("name"), text("b")), (name("age"), Integer(2))],
+            ]
+        );
+        assert_eq!(
+            db.execute(
+                "select count(*) from (select name from person \
+                 union select id from employee) as names;"
+            )
+            .unwrap(),
+            vec![vec![(name("count(*)"), Integer(4))]]
+        );
+
+        assert!(db
+            .execute("select name, age from person union select age from employee;")
+            .is_err());
+        assert!(db
+            .execute("select name from person union select age from employee;")
+            .is_err());
+        assert!(db
+            .execute("select age from person union select age from employee order by id;")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_subqueries() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar primary key, age integer);",
+                "insert into person (name, age) values ('a', 1);",
+                "insert into person (name, age) values ('b', 2);",
+                "insert into person (name, age) values ('c', 2);",
+                "create table employee (id varchar primary key, age integer);",
+                "insert into employee (id, age) values ('b', 2);",
+                "insert into employee (id, age) values ('d', 4);",
+            ],
+        );
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let name = |attr_name: &str| AttributeName(attr_name.to_owned());
+        let names = |names: Vec<&str>| {
+            names
+                .into_iter()
+                .map(|value| vec![(name("name"), text(value))])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            db.execute("select name from person where name in (select id from employee);")
+                .unwrap(),
+            names(vec!["b"])
+        );
+        assert_eq!(
+            db.execute(
+                "select name from person where name not in (select id from employee) \
+                 order by name;"
+            )
+            .unwrap(),
+            names(vec!["a", "c"])
+        );
+        assert_eq!(
+            db.execute(
+                "select name from person where name in (select id from employee \
+                 union select name from person where age < 2 order by id limit 2) order by name;"
+            )
+            .unwrap(),
+            names(vec!["a", "b"])
+        );
+        assert_eq!(
+            db.execute(
+                "select * from person where age = (select min(age) from employee) order by name;",
+            )
+            .unwrap(),
+            vec![
+                vec![(name("name"), text("b")), (name("age"), Integer(2))],
+                vec![(name("name"), text("c")), (name("age"), Integer(2))],
+            ]
+        );
+
+        // Correlated subqueries refer to the attributes of the record they are evaluated for.
+        assert_eq!(
+            db.execute(
+                "select name from person where exists \
+                 (select * from employee where employee.age = person.age) order by name;"
+            )
+            .unwrap(),
+            names(vec!["b", "c"])
+        );
+        assert_eq!(
+            db.execute(
+                "select name from person as p where not exists \
+                 (select * from employee where age = p.age);"
+            )
+            .unwrap(),
+            names(vec!["a"])
+        );
+        assert_eq!(
+            db.execute(
+                "select name from person as p where exists \
+                 (select * from employee as e where e.age = p.age and exists \
+                 (select * from employee where id = p.name));"
+            )
+            .unwrap(),
+            names(vec!["b"])
+        );
+        assert_eq!(
+            db.execute(
+                "select name, (select count(*) from employee where age = person.age) as count \
+                 from person order by name;"
+            )
+            .unwrap(),
+            vec![
+                vec![(name("name"), text("a")), (name("count"), Integer(0))],
+                vec![(name("name"), text("b")), (name("count"), Integer(1))],
+                vec![(name("name"), text("c")), (name("count"), Integer(1))],
+            ]
+        );
+        assert_eq!(
+            db.execute(
+                "select (select max(age) from employee) + age as max_age, \
+                 exists (select * from employee where id = 'x') from person where name = 'a';"
+            )
+            .unwrap(),
+            vec![vec![
+                (name("max_age"), Integer(5)),
+                (name("EXISTS (subquery)"), StorageTupleValue::Boolean(false)),
+            ]]
+        );
+
+        assert!(db
+            .execute("select name from person where name in (select id, age from employee);")
+            .is_err());
+        assert!(db
+            .execute("select name from person where age in (select id from employee);")
+            .is_err());
+        assert!(db
+            .execute("select name from person where age = (select age from employee);")
+            .is_err());
+        assert!(db
+            .execute("select name from person where exists (select * from employee where x = 1);")
+            .is_err());
+        assert!(db
+            .execute(
+                "select age from person group by age \
+                 having exists (select * from employee where employee.age = age);"
+            )
+            .is_err());
+
+        // Statements that modify a table cannot evaluate subqueries.
+        for statement in &[
+            "delete from person where name in (select id from employee);",
+            "update person set age = (select max(age) from employee);",
+            "update person set age = 3 where exists (select * from employee);",
+            "delete from person returning name, (select 1 from employee where id = 'b');",
+        ] {
+            let err = db.execute(statement).unwrap_err();
+            assert!(
+                err.to_string().contains("subqueries are not supported in"),
+                "{}",
+                err
+            );
+        }
+        assert_eq!(
+            db.execute("select count(*) from person;").unwrap(),
+            vec![vec![(name("count(*)"), Integer(3))]]
+        );
+    }
+
+    #[test]
+    fn exec_binary() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table blobs (id integer primary key, payload blob);",
+                "insert into blobs (id, payload) values (1, x'DEADBEEF');",
+                "insert into blobs (id, payload) values (2, x'00ff');",
+            ],
+        );
+
+        let res = db
+            .execute("select id, payload from blobs where payload = x'00FF';")
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (AttributeName("id".to_owned()), Integer(2)),
+                (
+                    AttributeName("payload".to_owned()),
+                    StorageTupleValue::Binary(vec![0x00, 0xff]),
+                ),
+            ]]
+        );
+
+        assert!(db
+            .execute("select id from blobs where payload = 'abc';")
+            .is_err());
+        assert!(db
+            .execute("select id from blobs where payload < x'00';")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_character_lengths() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table country (code char(3) primary key, name varchar(8));",
+                "insert into country (code, name) values ('de', 'germany');",
+                "insert into country (code, name) values ('fra', 'france');",
+            ],
+        );
+
+        assert!(db
+            .execute("insert into country (code, name) values ('ita', 'republic of italy');")
+            .is_err());
+        assert!(db
+            .execute("insert into country (code, name) values ('ital', 'italy');")
+            .is_err());
+
+        let res = db
+            .execute("select code, name from country where code = 'de';")
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (
+                    AttributeName("code".to_owned()),
+                    StorageTupleValue::String("de".to_owned()),
+                ),
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String("germany".to_owned()),
+                ),
+            ]]
+        );
+
+        // Trailing spaces are not significant when comparing against a CHAR.
+        let res = db
+            .execute("select name from country where code = 'de  ';")
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![(
+                AttributeName("name".to_owned()),
+                StorageTupleValue::String("germany".to_owned()),
+            )]]
+        );
+        let res = db
+            .execute("select name from country where name = 'france ';")
+            .unwrap();
+        assert!(res.is_empty());
+        let res = db
+            .execute("select name from country where code != 'fra ';")
+            .unwrap();
+        assert_eq!(res.len(), 1);
+    }
+
+    #[test]
+    fn exec_json() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table event (id integer primary key, data json);",
+                r#"insert into event (id, data) values (1, '{"kind": "click", "pos": {"x": 3, "y": [4, 5]}}');"#,
+                r#"insert into event (id, data) values (2, '{"kind": "scroll", "pos": {"x": 7}}');"#,
+            ],
+        );
+
+        assert!(db
+            .execute("insert into event (id, data) values (3, '{\"kind\": ');")
+            .is_err());
+        assert!(db
+            .execute("select data->'kind' from event where data->'kind' = 1;")
+            .is_err());
+
+        let res = db
+            .execute(
+                "select id, data->>'kind', json_extract(data, '$.pos.y[1]') from event \
+                 where data->'pos'->>'x' = '3';",
+            )
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (AttributeName("id".to_owned()), Integer(1)),
+                (
+                    AttributeName("data ->> 'kind'".to_owned()),
+                    StorageTupleValue::String("click".to_owned()),
+                ),
+                (
+                    AttributeName("json_extract(data, '$.pos.y[1]')".to_owned()),
+                    StorageTupleValue::Json(JsonValue::parse("5").unwrap()),
+                ),
+            ]]
+        );
+
+        let res = db
+            .execute("select data->'pos'->'y', data->'pos'->>'y' from event where id = 2;")
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (
+                    AttributeName("data -> 'pos' -> 'y'".to_owned()),
+                    StorageTupleValue::Null,
+                ),
+                (
+                    AttributeName("data -> 'pos' ->> 'y'".to_owned()),
+                    StorageTupleValue::Null,
+                ),
+            ]]
+        );
+
+        execute_and_discard_result(
+            &mut db,
+            vec![r#"insert into event (id, data) values (4, '{"kind": null}');"#],
+        );
+        let res = db
+            .execute("select data->'kind', data->>'kind' from event where id = 4;")
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (
+                    AttributeName("data -> 'kind'".to_owned()),
+                    StorageTupleValue::Json(JsonValue::Null),
+                ),
+                (
+                    AttributeName("data ->> 'kind'".to_owned()),
+                    StorageTupleValue::Null,
+                ),
+            ]]
+        );
+    }
+
+    #[test]
+    fn exec_uuid() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table account (id uuid primary key, name varchar);",
+                "insert into account (id, name) values ('A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A11', 'a');",
+                "insert into account (id, name) values ('c0eebc999c0b4ef8bb6d6bb9bd380a11', 'c');",
+                "insert into account (id, name) values (gen_random_uuid(), 'r');",
+            ],
+        );
+
+        assert!(db
+            .execute("insert into account (id, name) values ('a0eebc99-9c0b', 'x');")
+            .is_err());
+        assert!(db
+            .execute("select name from account where id = 'not a uuid';")
+            .is_err());
+
+        let res = db
+            .execute(
+                "select id, name from account where id = 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11';",
+            )
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (
+                    AttributeName("id".to_owned()),
+                    StorageTupleValue::Uuid(
+                        Uuid::parse("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").unwrap()
+                    ),
+                ),
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String("a".to_owned()),
+                ),
+            ]]
+        );
+        match &res[0][0].1 {
+            StorageTupleValue::Uuid(id) => {
+                assert_eq!(id.to_string(), "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11")
+            }
+            unexpected => panic!("expected a UUID, got {:?}", unexpected),
+        }
+
+        let names = db
+            .execute("select name from account where id > 'b0000000-0000-0000-0000-000000000000';")
+            .unwrap()
+            .into_iter()
+            .map(|tuple| tuple[0].1.clone())
+            .collect::<Vec<_>>();
+        assert!(names.contains(&StorageTupleValue::String("c".to_owned())));
+        assert!(!names.contains(&StorageTupleValue::String("a".to_owned())));
+
+        let res = db
+            .execute("select name from account where name = 'r';")
+            .unwrap();
+        assert_eq!(res.len(), 1);
+    }
+
+    #[test]
+    fn exec_enum() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create type mood as enum ('sad', 'ok', 'happy');",
+                "create table person (name varchar primary key, current mood);",
+                "create table reference (name varchar primary key, threshold mood);",
+                "insert into person (name, current) values ('a', 'happy');",
+                "insert into person (name, current) values ('b', 'sad');",
+                "insert into person (name, current) values ('c', 'ok');",
+                "insert into reference (name, threshold) values ('r', 'ok');",
+            ],
+        );
+
+        assert!(db.execute("create type mood as enum ('x');").is_err());
+        assert!(db.execute("create type dup as enum ('x', 'x');").is_err());
+        assert!(db
+            .execute("create table other (name varchar primary key, m missing);")
+            .is_err());
+        assert!(db
+            .execute("insert into person (name, current) values ('d', 'angry');")
+            .is_err());
+        assert!(db
+            .execute("select name from person where current = 'angry';")
+            .is_err());
+
+        let res = db
+            .execute("select name, current from person where current = 'sad';")
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String("b".to_owned()),
+                ),
+                (
+                    AttributeName("current".to_owned()),
+                    StorageTupleValue::Enum(EnumLabel {
+                        ordinal: 0,
+                        label: "sad".to_owned(),
+                    }),
+                ),
+            ]]
+        );
+
+        // Ordering follows the declaration order of the labels, not their spelling.
+        let res = db
+            .execute(
+                "select p.name from person as p inner join reference as r \
+                 on p.current >= r.threshold;",
+            )
+            .unwrap();
+        assert_tuples(
+            vec![
+                vec![(
+                    AttributeName("p.name".to_owned()),
+                    StorageTupleValue::String("a".to_owned()),
+                )],
+                vec![(
+                    AttributeName("p.name".to_owned()),
+                    StorageTupleValue::String("c".to_owned()),
+                )],
+            ],
+            res,
+        );
+
+        // Labels are ordered by their position too, on either side of the comparison.
+        let names = |db: &mut DB, query: &str| {
+            db.execute(query)
+                .unwrap()
+                .into_iter()
+                .map(|row| row[0].1.clone())
+                .collect::<Vec<_>>()
+        };
+        let string = |text: &str| StorageTupleValue::String(text.to_owned());
+        assert_eq!(
+            names(
+                &mut db,
+                "select name from person where current > 'ok' order by name;"
+            ),
+            vec![string("a")]
+        );
+        assert_eq!(
+            names(
+                &mut db,
+                "select name from person where 'ok' >= current order by name;"
+            ),
+            vec![string("b"), string("c")]
+        );
+        assert_eq!(
+            names(
+                &mut db,
+                "select current < 'happy' as unhappy from person order by name;"
+            ),
+            vec![
+                StorageTupleValue::Boolean(false),
+                StorageTupleValue::Boolean(true),
+                StorageTupleValue::Boolean(true),
+            ]
+        );
+        assert!(db
+            .execute("select name from person where current < 'angry';")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_update() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create type status as enum ('active', 'suspended');",
+                "create table account (id integer primary key, name varchar(5), balance integer, state status);",
+                "insert into account (id, name, balance, state) values (1, 'a', 10, 'active');",
+                "insert into account (id, name, balance, state) values (2, 'b', 20, 'active');",
+                "insert into account (id, name, balance, state) values (3, 'c', 30, 'active');",
+            ],
+        );
+
+        let count = |n| {
+            vec![vec![(
+                AttributeName("count".to_owned()),
+                StorageTupleValue::Integer(n),
+            )]]
+        };
+        assert_eq!(
+            db.execute(
+                "update account set balance = balance * 2, state = 'suspended' where balance > 15;"
+            )
+            .unwrap(),
+            count(2)
+        );
+        assert_eq!(
+            db.execute("update account set name = 'x' where id = 4;")
+                .unwrap(),
+            count(0)
+        );
+
+        assert!(db.execute("update account set missing = 1;").is_err());
+        assert!(db.execute("update account set balance = 'a';").is_err());
+        assert!(db
+            .execute("update account set balance = 1, balance = 2;")
+            .is_err());
+        assert!(db.execute("update account set state = 'closed';").is_err());
+        assert!(db
+            .execute("update account set balance = 1 where id;")
+            .is_err());
+        assert!(db.execute("update account set name = 'toolong';").is_err());
+        // Arithmetic that overflows or divides by zero fails the whole statement.
+        let err = db
+            .execute("update account set balance = balance / (id - 1);")
+            .unwrap_err();
+        assert!(err.to_string().contains("division by zero"), "{}", err);
+        let err = db
+            .execute("update account set balance = balance * 2147483647;")
+            .unwrap_err();
+        assert!(err.to_string().contains("integer out of range"), "{}", err);
+        assert!(db
+            .execute("select id from account where 2147483647 + id > 0;")
+            .is_err());
+
+        let res = db
+            .execute("select id, balance, state from account;")
+            .unwrap();
+        let state = |label: &str, ordinal| {
+            (
+                AttributeName("state".to_owned()),
+                StorageTupleValue::Enum(EnumLabel {
+                    ordinal,
+                    label: label.to_owned(),
+                }),
+            )
+        };
+        assert_tuples(
+            vec![
+                vec![
+                    (AttributeName("id".to_owned()), Integer(1)),
+                    (AttributeName("balance".to_owned()), Integer(10)),
+                    state("active", 0),
+                ],
+                vec![
+                    (AttributeName("id".to_owned()), Integer(2)),
+                    (AttributeName("balance".to_owned()), Integer(40)),
+                    state("suspended", 1),
+                ],
+                vec![
+                    (AttributeName("id".to_owned()), Integer(3)),
+                    (AttributeName("balance".to_owned()), Integer(60)),
+                    state("suspended", 1),
+                ],
+            ],
+            res,
+        );
+    }
+
+    #[test]
+    fn exec_update_primary_key() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table p (id integer primary key, name varchar);",
+                "insert into p (id, name) values (1, 'a');",
+                "insert into p (id, name) values (2, 'b');",
+                "insert into p (id, name) values (3, 'c');",
+            ],
+        );
+
+        // A new key can neither be taken by a row left as it is nor by another updated row.
+        for statement in &[
+            "update p set id = 2 where id = 1;",
+            "update p set id = 5 where id > 1;",
+        ] {
+            let err = db.execute(statement).unwrap_err();
+            assert!(err.to_string().contains("already exists"), "{}", err);
+        }
+        // Keys are only checked once all rows are updated, so they can be shifted.
+        db.execute("update p set id = id + 1;").unwrap();
+        // Inserts see the shifted keys.
+        assert!(db
+            .execute("insert into p (id, name) values (4, 'd');")
+            .is_err());
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "insert into p (id, name) values (1, 'd');",
+                "delete from p where id = 1;",
+            ],
+        );
+
+        let res = db.execute("select id, name from p;").unwrap();
+        let row = |id, name: &str| {
+            vec![
+                (AttributeName("id".to_owned()), Integer(id)),
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String(name.to_owned()),
+                ),
+            ]
+        };
+        assert_tuples(vec![row(2, "a"), row(3, "b"), row(4, "c")], res);
+    }
+
+    #[test]
+    fn exec_delete() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar primary key, age integer);",
+                "insert into person (name, age) values ('a', 10);",
+                "insert into person (name, age) values ('b', 20);",
+                "insert into person (name, age) values ('c', 30);",
+            ],
+        );
+
+        assert!(db.execute("delete from missing;").is_err());
+        assert!(db.execute("delete from person where age;").is_err());
+        assert_eq!(
+            db.execute("delete from person where age < 25;").unwrap(),
+            vec![vec![(AttributeName("count".to_owned()), Integer(2))]]
+        );
+        execute_and_discard_result(
+            &mut db,
+            vec!["insert into person (name, age) values ('d', 40);"],
+        );
+
+        let res = db.execute("select name, age from person;").unwrap();
+        assert_tuples(
+            vec![
+                vec![
+                    (
+                        AttributeName("name".to_owned()),
+                        StorageTupleValue::String("c".to_owned()),
+                    ),
+                    (AttributeName("age".to_owned()), Integer(30)),
+                ],
+                vec![
+                    (
+                        AttributeName("name".to_owned()),
+                        StorageTupleValue::String("d".to_owned()),
+                    ),
+                    (AttributeName("age".to_owned()), Integer(40)),
+                ],
+            ],
+            res,
+        );
+
+        assert_eq!(
+            db.execute("delete from person;").unwrap(),
+            vec![vec![(AttributeName("count".to_owned()), Integer(2))]]
+        );
+        assert!(db.execute("select name from person;").unwrap().is_empty());
+    }
+
+    #[test]
+    fn exec_multi_row_insert() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, age integer);",
+                "insert into person (age, name) values (10, 'a'), (20, 'b'), (30, 'c');",
+            ],
+        );
+
+        // A single invalid row rejects the whole statement.
+        assert!(db
+            .execute("insert into person (name, age) values ('d', 40), ('toolong', 50);")
+            .is_err());
+        assert!(db
+            .execute("insert into person (name, age) values ('d', 40), ('e');")
+            .is_err());
+        assert!(db
+            .execute("insert into person (name) values ('d');")
+            .is_err());
+
+        let res = db.execute("select name, age from person;").unwrap();
+        let row = |name: &str, age| {
+            vec![
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String(name.to_owned()),
+                ),
+                (AttributeName("age".to_owned()), Integer(age)),
+            ]
+        };
+        assert_tuples(vec![row("a", 10), row("b", 20), row("c", 30)], res);
+    }
+
+    #[test]
+    fn exec_upsert() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, age integer);",
+                "insert into person (name, age) values ('a', 10), ('b', 20);",
+            ],
+        );
+
+        // Without ON CONFLICT a duplicate key rejects the whole statement.
+        assert!(db
+            .execute("insert into person (name, age) values ('c', 30), ('a', 11);")
+            .is_err());
+        assert!(db
+            .execute("insert into person (name, age) values ('c', 30), ('c', 31);")
+            .is_err());
+        // Only the primary key can be a conflict target.
+        assert!(db
+            .execute("insert into person (name, age) values ('a', 1) on conflict (age) do nothing;")
+            .is_err());
+
+        // DO UPDATE cannot affect a row twice, whether it is stored or inserted by the statement.
+        let err = db
+            .execute(
+                "insert into person (name, age) values ('d', 40), ('d', 2) \
+                 on conflict (name) do update set age = age + excluded.age;",
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("command cannot affect row a second time"),
+            "{}",
+            err
+        );
+        assert!(db
+            .execute(
+                "insert into person (name, age) values ('b', 1), ('b', 2) \
+                 on conflict (name) do update set age = age + excluded.age;",
+            )
+            .is_err());
+
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "insert into person (name, age) values ('a', 1), ('c', 30), ('c', 31) \
+                 on conflict do nothing;",
+                "insert into person (name, age) values ('b', 1), ('d', 42) \
+                 on conflict (name) do update set age = age + excluded.age;",
+            ],
+        );
+
+        let res = db.execute("select name, age from person;").unwrap();
+        let row = |name: &str, age| {
+            vec![
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String(name.to_owned()),
+                ),
+                (AttributeName("age".to_owned()), Integer(age)),
+            ]
+        };
+        assert_tuples(
+            vec![row("a", 10), row("b", 21), row("c", 30), row("d", 42)],
+            res,
+        );
+
+        // CONFLICT and NOTHING are only keywords in an ON CONFLICT clause.
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table alias (conflict varchar(5) primary key, nothing integer);",
+                "insert into alias (conflict, nothing) values ('a', 1) on conflict do nothing;",
+            ],
+        );
+        let res = db
+            .execute(
+                "select name, nothing from person inner join alias on conflict = name \
+                 where nothing = 1;",
+            )
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String("a".to_owned()),
+                ),
+                (AttributeName("nothing".to_owned()), Integer(1)),
+            ]]
+        );
+    }
+
+    #[test]
+    fn exec_returning() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec!["create table person (id uuid primary key, name varchar(5), age integer);"],
+        );
+        let name = |name: &str| {
+            (
+                AttributeName("name".to_owned()),
+                StorageTupleValue::String(name.to_owned()),
+            )
+        };
+        let age = |age| (AttributeName("age".to_owned()), Integer(age));
+
+        // Generated values can be read back without a second query.
+        let res = db
+            .execute(
+                "insert into person (id, name, age) \
+                 values (gen_random_uuid(), 'a', 10), (gen_random_uuid(), 'b', 20) \
+                 returning id, name;",
+            )
+            .unwrap();
+        assert_eq!(res.len(), 2);
+        let ids = res
+            .iter()
+            .map(|row| match &row[0] {
+                (attr_name, StorageTupleValue::Uuid(id)) if attr_name.0 == "id" => *id,
+                other => panic!("unexpected value {:?}", other),
+            })
+            .collect::<Vec<_>>();
+        assert_ne!(ids[0], ids[1]);
+
+        let res = db
+            .execute("update person set age = age + 1 where age > 15 returning name, age * 2;")
+            .unwrap();
+        assert_tuples(
+            vec![vec![
+                name("b"),
+                (AttributeName("age * 2".to_owned()), Integer(42)),
+            ]],
+            res,
+        );
+
+        let res = db
+            .execute("delete from person where name = 'a' returning *;")
+            .unwrap();
+        assert_tuples(
+            vec![vec![
+                (
+                    AttributeName("id".to_owned()),
+                    StorageTupleValue::Uuid(ids[0]),
+                ),
+                name("a"),
+                age(10),
+            ]],
+            res,
+        );
+
+        // Nothing is returned for rows that were not affected.
+        let res = db
+            .execute("delete from person where name = 'a' returning name;")
+            .unwrap();
+        assert!(res.is_empty());
+
+        assert!(db
+            .execute("update person set age = 1 returning missing;")
+            .is_err());
+
+        let res = db.execute("select name, age from person;").unwrap();
+        assert_tuples(vec![vec![name("b"), age(21)]], res);
+    }
+
+    #[test]
+    fn exec_truncate() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, age integer);",
+                "create table pet (name varchar(5) primary key, owner varchar(5));",
+                "insert into person (name, age) values ('a', 10), ('b', 20);",
+                "insert into pet (name, owner) values ('x', 'a');",
+            ],
+        );
+
+        // Nothing is truncated when one of the tables does not exist.
+        assert!(db.execute("truncate person, missing;").is_err());
+        assert_eq!(db.execute("select name from person;").unwrap().len(), 2);
+
+        execute_and_discard_result(
+            &mut db,
+            vec!["truncate table person, pet restart identity;"],
+        );
+        assert!(db.execute("select name from person;").unwrap().is_empty());
+        assert!(db.execute("select name from pet;").unwrap().is_empty());
+
+        // The schema is kept, so the tables can be filled again.
+        execute_and_discard_result(
+            &mut db,
+            vec!["insert into person (name, age) values ('a', 30);"],
+        );
+        let res = db.execute("select name, age from person;").unwrap();
+        assert_tuples(
+            vec![vec![
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String("a".to_owned()),
+                ),
+                (AttributeName("age".to_owned()), Integer(30)),
+            ]],
+            res,
+        );
+    }
+
+    #[test]
+    fn exec_merge() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table account (id integer primary key, name varchar(5), balance integer);",
+                "create table staging (id integer primary key, name varchar(5), balance integer, closed integer);",
+                "insert into account (id, name, balance) values (1, 'a', 10), (2, 'b', 20), (3, 'c', 30);",
+                "insert into staging (id, name, balance, closed) \
+                 values (2, 'b', 25, 0), (3, 'c', 0, 1), (4, 'd', 40, 0), (5, 'e', 50, 1);",
+            ],
+        );
+        let account = |id, name: &str, balance| {
+            vec![
+                (AttributeName("id".to_owned()), Integer(id)),
+                (
+                    AttributeName("name".to_owned()),
+                    StorageTupleValue::String(name.to_owned()),
+                ),
+                (AttributeName("balance".to_owned()), Integer(balance)),
+            ]
+        };
+
+        // A target row can only be affected by a single source row.
+        assert!(db
+            .execute(
+                "merge into account as t using staging as s on t.id < s.id \
+                 when matched then update set balance = 0;"
+            )
+            .is_err());
+        // Inserted rows must not reuse a primary key.
+        assert!(db
+            .execute(
+                "merge into account as t using staging as s on t.id = 100 \
+                 when not matched then insert values (1, s.name, s.balance);"
+            )
+            .is_err());
+        assert!(db
+            .execute(
+                "merge into account as t using staging as s on t.id = s.id \
+                 when matched then update set id = s.id;"
+            )
+            .is_err());
+        assert!(db
+            .execute(
+                "merge into account as t using staging as s on id = s.id \
+                 when matched then delete;"
+            )
+            .is_err());
+        assert!(db
+            .execute(
+                "merge into account as t using staging as s on t.id = s.id \
+                 when not matched then insert (id, name) values (s.id, s.name);"
+            )
+            .is_err());
+        let res = db
+            .execute("select id, name, balance from account;")
+            .unwrap();
+        assert_tuples(
+            vec![
+                account(1, "a", 10),
+                account(2, "b", 20),
+                account(3, "c", 30),
+            ],
+            res,
+        );
+
+        let res = db
+            .execute(
+                "merge into account as t using (select * from staging) as s on t.id = s.id \
+                 when matched and s.closed = 1 then delete \
+                 when matched then update set balance = t.balance + s.balance \
+                 when not matched and s.closed = 0 then \
+                   insert (id, name, balance) values (s.id, s.name, s.balance) \
+                 when not matched then do nothing;",
+            )
+            .unwrap();
+        assert_eq!(
+            res,
+            vec![vec![
+                (AttributeName("inserted".to_owned()), Integer(1)),
+                (AttributeName("updated".to_owned()), Integer(1)),
+                (AttributeName("deleted".to_owned()), Integer(1)),
+            ]]
+        );
+
+        let res = db
+            .execute("select id, name, balance from account;")
+            .unwrap();
+        assert_tuples(
+            vec![
+                account(1, "a", 10),
+                account(2, "b", 45),
+                account(4, "d", 40),
+            ],
+            res,
+        );
+    }
+
+    #[test]
+    fn exec_boolean_connectives() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, age integer);",
+                "insert into person (name, age) values ('a', 0), ('b', 2), ('c', 5), ('d', 8);",
+            ],
+        );
+        let names = |res: Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            let mut names = res
+                .into_iter()
+                .map(|row| match &row[0].1 {
+                    StorageTupleValue::String(name) => name.clone(),
+                    other => panic!("unexpected value {:?}", other),
+                })
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        let res = db
+            .execute("select name from person where age > 1 and age < 6;")
+            .unwrap();
+        assert_eq!(names(res), vec!["b", "c"]);
+
+        let res = db
+            .execute("select name from person where age = 0 or not (age < 8);")
+            .unwrap();
+        assert_eq!(names(res), vec!["a", "d"]);
+
+        // The right operand is not evaluated for rows decided by the left one.
+        let res = db
+            .execute("select name from person where age != 0 and 10 / age >= 1;")
+            .unwrap();
+        assert_eq!(names(res), vec!["b", "c", "d"]);
+        let res = db
+            .execute("select name from person where age = 0 or 10 / age = 5;")
+            .unwrap();
+        assert_eq!(names(res), vec!["a", "b"]);
+
+        assert!(db
+            .execute("select name from person where age and true;")
+            .is_err());
+        assert!(db
+            .execute("select name from person where not age;")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_negative_numbers() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table account (id integer primary key, balance integer);",
+                "insert into account (id, balance) values (1, -5), (2, 3), (-2147483648, -(-7));",
+            ],
+        );
+        let ids = |res: Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            let mut ids = res
+                .into_iter()
+                .map(|row| match &row[0].1 {
+                    StorageTupleValue::Integer(id) => *id,
+                    other => panic!("unexpected value {:?}", other),
+                })
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+
+        let res = db
+            .execute("select id from account where balance > -1;")
+            .unwrap();
+        assert_eq!(ids(res), vec![i32::MIN, 2]);
+        let res = db
+            .execute("select id from account where -balance = 5;")
+            .unwrap();
+        assert_eq!(ids(res), vec![1]);
+        let res = db
+            .execute("select id from account where id = -2147483648;")
+            .unwrap();
+        assert_eq!(ids(res), vec![i32::MIN]);
+
+        assert!(db
+            .execute("insert into account (id, balance) values (2147483648, 0);")
+            .is_err());
+        assert!(db
+            .execute("select id from account where -(balance > 0);")
+            .is_err());
+        assert!(db
+            .execute("select id from account where -'a' = 'a';")
+            .is_err());
+        let err = db
+            .execute("select -id from account where id < 0;")
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("integer 2147483648 is out of range"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn exec_order_by() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, age integer, data json);",
+                "insert into person (name, age, data) values ('a', 3, '{\"k\": null}'), \
+                 ('b', 1, '{\"k\": 2}'), ('c', 3, '{\"k\": 1}'), ('d', 2, '{}'), \
+                 ('e', 5, '{\"k\": 3}');",
+            ],
+        );
+        let names = |res: Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            res.into_iter()
+                .map(|row| match &row[0].1 {
+                    StorageTupleValue::String(name) => name.clone(),
+                    other => panic!("unexpected value {:?}", other),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let res = db
+            .execute("select name from person order by age, name desc;")
+            .unwrap();
+        assert_eq!(names(res), vec!["b", "d", "c", "a", "e"]);
+
+        // Sorted by an attribute that is not projected.
+        let res = db
+            .execute("select name from person where age < 5 order by -age asc, name;")
+            .unwrap();
+        assert_eq!(names(res), vec!["a", "c", "d", "b"]);
+
+        // The missing member of d is NULL, the JSON null of a sorts before the numbers.
+        let res = db
+            .execute("select * from person order by data->'k', name;")
+            .unwrap();
+        assert_eq!(names(res), vec!["a", "c", "b", "e", "d"]);
+        let res = db
+            .execute("select * from person order by data->'k' desc, name;")
+            .unwrap();
+        assert_eq!(names(res), vec!["d", "e", "b", "c", "a"]);
+        let res = db
+            .execute("select name, data from person order by data->'k' nulls first, name;")
+            .unwrap();
+        assert_eq!(names(res), vec!["d", "a", "c", "b", "e"]);
+        let res = db
+            .execute("select name from person order by data->'k' desc nulls last, name;")
+            .unwrap();
+        assert_eq!(names(res), vec!["e", "b", "c", "a", "d"]);
+
+        // An integer is the position of an expression of the select list.
+        let res = db
+            .execute("select name, age from person order by 2 desc, 1;")
+            .unwrap();
+        assert_eq!(names(res), vec!["e", "a", "c", "d", "b"]);
+        let res = db
+            .execute("select * from person order by 2, name;")
+            .unwrap();
+        assert_eq!(names(res), vec!["b", "d", "a", "c", "e"]);
+        let res = db
+            .execute("select name, -age from person where age < 5 order by 2, age, name;")
+            .unwrap();
+        assert_eq!(names(res), vec!["a", "c", "d", "b"]);
+        for statement in &[
+            "select name, age from person order by 0;",
+            "select name, age from person order by 3;",
+        ] {
+            let err = db.execute(statement).unwrap_err();
+            assert!(err.to_string().contains("is not in select list"), "{}", err);
+        }
+
+        let res = db
+            .execute("select * from (select name, age from person order by age desc, name) as p;")
+            .unwrap();
+        assert_eq!(names(res), vec!["e", "a", "c", "d", "b"]);
+
+        assert!(db
+            .execute("select name from person order by missing;")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_limit() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, age integer);",
+                "insert into person (name, age) values ('a', 1), ('b', 2), ('c', 3), ('d', 4);",
+                "create table pet (owner varchar(5) primary key, kind varchar(5));",
+                "insert into pet (owner, kind) values ('a', 'cat'), ('c', 'dog'), ('d', 'cat');",
+            ],
+        );
+        let names = |res: Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            res.into_iter()
+                .map(|row| match &row[0].1 {
+                    StorageTupleValue::String(name) => name.clone(),
+                    other => panic!("unexpected value {:?}", other),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let res = db
+            .execute("select name from person order by age desc limit 2;")
+            .unwrap();
+        assert_eq!(names(res), vec!["d", "c"]);
+        let res = db
+            .execute("select * from person order by age limit 2 offset 1;")
+            .unwrap();
+        assert_eq!(names(res), vec!["b", "c"]);
+        let res = db
+            .execute("select name from person order by name offset 3;")
+            .unwrap();
+        assert_eq!(names(res), vec!["d"]);
+        let res = db
+            .execute(
+                "select name from person where age > 1 order by name \
+                 offset 1 rows fetch first 1 row only;",
+            )
+            .unwrap();
+        assert_eq!(names(res), vec!["c"]);
+        let res = db.execute("select name from person limit 0;").unwrap();
+        assert_eq!(names(res), Vec::<String>::new());
+        let res = db
+            .execute("select name from person order by name offset 10;")
+            .unwrap();
+        assert_eq!(names(res), Vec::<String>::new());
+
+        let res = db
+            .execute(
+                "select person.name, pet.kind from person as person \
+                 inner join pet as pet on person.name = pet.owner \
+                 order by person.name desc limit 2;",
+            )
+            .unwrap();
+        assert_eq!(names(res), vec!["d", "c"]);
+
+        let res = db
+            .execute("select * from (select name from person order by name limit 3) as p;")
+            .unwrap();
+        assert_eq!(names(res), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn exec_distinct() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, city varchar(5), age integer);",
+                "insert into person (name, city, age) values ('a', 'x', 30), ('b', 'y', 20), \
+                 ('c', 'x', 25), ('d', 'z', 20), ('e', 'y', 40);",
+            ],
+        );
+        let values = |res: Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            res.into_iter()
+                .map(|row| row.into_iter().map(|(_, value)| value).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+
+        let res = db
+            .execute("select distinct city from person order by city;")
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![vec![text("x")], vec![text("y")], vec![text("z")]]
+        );
+
+        let res = db
+            .execute("select distinct age from person order by age desc limit 2;")
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![
+                vec![StorageTupleValue::Integer(40)],
+                vec![StorageTupleValue::Integer(30)]
+            ]
+        );
+        let err = db
+            .execute("select distinct city from person order by age;")
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("for SELECT DISTINCT, ORDER BY expressions must appear in select list"),
+            "{}",
+            err
+        );
+
+        // The youngest person of each city.
+        let res = db
+            .execute("select distinct on (city) city, name from person order by city, age;")
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![
+                vec![text("x"), text("c")],
+                vec![text("y"), text("b")],
+                vec![text("z"), text("d")]
+            ]
+        );
+        let res = db
+            .execute("select distinct on (city) name from person order by city, age desc;")
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![vec![text("a")], vec![text("e")], vec![text("d")]]
+        );
+        let res = db
+            .execute("select distinct on (age) * from person order by age, name desc;")
+            .unwrap();
+        assert_eq!(
+            values(res)
+                .into_iter()
+                .map(|row| row[0].clone())
+                .collect::<Vec<_>>(),
+            vec![text("d"), text("c"), text("a"), text("e")]
+        );
+
+        assert!(db
+            .execute("select distinct on (city) name from person order by age;")
+            .is_err());
+        assert!(db
+            .execute("select distinct on (missing) name from person;")
+            .is_err());
+    }
+
+    #[test]
+    fn exec_group_by() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, city varchar(5), age integer);",
+                "insert into person (name, city, age) values ('a', 'x', 30), ('b', 'y', 20), \
+                 ('c', 'x', 25), ('d', 'z', 20), ('e', 'y', 40), ('f', 'x', 41);",
+            ],
+        );
+        let values = |res: Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            res.into_iter()
+                .map(|row| row.into_iter().map(|(_, value)| value).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let int = StorageTupleValue::Integer;
+
+        let res = db
+            .execute(
+                "select city, count(*), sum(age), avg(age), min(name), max(age) from person \
+                 group by city order by city;",
+            )
+            .unwrap();
+        assert_eq!(
+            res[0]
+                .iter()
+                .map(|(name, _)| name.0.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                "city",
+                "count(*)",
+                "sum(age)",
+                "avg(age)",
+                "min(name)",
+                "max(age)"
+            ]
+        );
+        assert_eq!(
+            values(res),
+            vec![
+                vec![text("x"), int(3), int(96), int(32), text("a"), int(41)],
+                vec![text("y"), int(2), int(60), int(30), text("b"), int(40)],
+                vec![text("z"), int(1), int(20), int(20), text("d"), int(20)]
+            ]
+        );
+
+        let res = db
+            .execute(
+                "select city, max(age) - min(age) from person group by city \
+                 having count(*) > 1 order by max(age) - min(age) desc;",
+            )
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![vec![text("y"), int(20)], vec![text("x"), int(16)]]
+        );
+
+        let res = db
+            .execute(
+                "select age / 10 * 10, count(*) from person group by age / 10 * 10 \
+                 order by count(*) desc, age / 10 * 10;",
+            )
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![
+                vec![int(20), int(3)],
+                vec![int(40), int(2)],
+                vec![int(30), int(1)]
+            ]
+        );
+
+        let res = db
+            .execute("select count(*), sum(age) from person;")
+            .unwrap();
+        assert_eq!(values(res), vec![vec![int(6), int(176)]]);
+        let res = db
+            .execute("select count(*) from person where age > 100;")
+            .unwrap();
+        assert_eq!(values(res), vec![vec![int(0)]]);
+        // Only COUNT has a value when there is nothing to aggregate.
+        let res = db
+            .execute(
+                "select count(age), sum(age), avg(age), min(name), max(age) from person \
+                 where age > 100;",
+            )
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![vec![
+                int(0),
+                StorageTupleValue::Null,
+                StorageTupleValue::Null,
+                StorageTupleValue::Null,
+                StorageTupleValue::Null,
+            ]]
+        );
+        let res = db
+            .execute("select max(age) from person where false;")
+            .unwrap();
+        assert_eq!(values(res), vec![vec![StorageTupleValue::Null]]);
+        let res = db
+            .execute(
+                "select name from person \
+                 where age > (select min(age) from person where city = 'w');",
+            )
+            .unwrap();
+        assert!(res.is_empty());
+
+        assert!(db
+            .execute("select city, age from person group by city;")
+            .is_err());
+        assert!(db
+            .execute("select name from person where count(*) > 1;")
+            .is_err());
+        assert!(db
+            .execute("select city from person group by city having sum(age);")
+            .is_err());
+        assert!(db.execute("select sum(name) from person;").is_err());
+    }
+
+    #[test]
+    fn exec_select_exprs() {
+        let mut db = DB::new();
+        execute_and_discard_result(
+            &mut db,
+            vec![
+                "create table person (name varchar(5) primary key, city varchar(5), age integer);",
+                "insert into person (name, city, age) values ('a', 'x', 30), ('b', 'y', 20), \
+                 ('c', 'x', 25);",
+            ],
+        );
+        let names = |res: &Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            res[0]
+                .iter()
+                .map(|(name, _)| name.0.clone())
+                .collect::<Vec<_>>()
+        };
+        let values = |res: Vec<Vec<(AttributeName, StorageTupleValue)>>| {
+            res.into_iter()
+                .map(|row| row.into_iter().map(|(_, value)| value).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let int = StorageTupleValue::Integer;
+
+        let res = db
+            .execute("select age + 1 as next_age, name, age * 2 from person order by next_age;")
+            .unwrap();
+        assert_eq!(names(&res), vec!["next_age", "name", "age * 2"]);
+        assert_eq!(
+            values(res),
+            vec![
+                vec![int(21), text("b"), int(40)],
+                vec![int(26), text("c"), int(50)],
+                vec![int(31), text("a"), int(60)]
+            ]
+        );
+
+        let res = db
+            .execute("select name as n, age > 21 as adult from person order by n desc;")
+            .unwrap();
+        assert_eq!(names(&res), vec!["n", "adult"]);
+        assert_eq!(
+            values(res),
+            vec![
+                vec![text("c"), StorageTupleValue::Boolean(true)],
+                vec![text("b"), StorageTupleValue::Boolean(false)],
+                vec![text("a"), StorageTupleValue::Boolean(true)]
+            ]
+        );
+
+        let res = db
+            .execute(
+                "select city as c, count(*) as people from person group by city \
+                 order by people desc;",
+            )
+            .unwrap();
+        assert_eq!(names(&res), vec!["c", "people"]);
+        assert_eq!(
+            values(res),
+            vec![vec![text("x"), int(2)], vec![text("y"), int(1)]]
+        );
+
+        let res = db
+            .execute("update person set age = age + 1 where name = 'a' returning age as new_age;")
+            .unwrap();
+        assert_eq!(names(&res), vec!["new_age"]);
+
+        assert!(db.execute("select age + name as x from person;").is_err());
+        assert!(db.execute("select age as from person;").is_err());
+    }
 }
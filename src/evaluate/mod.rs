@@ -1,23 +1,33 @@
 mod db;
 
 use crate::execution::{
-    CreateTableOperation, EmptyResult, Engine, FilterOperation, InnerJoinOperation,
-    InsertTupleOperation, NextTuple, Operation, ProjectOperation, ScanOperation, SubQueryTuples,
-    TupleResult,
+    AffectedTuplesResult, AggregateOperation, CreateSubquery, CreateTableOperation,
+    CreateTypeOperation, DeleteOperation, DistinctOperation, EmptyResult, Engine, FilterOperation,
+    InsertTupleOperation, JoinOperation, LimitOperation, MergeOperation, MergeResult, NextTuple,
+    Operation, ProjectOperation, RowCountResult, ScanOperation, SetOperation, SortOperation,
+    SubQueryTuples, SubqueryOperation, TruncateOperation, TupleResult, UpdateOperation,
 };
 use crate::planner::optimizer::{
-    CreateTableExecutionPlan, InsertTupleExecutionPlan, QueryExecutionPlan,
+    CreateTableExecutionPlan, CreateTypeExecutionPlan, DeleteExecutionPlan,
+    InsertTupleExecutionPlan, MergeExecutionPlan, QueryExecutionPlan, TruncateExecutionPlan,
+    UpdateExecutionPlan,
 };
 use crate::planner::plan::query_plan::QueryPlanNode::Project;
 use crate::planner::plan::query_plan::{
-    FilterNode, JoinNode, ProjectNode, QueryPlan, QueryPlanNode, QueryResultSchema, ScanNode,
+    AggregateNode, DistinctNode, FilterNode, JoinNode, LimitNode, ProjectNode, QueryPlan,
+    QueryPlanNode, QueryResultSchema, ReturningClause, ScanNode, SetOperationNode, SortNode,
+    SubqueryNode,
 };
 use crate::planner::ExecutionPlan;
 use crate::storage::error::{Result as StorageResult, StorageError};
-use crate::storage::storage_manager::{AttributeName, Schema, StorageManager};
+use crate::storage::storage_manager::{
+    AttributeName, Attributes, Schema, StorageManager, TableName,
+};
 use crate::storage::tuple::TupleRecord;
-use crate::storage::tuple_serde::StorageTupleValue;
-use std::collections::HashMap;
+use crate::storage::tuple_serde::{serialize_tuple, StorageTupleValue};
+use crate::storage::types::AttributeType;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 // Interface between optimizer and execution engine
 pub(crate) struct Evaluation<'storage> {
@@ -62,6 +72,91 @@ impl From<EmptyResult> for EvaluationResult {
     }
 }
 
+// Statements that modify a table report the number of affected rows as a single `count` row.
+impl From<RowCountResult> for EvaluationResult {
+    fn from(result: RowCountResult) -> Self {
+        struct RowCountIterator {
+            result: Option<RowCountResult>, // Option to only return the result once.
+        }
+        impl NextTuple for RowCountIterator {
+            fn next(&mut self) -> TupleResult {
+                self.result.take().map(|result| {
+                    result.map(|count| {
+                        serialize_tuple(vec![StorageTupleValue::Integer(count as i32)])
+                    })
+                })
+            }
+        }
+
+        EvaluationResult {
+            schema: Some(QueryResultSchema::new(Attributes::new(vec![(
+                AttributeName("count".to_owned()),
+                AttributeType::Integer,
+            )]))),
+            input: Box::new(RowCountIterator {
+                result: Some(result),
+            }),
+        }
+    }
+}
+
+// A MERGE statement reports the rows affected by each kind of action as a single row.
+impl From<MergeResult> for EvaluationResult {
+    fn from(result: MergeResult) -> Self {
+        struct MergeCountsIterator {
+            result: Option<MergeResult>, // Option to only return the result once.
+        }
+        impl NextTuple for MergeCountsIterator {
+            fn next(&mut self) -> TupleResult {
+                self.result.take().map(|result| {
+                    result.map(|counts| {
+                        serialize_tuple(vec![
+                            StorageTupleValue::Integer(counts.inserted as i32),
+                            StorageTupleValue::Integer(counts.updated as i32),
+                            StorageTupleValue::Integer(counts.deleted as i32),
+                        ])
+                    })
+                })
+            }
+        }
+
+        EvaluationResult {
+            schema: Some(QueryResultSchema::new(Attributes::new(
+                ["inserted", "updated", "deleted"]
+                    .iter()
+                    .map(|name| (AttributeName((*name).to_owned()), AttributeType::Integer))
+                    .collect(),
+            ))),
+            input: Box::new(MergeCountsIterator {
+                result: Some(result),
+            }),
+        }
+    }
+}
+
+impl EvaluationResult {
+    // With a `RETURNING` clause the affected records are projected like the rows of a query,
+    // otherwise the statement reports how many rows it affected.
+    fn from_affected_tuples(
+        result: AffectedTuplesResult,
+        record_schema: QueryResultSchema,
+        returning: Option<ReturningClause>,
+    ) -> Self {
+        match (result, returning) {
+            (Ok(tuples), Some(ReturningClause { schema, exprs })) => EvaluationResult {
+                schema: Some(schema.clone()),
+                input: Box::new(ProjectOperation {
+                    schema,
+                    record_schema,
+                    exprs,
+                    input: Box::new(ScanOperation::new(tuples)),
+                }),
+            },
+            (result, _) => EvaluationResult::from(result.map(|tuples| tuples.len())),
+        }
+    }
+}
+
 impl<'storage> Evaluation<'storage> {
     pub fn evaluate(&mut self, plan: ExecutionPlan) -> EvaluationResult {
         let schema = plan.result_schema();
@@ -79,10 +174,84 @@ impl<'storage> Evaluation<'storage> {
 
                 EvaluationResult::from(self.engine.execute_create_table(op))
             }
-            ExecutionPlan::InsertTuple(InsertTupleExecutionPlan { table_name, tuple }) => {
+            ExecutionPlan::CreateType(CreateTypeExecutionPlan { enum_type }) => {
                 EvaluationResult::from(
                     self.engine
-                        .execute_insert_tuple(InsertTupleOperation { table_name, tuple }),
+                        .execute_create_type(CreateTypeOperation { enum_type }),
+                )
+            }
+            ExecutionPlan::InsertTuple(InsertTupleExecutionPlan {
+                table_name,
+                schema,
+                primary_key,
+                tuples,
+                on_conflict,
+                returning,
+            }) => {
+                let result = self.engine.execute_insert_tuple(InsertTupleOperation {
+                    table_name,
+                    schema: schema.clone(),
+                    primary_key,
+                    tuples,
+                    on_conflict,
+                });
+                match returning {
+                    Some(_) => EvaluationResult::from_affected_tuples(result, schema, returning),
+                    None => EvaluationResult::from(result.map(|_| ())),
+                }
+            }
+            ExecutionPlan::Update(UpdateExecutionPlan {
+                table_name,
+                schema,
+                primary_key,
+                assignments,
+                predicate,
+                returning,
+            }) => {
+                let result = self.engine.execute_update(UpdateOperation {
+                    table_name,
+                    schema: schema.clone(),
+                    primary_key,
+                    assignments,
+                    predicate,
+                });
+                EvaluationResult::from_affected_tuples(result, schema, returning)
+            }
+            ExecutionPlan::Delete(DeleteExecutionPlan {
+                table_name,
+                schema,
+                predicate,
+                returning,
+            }) => {
+                let result = self.engine.execute_delete(DeleteOperation {
+                    table_name,
+                    schema: schema.clone(),
+                    predicate,
+                });
+                EvaluationResult::from_affected_tuples(result, schema, returning)
+            }
+            ExecutionPlan::Merge(MergeExecutionPlan {
+                table_name,
+                target_schema,
+                primary_key,
+                source,
+                predicate,
+                when_clauses,
+            }) => {
+                let source = self.create_query_plan(source.result_schema, source.plan);
+                EvaluationResult::from(self.engine.execute_merge(MergeOperation {
+                    table_name,
+                    target_schema,
+                    primary_key,
+                    source,
+                    predicate,
+                    when_clauses,
+                }))
+            }
+            ExecutionPlan::Truncate(TruncateExecutionPlan { table_names }) => {
+                EvaluationResult::from(
+                    self.engine
+                        .execute_truncate(TruncateOperation { table_names }),
                 )
             }
             ExecutionPlan::Query(QueryExecutionPlan { plan }) => {
@@ -96,19 +265,64 @@ impl<'storage> Evaluation<'storage> {
         }
     }
 
-    fn evaluate_scan(&self, node: ScanNode) -> ScanOperation {
-        let tuples = self
-            .engine
-            .storage_manager
-            .get_table_store(&node.table_name)
-            .expect("[scan operation] table storage no longer exists?")
-            .scan()
-            .map(|(_id, record)| record.clone())
+    fn create_query_plan(
+        &mut self,
+        schema: QueryResultSchema,
+        node: QueryPlanNode,
+    ) -> SubQueryTuples {
+        QueryEvaluation::new(self.engine.storage_manager, &node).create_query_plan(schema, node)
+    }
+}
+
+// Creates the operations of a query plan. The tables the plan scans are copied when the
+// operations are created, the operations of a subquery are created again from the copies for
+// each record of the query it is nested in.
+struct QueryEvaluation {
+    tables: Rc<HashMap<TableName, Vec<TupleRecord>>>,
+    // The record of the query a subquery is evaluated for.
+    outer_record: Option<TupleRecord>,
+}
+
+impl QueryEvaluation {
+    fn new(storage_manager: &StorageManager, node: &QueryPlanNode) -> Self {
+        fn scanned_tables<'p>(node: &'p QueryPlanNode, table_names: &mut HashSet<&'p TableName>) {
+            if let QueryPlanNode::Scan(node) = node {
+                table_names.insert(&node.table_name);
+            }
+            for child in node.children() {
+                scanned_tables(&child.plan, table_names);
+            }
+        }
+
+        let mut table_names = HashSet::new();
+        scanned_tables(node, &mut table_names);
+        let tables = table_names
+            .into_iter()
+            .map(|table_name| {
+                let tuples = storage_manager
+                    .get_table_store(table_name)
+                    .expect("[scan operation] table storage no longer exists?")
+                    .scan()
+                    .map(|(_id, record)| record.clone())
+                    .collect();
+                (table_name.clone(), tuples)
+            })
             .collect();
-        ScanOperation::new(tuples)
+        QueryEvaluation {
+            tables: Rc::new(tables),
+            outer_record: None,
+        }
     }
 
-    fn evaluate_filter(&mut self, node: FilterNode) -> FilterOperation {
+    fn evaluate_scan(&self, node: ScanNode) -> ScanOperation {
+        ScanOperation::new(self.tables[&node.table_name].clone())
+    }
+
+    fn evaluate_outer_record(&self) -> ScanOperation {
+        ScanOperation::new(self.outer_record.iter().cloned().collect())
+    }
+
+    fn evaluate_filter(&self, node: FilterNode) -> FilterOperation {
         let FilterNode {
             predicate,
             schema,
@@ -118,28 +332,76 @@ impl<'storage> Evaluation<'storage> {
         FilterOperation::new(predicate, schema, sub_query.tuples)
     }
 
-    fn evaluate_project(&mut self, node: ProjectNode) -> ProjectOperation {
+    fn evaluate_project(&self, node: ProjectNode) -> ProjectOperation {
         let ProjectNode {
+            schema,
             record_schema,
+            exprs,
             child,
-            schema,
         } = node;
         let sub_query = self.create_query_plan(child.result_schema, child.plan);
-        let projected_attributes = schema
-            .attributes
-            .attributes_iter()
-            .map(|(attr_name, _)| attr_name.clone())
-            .collect::<Vec<_>>();
         ProjectOperation {
+            schema,
             record_schema,
-            projected_attributes,
+            exprs,
             input: sub_query.tuples,
         }
     }
 
-    fn evaluate_join(&mut self, node: JoinNode) -> InnerJoinOperation {
+    fn evaluate_sort(&self, node: SortNode) -> SortOperation {
+        let SortNode {
+            record_schema,
+            sort_keys,
+            child,
+            schema: _,
+        } = node;
+        let sub_query = self.create_query_plan(child.result_schema, child.plan);
+        SortOperation::new(record_schema, sort_keys, sub_query.tuples)
+    }
+
+    fn evaluate_distinct(&self, node: DistinctNode) -> DistinctOperation {
+        let DistinctNode {
+            record_schema,
+            on,
+            child,
+            schema: _,
+        } = node;
+        let sub_query = self.create_query_plan(child.result_schema, child.plan);
+        DistinctOperation::new(record_schema, on, sub_query.tuples)
+    }
+
+    fn evaluate_aggregate(&self, node: AggregateNode) -> AggregateOperation {
+        let AggregateNode {
+            schema,
+            record_schema,
+            group_by,
+            aggregates,
+            child,
+        } = node;
+        let sub_query = self.create_query_plan(child.result_schema, child.plan);
+        AggregateOperation::new(
+            schema,
+            record_schema,
+            group_by,
+            aggregates,
+            sub_query.tuples,
+        )
+    }
+
+    fn evaluate_limit(&self, node: LimitNode) -> LimitOperation {
+        let LimitNode {
+            limit,
+            offset,
+            child,
+            schema: _,
+        } = node;
+        let sub_query = self.create_query_plan(child.result_schema, child.plan);
+        LimitOperation::new(limit, offset, sub_query.tuples)
+    }
+
+    fn evaluate_join(&self, node: JoinNode) -> JoinOperation {
         let JoinNode {
-            join_type: _,
+            join_type,
             predicate,
             schema,
             left,
@@ -149,19 +411,66 @@ impl<'storage> Evaluation<'storage> {
         let left = self.create_query_plan(left.result_schema, left.plan);
         let right = self.create_query_plan(right.result_schema, right.plan);
 
-        InnerJoinOperation::new(left, right, predicate)
+        JoinOperation::new(join_type, schema, left, right, predicate)
     }
 
-    fn create_query_plan(
-        &mut self,
-        schema: QueryResultSchema,
-        node: QueryPlanNode,
-    ) -> SubQueryTuples {
+    fn evaluate_set_operation(&self, node: SetOperationNode) -> SetOperation {
+        let SetOperationNode {
+            op,
+            all,
+            schema,
+            left,
+            right,
+        } = node;
+
+        let left = self.create_query_plan(left.result_schema, left.plan);
+        let right = self.create_query_plan(right.result_schema, right.plan);
+
+        SetOperation::new(op, all, schema, left, right)
+    }
+
+    fn evaluate_subquery(&self, node: SubqueryNode) -> SubqueryOperation {
+        let SubqueryNode {
+            schema,
+            kind,
+            correlated,
+            query,
+            child,
+        } = node;
+
+        let sub_query = self.create_query_plan(child.result_schema, child.plan);
+        let tables = self.tables.clone();
+        let create_query: CreateSubquery = Box::new(move |record| {
+            let evaluation = QueryEvaluation {
+                tables: tables.clone(),
+                outer_record: Some(record.clone()),
+            };
+            evaluation.create_query_plan(query.result_schema.clone(), query.plan.clone())
+        });
+
+        SubqueryOperation::new(
+            kind,
+            correlated,
+            schema,
+            sub_query.schema,
+            create_query,
+            sub_query.tuples,
+        )
+    }
+
+    fn create_query_plan(&self, schema: QueryResultSchema, node: QueryPlanNode) -> SubQueryTuples {
         let tuples: Box<dyn NextTuple> = match node {
             QueryPlanNode::Scan(node) => Box::new(self.evaluate_scan(node)),
             QueryPlanNode::Filter(node) => Box::new(self.evaluate_filter(node)),
             QueryPlanNode::Project(node) => Box::new(self.evaluate_project(node)),
+            QueryPlanNode::Sort(node) => Box::new(self.evaluate_sort(node)),
+            QueryPlanNode::Distinct(node) => Box::new(self.evaluate_distinct(node)),
+            QueryPlanNode::Aggregate(node) => Box::new(self.evaluate_aggregate(node)),
+            QueryPlanNode::Limit(node) => Box::new(self.evaluate_limit(node)),
             QueryPlanNode::Join(node) => Box::new(self.evaluate_join(node)),
+            QueryPlanNode::SetOperation(node) => Box::new(self.evaluate_set_operation(node)),
+            QueryPlanNode::Subquery(node) => Box::new(self.evaluate_subquery(node)),
+            QueryPlanNode::OuterRecord(_) => Box::new(self.evaluate_outer_record()),
         };
 
         SubQueryTuples { schema, tuples }
@@ -0,0 +1,303 @@
+use crate::execution::expr_evaluation::evaluate_expr_with_ctx;
+use crate::execution::{NextTuple, ScanOperation, TupleResult};
+use crate::parser::ast::{AggregateCall, AggregateFunction, Expr};
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::error::{Result as StorageResult, StorageError};
+use crate::storage::json::JsonValue;
+use crate::storage::tuple::TupleRecord;
+use crate::storage::tuple_serde::{serialize_key, serialize_record, StorageTupleValue};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+// Groups all of its input the first time it is asked for a tuple, groups are returned in the
+// order their first record came in. Without GROUP BY expressions all records form a single
+// group, which exists even when there are no records.
+pub struct AggregateOperation {
+    pub schema: QueryResultSchema,
+    pub record_schema: QueryResultSchema,
+    pub group_by: Vec<Expr>,
+    pub aggregates: Vec<AggregateCall>,
+    pub input: Box<dyn NextTuple>,
+    grouped: Option<ScanOperation>,
+}
+
+impl NextTuple for AggregateOperation {
+    fn next(&mut self) -> TupleResult {
+        if self.grouped.is_none() {
+            match self.group() {
+                Ok(records) => self.grouped = Some(ScanOperation::new(records)),
+                // The input is consumed, there are no more records after an error.
+                Err(err) => {
+                    self.grouped = Some(ScanOperation::new(vec![]));
+                    return Some(Err(err));
+                }
+            }
+        }
+        self.grouped.as_mut().and_then(|grouped| grouped.next())
+    }
+}
+
+impl AggregateOperation {
+    pub fn new(
+        schema: QueryResultSchema,
+        record_schema: QueryResultSchema,
+        group_by: Vec<Expr>,
+        aggregates: Vec<AggregateCall>,
+        input: Box<dyn NextTuple>,
+    ) -> Self {
+        AggregateOperation {
+            schema,
+            record_schema,
+            group_by,
+            aggregates,
+            input,
+            grouped: None,
+        }
+    }
+
+    fn group(&mut self) -> StorageResult<Vec<TupleRecord>> {
+        let mut groups: Vec<(Vec<StorageTupleValue>, Vec<Accumulator>)> = Vec::new();
+        let mut group_by_key = HashMap::new();
+        if self.group_by.is_empty() {
+            groups.push((vec![], self.accumulators()));
+        }
+
+        let types = self.record_schema.attributes.as_lookup_table();
+        while let Some(record) = self.input.next() {
+            let record = record?;
+            let value_by_attr = record
+                .to_values::<_, HashMap<_, _>>(self.record_schema.attributes.attributes_iter())?;
+            let ctx = value_by_attr
+                .iter()
+                .map(|(attr_name, value)| (&attr_name.0, value))
+                .collect();
+
+            let group_values = self
+                .group_by
+                .iter()
+                .map(|expr| evaluate_expr_with_ctx(expr, &ctx, &types))
+                .collect::<StorageResult<Vec<_>>>()?;
+            let key = serialize_key(group_values.clone());
+            let index = match group_by_key.get(&key) {
+                Some(index) => *index,
+                None if self.group_by.is_empty() => 0,
+                None => {
+                    groups.push((group_values, self.accumulators()));
+                    group_by_key.insert(key, groups.len() - 1);
+                    groups.len() - 1
+                }
+            };
+
+            let (_, accumulators) = &mut groups[index];
+            for (accumulator, call) in accumulators.iter_mut().zip(self.aggregates.iter()) {
+                let value = call
+                    .arg
+                    .as_ref()
+                    .map(|arg| evaluate_expr_with_ctx(arg, &ctx, &types))
+                    .transpose()?;
+                accumulator.add(value);
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(mut values, accumulators)| {
+                for (accumulator, call) in accumulators.into_iter().zip(self.aggregates.iter()) {
+                    values.push(accumulator.finish(call)?);
+                }
+                Ok(serialize_record(values, &self.schema.attributes))
+            })
+            .collect()
+    }
+
+    fn accumulators(&self) -> Vec<Accumulator> {
+        self.aggregates
+            .iter()
+            .map(|call| match call.function {
+                AggregateFunction::Count => Accumulator::Count(0),
+                AggregateFunction::Sum => Accumulator::Sum(None),
+                AggregateFunction::Avg => Accumulator::Avg { sum: 0, count: 0 },
+                AggregateFunction::Min => Accumulator::Min(None),
+                AggregateFunction::Max => Accumulator::Max(None),
+            })
+            .collect()
+    }
+}
+
+// The state of an aggregate over the records of a group seen so far. The aggregates of an
+// expression skip NULL, and JSON null too.
+enum Accumulator {
+    Count(i64),
+    Sum(Option<i64>),
+    Avg { sum: i64, count: i64 },
+    Min(Option<StorageTupleValue>),
+    Max(Option<StorageTupleValue>),
+}
+
+impl Accumulator {
+    // `value` is the evaluated argument of the aggregate, COUNT(*) has none.
+    fn add(&mut self, value: Option<StorageTupleValue>) {
+        if let Some(StorageTupleValue::Null) | Some(StorageTupleValue::Json(JsonValue::Null)) =
+            value
+        {
+            return;
+        }
+        match (self, value) {
+            (Accumulator::Count(count), _) => *count += 1,
+            (Accumulator::Sum(sum), Some(StorageTupleValue::Integer(value))) => {
+                *sum = Some(sum.unwrap_or(0) + i64::from(value))
+            }
+            (Accumulator::Avg { sum, count }, Some(StorageTupleValue::Integer(value))) => {
+                *sum += i64::from(value);
+                *count += 1;
+            }
+            (Accumulator::Min(min), Some(value)) => match min {
+                Some(current) if *current <= value => {}
+                _ => *min = Some(value),
+            },
+            (Accumulator::Max(max), Some(value)) => match max {
+                Some(current) if *current >= value => {}
+                _ => *max = Some(value),
+            },
+            (_, value) => unreachable!("[validation] invalid aggregate argument {:?}", value),
+        }
+    }
+
+    // COUNT of no values is 0, the other aggregates of no values are NULL.
+    fn finish(self, call: &AggregateCall) -> StorageResult<StorageTupleValue> {
+        let name = Expr::Aggregate(call.clone());
+        let integer = |value: i64| {
+            i32::try_from(value)
+                .map(StorageTupleValue::Integer)
+                .map_err(|_| StorageError::InvalidValue(format!("{} is out of range", name)))
+        };
+        match self {
+            Accumulator::Count(count) => integer(count),
+            Accumulator::Sum(Some(sum)) => integer(sum),
+            Accumulator::Avg { sum, count } if count > 0 => integer(sum / count),
+            Accumulator::Min(Some(value)) | Accumulator::Max(Some(value)) => Ok(value),
+            Accumulator::Sum(None)
+            | Accumulator::Avg { .. }
+            | Accumulator::Min(None)
+            | Accumulator::Max(None) => Ok(StorageTupleValue::Null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::execution::aggregate::AggregateOperation;
+    use crate::execution::{NextTuple, ScanOperation};
+    use crate::parser::ast::{AggregateCall, AggregateFunction, Expr, LiteralExpr};
+    use crate::planner::plan::query_plan::QueryResultSchema;
+    use crate::storage::storage_manager::{AttributeName, Attributes};
+    use crate::storage::tuple::TupleRecord;
+    use crate::storage::tuple_serde::{serialize_record, serialize_tuple, StorageTupleValue};
+    use crate::storage::types::AttributeType;
+
+    #[test]
+    fn aggregate() {
+        let schema = QueryResultSchema::new(Attributes::new(vec![
+            (AttributeName("city".to_owned()), AttributeType::Text),
+            (AttributeName("age".to_owned()), AttributeType::Integer),
+        ]));
+        let row = |city: &str, age: i32| {
+            serialize_tuple(vec![
+                StorageTupleValue::String(city.to_owned()),
+                StorageTupleValue::Integer(age),
+            ])
+        };
+        let age = || Box::new(Expr::Literal(LiteralExpr::Identifier("age".to_owned())));
+        let call =
+            |function: AggregateFunction, arg: Option<Box<Expr>>| AggregateCall { function, arg };
+        let aggregates = vec![
+            call(AggregateFunction::Count, None),
+            call(AggregateFunction::Sum, Some(age())),
+            call(AggregateFunction::Avg, Some(age())),
+            call(AggregateFunction::Min, Some(age())),
+            call(AggregateFunction::Max, Some(age())),
+        ];
+        let run = |group_by: Vec<Expr>, aggregates: Vec<AggregateCall>, rows: Vec<TupleRecord>| {
+            let input = ScanOperation::new(rows);
+            let aggregated_schema = QueryResultSchema::new(Attributes::new(
+                group_by
+                    .iter()
+                    .map(|expr| (AttributeName(expr.to_string()), AttributeType::Text))
+                    .chain(aggregates.iter().map(|call| {
+                        let name = Expr::Aggregate(call.clone()).to_string();
+                        (AttributeName(name), AttributeType::Integer.nullable())
+                    }))
+                    .collect(),
+            ));
+            let mut a = AggregateOperation::new(
+                aggregated_schema,
+                schema.clone(),
+                group_by,
+                aggregates,
+                Box::new(input),
+            );
+            let mut items = Vec::new();
+            while let Some(item) = a.next() {
+                items.push(item);
+            }
+            items
+        };
+        let rows = || vec![row("b", 30), row("a", 20), row("b", 10), row("b", 21)];
+        let aggregated = |city: Option<&str>, values: Vec<Option<i32>>| {
+            let city = city.map(|city| StorageTupleValue::String(city.to_owned()));
+            let schema = QueryResultSchema::new(Attributes::new(
+                city.iter()
+                    .map(|_| (AttributeName("city".to_owned()), AttributeType::Text))
+                    .chain(values.iter().enumerate().map(|(position, _)| {
+                        (
+                            AttributeName(position.to_string()),
+                            AttributeType::Integer.nullable(),
+                        )
+                    }))
+                    .collect(),
+            ));
+            Ok(serialize_record(
+                city.into_iter()
+                    .chain(values.into_iter().map(|value| {
+                        value.map_or(StorageTupleValue::Null, StorageTupleValue::Integer)
+                    }))
+                    .collect(),
+                &schema.attributes,
+            ))
+        };
+
+        assert_eq!(
+            run(
+                vec![Expr::Literal(LiteralExpr::Identifier("city".to_owned()))],
+                aggregates.clone(),
+                rows()
+            ),
+            vec![
+                aggregated(
+                    Some("b"),
+                    vec![Some(3), Some(61), Some(20), Some(10), Some(30)]
+                ),
+                aggregated(
+                    Some("a"),
+                    vec![Some(1), Some(20), Some(20), Some(20), Some(20)]
+                ),
+            ]
+        );
+        assert_eq!(
+            run(vec![], aggregates[..2].to_vec(), rows()),
+            vec![aggregated(None, vec![Some(4), Some(81)])]
+        );
+        assert_eq!(
+            run(vec![], aggregates.clone(), vec![]),
+            vec![aggregated(None, vec![Some(0), None, None, None, None])]
+        );
+        assert_eq!(
+            run(
+                vec![Expr::Literal(LiteralExpr::Identifier("city".to_owned()))],
+                aggregates,
+                vec![]
+            ),
+            vec![]
+        );
+    }
+}
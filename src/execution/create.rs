@@ -2,7 +2,7 @@ use crate::execution::EmptyResult;
 use crate::storage::storage_manager::{
     AttributeName, CreateTableRequest, StorageManager, TableName,
 };
-use crate::storage::types::AttributeType;
+use crate::storage::types::{AttributeType, EnumType};
 use std::collections::HashMap;
 
 #[derive(Debug, Eq, PartialEq)]
@@ -22,3 +22,15 @@ impl CreateTableOperation {
         Ok(())
     }
 }
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CreateTypeOperation {
+    pub enum_type: EnumType,
+}
+
+impl CreateTypeOperation {
+    pub fn execute(self, storage_manager: &mut StorageManager) -> EmptyResult {
+        storage_manager.create_type(self.enum_type)?;
+        Ok(())
+    }
+}
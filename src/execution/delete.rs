@@ -0,0 +1,52 @@
+use crate::execution::expr_evaluation::evaluate_predicate_with_ctx;
+use crate::execution::AffectedTuplesResult;
+use crate::parser::ast::Expr;
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::storage_manager::{AttributeName, StorageManager, TableName};
+use crate::storage::tuple_serde::StorageTupleValue;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct DeleteOperation {
+    pub table_name: TableName,
+    pub schema: QueryResultSchema,
+    pub predicate: Option<Expr>,
+}
+
+impl DeleteOperation {
+    pub fn execute(self, storage_manager: &mut StorageManager) -> AffectedTuplesResult {
+        let mut storage = storage_manager
+            .get_table_store(&self.table_name)
+            .expect("[delete plan] table storage no longer exists?");
+
+        let types = self.schema.attributes.as_lookup_table();
+        let mut deleted_ids = Vec::new();
+        for (id, record) in storage.scan() {
+            let matches = match &self.predicate {
+                Some(predicate) => {
+                    let values = record.to_values::<_, Vec<(AttributeName, StorageTupleValue)>>(
+                        self.schema.attributes.attributes_iter(),
+                    )?;
+                    evaluate_predicate_with_ctx(
+                        predicate,
+                        &values
+                            .iter()
+                            .map(|(attr_name, value)| (&attr_name.0, value))
+                            .collect(),
+                        &types,
+                    )?
+                }
+                None => true,
+            };
+            if matches {
+                deleted_ids.push(id.clone());
+            }
+        }
+
+        let mut deleted = Vec::with_capacity(deleted_ids.len());
+        for id in &deleted_ids {
+            deleted.push(storage.delete_tuple(id)?);
+        }
+
+        Ok(deleted)
+    }
+}
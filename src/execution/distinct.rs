@@ -0,0 +1,113 @@
+use crate::execution::expr_evaluation::evaluate_expr_with_ctx;
+use crate::execution::{NextTuple, TupleResult};
+use crate::parser::ast::Expr;
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::error::Result as StorageResult;
+use crate::storage::tuple::TupleRecord;
+use crate::storage::tuple_serde::serialize_key;
+use std::collections::{HashMap, HashSet};
+
+// Returns the first of each set of equal records, values are equal when they are encoded the same.
+pub struct DistinctOperation {
+    pub record_schema: QueryResultSchema,
+    pub on: Option<Vec<Expr>>,
+    pub input: Box<dyn NextTuple>,
+    seen: HashSet<Vec<u8>>,
+}
+
+impl NextTuple for DistinctOperation {
+    fn next(&mut self) -> TupleResult {
+        loop {
+            match self.input.next() {
+                Some(Ok(record)) => match self.key(&record) {
+                    Ok(key) => {
+                        if self.seen.insert(key) {
+                            return Some(Ok(record));
+                        }
+                    }
+                    Err(err) => return Some(Err(err)),
+                },
+                other => return other,
+            }
+        }
+    }
+}
+
+impl DistinctOperation {
+    pub fn new(
+        record_schema: QueryResultSchema,
+        on: Option<Vec<Expr>>,
+        input: Box<dyn NextTuple>,
+    ) -> Self {
+        DistinctOperation {
+            record_schema,
+            on,
+            input,
+            seen: HashSet::new(),
+        }
+    }
+
+    fn key(&self, record: &TupleRecord) -> StorageResult<Vec<u8>> {
+        match &self.on {
+            Some(exprs) => {
+                let value_by_attr = record.to_values::<_, HashMap<_, _>>(
+                    self.record_schema.attributes.attributes_iter(),
+                )?;
+                let ctx = value_by_attr
+                    .iter()
+                    .map(|(attr_name, value)| (&attr_name.0, value))
+                    .collect();
+                let types = self.record_schema.attributes.as_lookup_table();
+                let values = exprs
+                    .iter()
+                    .map(|expr| evaluate_expr_with_ctx(expr, &ctx, &types))
+                    .collect::<StorageResult<_>>()?;
+                Ok(serialize_key(values))
+            }
+            None => Ok(record.0.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::execution::distinct::DistinctOperation;
+    use crate::execution::{NextTuple, ScanOperation};
+    use crate::parser::ast::{Expr, LiteralExpr};
+    use crate::planner::plan::query_plan::QueryResultSchema;
+    use crate::storage::storage_manager::{AttributeName, Attributes};
+    use crate::storage::tuple_serde::{serialize_tuple, StorageTupleValue};
+    use crate::storage::types::AttributeType;
+
+    #[test]
+    fn distinct() {
+        let schema = QueryResultSchema::new(Attributes::new(vec![
+            (AttributeName("name".to_owned()), AttributeType::Text),
+            (AttributeName("age".to_owned()), AttributeType::Integer),
+        ]));
+        let row = |name: &str, age: i32| {
+            serialize_tuple(vec![
+                StorageTupleValue::String(name.to_owned()),
+                StorageTupleValue::Integer(age),
+            ])
+        };
+        let run = |on: Option<Vec<Expr>>| {
+            let input =
+                ScanOperation::new(vec![row("a", 1), row("b", 1), row("a", 1), row("a", 2)]);
+            let mut d = DistinctOperation::new(schema.clone(), on, Box::new(input));
+            let mut items = Vec::new();
+            while let Some(item) = d.next() {
+                items.push(item.unwrap());
+            }
+            items
+        };
+
+        assert_eq!(run(None), vec![row("a", 1), row("b", 1), row("a", 2)]);
+        assert_eq!(
+            run(Some(vec![Expr::Literal(LiteralExpr::Identifier(
+                "age".to_owned()
+            ))])),
+            vec![row("a", 1), row("a", 2)]
+        );
+    }
+}
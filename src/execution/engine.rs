@@ -1,6 +1,7 @@
 use crate::execution::{
-    CreateTableOperation, EmptyResult, FilterOperation, InsertTupleOperation, ProjectOperation,
-    ScanOperation,
+    AffectedTuplesResult, CreateTableOperation, CreateTypeOperation, DeleteOperation, EmptyResult,
+    FilterOperation, InsertTupleOperation, MergeOperation, MergeResult, ProjectOperation,
+    ScanOperation, TruncateOperation, UpdateOperation,
 };
 use crate::storage::error::Result as StorageResult;
 use crate::storage::storage_manager::StorageManager;
@@ -23,7 +24,27 @@ impl<'storage> Engine<'storage> {
         op.execute(self.storage_manager)
     }
 
-    pub fn execute_insert_tuple(&mut self, op: InsertTupleOperation) -> EmptyResult {
+    pub fn execute_create_type(&mut self, op: CreateTypeOperation) -> EmptyResult {
+        op.execute(self.storage_manager)
+    }
+
+    pub fn execute_insert_tuple(&mut self, op: InsertTupleOperation) -> AffectedTuplesResult {
+        op.execute(self.storage_manager)
+    }
+
+    pub fn execute_update(&mut self, op: UpdateOperation) -> AffectedTuplesResult {
+        op.execute(self.storage_manager)
+    }
+
+    pub fn execute_merge(&mut self, op: MergeOperation) -> MergeResult {
+        op.execute(self.storage_manager)
+    }
+
+    pub fn execute_truncate(&mut self, op: TruncateOperation) -> EmptyResult {
+        op.execute(self.storage_manager)
+    }
+
+    pub fn execute_delete(&mut self, op: DeleteOperation) -> AffectedTuplesResult {
         op.execute(self.storage_manager)
     }
 }
@@ -1,89 +1,267 @@
-use crate::parser::ast::{BinaryOperation, Expr, LiteralExpr};
+use crate::parser::ast::{BinaryOperation, Expr, FunctionCall, LiteralExpr, UnaryOperation};
+use crate::storage::error::{Result as StorageResult, StorageError};
+use crate::storage::json::JsonValue;
 use crate::storage::tuple_serde::StorageTupleValue;
+use crate::storage::types::{AttributeType, EnumLabel};
+use crate::storage::uuid::Uuid as UuidValue;
 use std::collections::HashMap;
 
+// A predicate that evaluates to NULL does not hold.
 pub fn evaluate_predicate_with_ctx(
     predicate: &Expr,
     ctx: &HashMap<&String, &StorageTupleValue>,
-) -> bool {
-    fn eval<'a>(
-        attr: &String,
-        ctx: &'a HashMap<&String, &StorageTupleValue>,
-    ) -> &'a StorageTupleValue {
-        ctx.get(attr)
-            .expect("[validation] attribute doesn't exist in this context")
+    types: &HashMap<&String, &AttributeType>,
+) -> StorageResult<bool> {
+    match evaluate_expr_with_ctx(predicate, ctx, types)? {
+        StorageTupleValue::Boolean(result) => Ok(result),
+        StorageTupleValue::Null => Ok(false),
+        unexpected => unreachable!(
+            "[validation] predicate is not an equality expression {:?}",
+            unexpected
+        ),
     }
+}
 
-    fn evaluate_expr(expr: &Expr, ctx: &HashMap<&String, &StorageTupleValue>) -> LiteralExpr {
-        match expr {
-            Expr::Binary(expr) => {
-                let left = evaluate_expr(&expr.left, ctx);
-                let right = evaluate_expr(&expr.right, ctx);
-                match left {
-                    LiteralExpr::Boolean(left) => {
-                        match right {
-                            LiteralExpr::Boolean(right) => {
-                                match expr.op {
-                                    BinaryOperation::Equal => LiteralExpr::Boolean(left == right),
-                                    BinaryOperation::NotEqual => LiteralExpr::Boolean(left != right),
-                                    BinaryOperation::LessThan => LiteralExpr::Boolean(left < right),
-                                    BinaryOperation::LessThanOrEqual => LiteralExpr::Boolean(left <= right),
-                                    BinaryOperation::GreaterThan => LiteralExpr::Boolean(left > right),
-                                    BinaryOperation::GreaterThanOrEqual => LiteralExpr::Boolean(left >= right),
-                                    _ => unreachable!("[validation] only equality operations are allowed between two booleans"),
-                                }
-                            },
-                            _ => unreachable!("[validation] incompatible op: left hand is bool but right hand isn't")
-                        }
-                    },
-                    LiteralExpr::Integer(left) => {
-                        match right {
-                            LiteralExpr::Integer(right) => {
-                                match expr.op {
-                                    BinaryOperation::Addition => LiteralExpr::Integer(left + right),
-                                    BinaryOperation::Subtraction => LiteralExpr::Integer(left - right),
-                                    BinaryOperation::Multiplication => LiteralExpr::Integer(left * right),
-                                    BinaryOperation::Division => LiteralExpr::Integer(left / right),
-                                    BinaryOperation::Equal => LiteralExpr::Boolean(left == right),
-                                    BinaryOperation::NotEqual => LiteralExpr::Boolean(left != right),
-                                    BinaryOperation::LessThan => LiteralExpr::Boolean(left < right),
-                                    BinaryOperation::LessThanOrEqual => LiteralExpr::Boolean(left <= right),
-                                    BinaryOperation::GreaterThan => LiteralExpr::Boolean(left > right),
-                                    BinaryOperation::GreaterThanOrEqual => LiteralExpr::Boolean(left >= right),
-                                }
-                            },
-                            _ => unreachable!("[validation] incompatible op: left hand is a number but right hand isn't")
-                        }
-                    },
-                    LiteralExpr::String(left) => {
-                        match right {
-                            LiteralExpr::String(right) => {
-                                match expr.op {
-                                    BinaryOperation::Equal => LiteralExpr::Boolean(left == right),
-                                    BinaryOperation::NotEqual => LiteralExpr::Boolean(left != right),
-                                    _ => unreachable!("[validation] incompatible op: left hand is a string but right hand isn't")
-                                }
-                            },
-                            _ => unreachable!("[validation] only equality operations are allowed between two strings"),
-                        }
-                    },
-                    LiteralExpr::Identifier(_) => unreachable!("identifier should have been evaluated to a concrete value.")
-                }
-            }
-            Expr::Literal(LiteralExpr::Identifier(id)) => match eval(id, ctx) {
-                StorageTupleValue::Boolean(value) => LiteralExpr::Boolean(*value),
-                StorageTupleValue::Integer(value) => LiteralExpr::Integer(*value),
-                StorageTupleValue::String(value) => LiteralExpr::String(value.clone()),
-            },
-            Expr::Literal(literal) => literal.clone(),
-        }
+pub fn evaluate_expr_with_ctx(
+    expr: &Expr,
+    ctx: &HashMap<&String, &StorageTupleValue>,
+    types: &HashMap<&String, &AttributeType>,
+) -> StorageResult<StorageTupleValue> {
+    fn eval(attr: &String, ctx: &HashMap<&String, &StorageTupleValue>) -> StorageTupleValue {
+        (*ctx
+            .get(attr)
+            .expect("[validation] attribute doesn't exist in this context"))
+        .clone()
     }
 
-    match evaluate_expr(&predicate, ctx) {
-        LiteralExpr::Boolean(result) => result,
-        unexpected => unreachable!(format!(
-            "[validation] predicate is not an equality expression {:?}",
-            unexpected
+    match expr {
+        // The right operand of AND and OR is only evaluated when it decides the result. NULL is
+        // an unknown truth value, `NULL AND false` is false and `NULL OR true` is true.
+        Expr::Binary(expr) if expr.op == BinaryOperation::And => {
+            Ok(match evaluate_expr_with_ctx(&expr.left, ctx, types)? {
+                StorageTupleValue::Boolean(false) => StorageTupleValue::Boolean(false),
+                left => match (left, evaluate_expr_with_ctx(&expr.right, ctx, types)?) {
+                    (StorageTupleValue::Boolean(true), right) => right,
+                    (_, StorageTupleValue::Boolean(false)) => StorageTupleValue::Boolean(false),
+                    _ => StorageTupleValue::Null,
+                },
+            })
+        }
+        Expr::Binary(expr) if expr.op == BinaryOperation::Or => {
+            Ok(match evaluate_expr_with_ctx(&expr.left, ctx, types)? {
+                StorageTupleValue::Boolean(true) => StorageTupleValue::Boolean(true),
+                left => match (left, evaluate_expr_with_ctx(&expr.right, ctx, types)?) {
+                    (StorageTupleValue::Boolean(false), right) => right,
+                    (_, StorageTupleValue::Boolean(true)) => StorageTupleValue::Boolean(true),
+                    _ => StorageTupleValue::Null,
+                },
+            })
+        }
+        // Trailing spaces of CHAR values are not significant, a comparison with a CHAR operand
+        // compares both operands without them.
+        Expr::Binary(expr)
+            if expr.op.is_comparison()
+                && (is_char(&expr.left, types) || is_char(&expr.right, types)) =>
+        {
+            evaluate_binary(
+                &expr.op,
+                trim_char(evaluate_expr_with_ctx(&expr.left, ctx, types)?),
+                trim_char(evaluate_expr_with_ctx(&expr.right, ctx, types)?),
+            )
+        }
+        Expr::Binary(expr) => evaluate_binary(
+            &expr.op,
+            evaluate_expr_with_ctx(&expr.left, ctx, types)?,
+            evaluate_expr_with_ctx(&expr.right, ctx, types)?,
+        ),
+        Expr::Unary(expr) => {
+            evaluate_unary(&expr.op, evaluate_expr_with_ctx(&expr.operand, ctx, types)?)
+        }
+        Expr::Function(call) => Ok(evaluate_function(
+            call,
+            call.args
+                .iter()
+                .map(|arg| evaluate_expr_with_ctx(arg, ctx, types))
+                .collect::<StorageResult<_>>()?,
         )),
+        Expr::Aggregate(call) => unreachable!(
+            "[validation] aggregate {} is computed by the aggregate operation",
+            Expr::Aggregate(call.clone())
+        ),
+        Expr::Subquery(_) => unreachable!(
+            "[validation] subquery {} is computed by the subquery operation",
+            expr
+        ),
+        Expr::Literal(literal) => Ok(match literal {
+            LiteralExpr::Integer(value) => StorageTupleValue::Integer(*value),
+            LiteralExpr::Boolean(value) => StorageTupleValue::Boolean(*value),
+            LiteralExpr::String(value) => StorageTupleValue::String(value.clone()),
+            LiteralExpr::Binary(value) => StorageTupleValue::Binary(value.clone()),
+            LiteralExpr::Identifier(id) => eval(id, ctx),
+        }),
+    }
+}
+
+fn is_char(expr: &Expr, types: &HashMap<&String, &AttributeType>) -> bool {
+    match expr {
+        Expr::Literal(LiteralExpr::Identifier(id)) => matches!(
+            types.get(id).map(|attr_type| attr_type.without_null()),
+            Some(AttributeType::Char(_))
+        ),
+        _ => false,
+    }
+}
+
+fn trim_char(value: StorageTupleValue) -> StorageTupleValue {
+    match value {
+        StorageTupleValue::String(value) => {
+            StorageTupleValue::String(value.trim_end_matches(' ').to_owned())
+        }
+        value => value,
+    }
+}
+
+fn evaluate_binary(
+    op: &BinaryOperation,
+    left: StorageTupleValue,
+    right: StorageTupleValue,
+) -> StorageResult<StorageTupleValue> {
+    use StorageTupleValue::*;
+
+    Ok(match (left, right) {
+        (Null, _) | (_, Null) => Null,
+        (Integer(_), Integer(0)) if *op == BinaryOperation::Division => {
+            return Err(StorageError::InvalidValue("division by zero".to_owned()))
+        }
+        (Integer(left), Integer(right)) => match op {
+            BinaryOperation::Addition => checked_arithmetic(left.checked_add(right))?,
+            BinaryOperation::Subtraction => checked_arithmetic(left.checked_sub(right))?,
+            BinaryOperation::Multiplication => checked_arithmetic(left.checked_mul(right))?,
+            BinaryOperation::Division => checked_arithmetic(left.checked_div(right))?,
+            BinaryOperation::Equal => Boolean(left == right),
+            BinaryOperation::NotEqual => Boolean(left != right),
+            BinaryOperation::LessThan => Boolean(left < right),
+            BinaryOperation::LessThanOrEqual => Boolean(left <= right),
+            BinaryOperation::GreaterThan => Boolean(left > right),
+            BinaryOperation::GreaterThanOrEqual => Boolean(left >= right),
+            op => unreachable!(
+                "[validation] invalid operation {:?} between two numbers",
+                op
+            ),
+        },
+        (Boolean(left), Boolean(right)) => match op {
+            BinaryOperation::Equal => Boolean(left == right),
+            BinaryOperation::NotEqual => Boolean(left != right),
+            op => unreachable!(
+                "[validation] invalid operation {:?} between two booleans",
+                op
+            ),
+        },
+        (Uuid(left), Uuid(right)) => compare(op, left, right),
+        (Uuid(left), String(right)) => compare(op, left, parse_uuid_literal(&right)),
+        (String(left), Uuid(right)) => compare(op, parse_uuid_literal(&left), right),
+        (Enum(left), Enum(right)) => compare(op, left, right),
+        (Enum(left), String(right)) => compare(op, left.label, right),
+        (String(left), Enum(right)) => compare(op, left, right.label),
+        (Json(left), String(key)) => json_extract(op, left.get_key(&key)),
+        (Json(left), Integer(index)) => json_extract(op, left.get_index(index)),
+        (left, right) => match op {
+            BinaryOperation::Equal => Boolean(left == right),
+            BinaryOperation::NotEqual => Boolean(left != right),
+            op => unreachable!(
+                "[validation] invalid operation {:?} between {:?} and {:?}",
+                op, left, right
+            ),
+        },
+    })
+}
+
+// Integers are 32 bits wide, a result that does not fit is an error rather than wrapping around.
+fn checked_arithmetic(result: Option<i32>) -> StorageResult<StorageTupleValue> {
+    result
+        .map(StorageTupleValue::Integer)
+        .ok_or_else(|| StorageError::InvalidValue("integer out of range".to_owned()))
+}
+
+fn evaluate_unary(
+    op: &UnaryOperation,
+    operand: StorageTupleValue,
+) -> StorageResult<StorageTupleValue> {
+    match (op, operand) {
+        (_, StorageTupleValue::Null) => Ok(StorageTupleValue::Null),
+        (UnaryOperation::Not, StorageTupleValue::Boolean(value)) => {
+            Ok(StorageTupleValue::Boolean(!value))
+        }
+        // Like an integer literal, a negated value must fit an INTEGER.
+        (UnaryOperation::Negation, StorageTupleValue::Integer(value)) => value
+            .checked_neg()
+            .map(StorageTupleValue::Integer)
+            .ok_or_else(|| {
+                StorageError::InvalidValue(format!("integer {} is out of range", -i64::from(value)))
+            }),
+        (op, operand) => unreachable!("[validation] invalid operation {:?} on {:?}", op, operand),
+    }
+}
+
+fn compare<T: Ord>(op: &BinaryOperation, left: T, right: T) -> StorageTupleValue {
+    StorageTupleValue::Boolean(match op {
+        BinaryOperation::Equal => left == right,
+        BinaryOperation::NotEqual => left != right,
+        BinaryOperation::LessThan => left < right,
+        BinaryOperation::LessThanOrEqual => left <= right,
+        BinaryOperation::GreaterThan => left > right,
+        BinaryOperation::GreaterThanOrEqual => left >= right,
+        op => unreachable!("[validation] {:?} is not a comparison", op),
+    })
+}
+
+fn parse_uuid_literal(literal: &str) -> UuidValue {
+    UuidValue::parse(literal).expect("[validation] UUID literals are checked")
+}
+
+// Evaluates `->` and `->>`. A missing member is NULL, and so is a JSON null extracted as text.
+fn json_extract(op: &BinaryOperation, member: Option<&JsonValue>) -> StorageTupleValue {
+    match (op, member) {
+        (_, None) | (BinaryOperation::JsonExtractText, Some(JsonValue::Null)) => {
+            StorageTupleValue::Null
+        }
+        (BinaryOperation::JsonExtract, Some(member)) => StorageTupleValue::Json(member.clone()),
+        (BinaryOperation::JsonExtractText, Some(member)) => {
+            StorageTupleValue::String(member.to_unquoted_string())
+        }
+        (op, _) => unreachable!("[validation] invalid operation {:?} on a JSON value", op),
+    }
+}
+
+fn evaluate_function(call: &FunctionCall, args: Vec<StorageTupleValue>) -> StorageTupleValue {
+    match (call.name.as_str(), args.as_slice()) {
+        ("coalesce", args) => args
+            .iter()
+            .find(|arg| **arg != StorageTupleValue::Null)
+            .cloned()
+            .unwrap_or(StorageTupleValue::Null),
+        (_, args) if args.contains(&StorageTupleValue::Null) => StorageTupleValue::Null,
+        ("json_extract", [StorageTupleValue::Json(value), StorageTupleValue::String(path)]) => {
+            StorageTupleValue::Json(
+                value
+                    .get_path(path)
+                    .ok()
+                    .flatten()
+                    .cloned()
+                    .unwrap_or(JsonValue::Null),
+            )
+        }
+        ("gen_random_uuid", []) => StorageTupleValue::Uuid(UuidValue::new_random()),
+        (
+            "$enum_label",
+            [StorageTupleValue::Integer(ordinal), StorageTupleValue::String(label)],
+        ) => StorageTupleValue::Enum(EnumLabel {
+            ordinal: *ordinal as u16,
+            label: label.clone(),
+        }),
+        _ => unreachable!(
+            "[validation] invalid function call {}",
+            Expr::Function(call.clone())
+        ),
     }
 }
@@ -29,10 +29,13 @@ impl NextTuple for FilterOperation {
                                     .iter()
                                     .map(|(attr_name, attr_type)| (&attr_name.0, attr_type))
                                     .collect(),
+                                &self.schema.attributes.as_lookup_table(),
                             );
 
-                            if forward {
-                                return Some(Ok(record));
+                            match forward {
+                                Ok(true) => return Some(Ok(record)),
+                                Ok(false) => {}
+                                Err(err) => return Some(Err(err)),
                             }
                         }
                         Err(err) => return Some(Err(StorageError::from(err))),
@@ -1,21 +1,165 @@
-use crate::execution::EmptyResult;
-use crate::storage::storage_manager::{StorageManager, TableName};
-use crate::storage::tuple::TupleRecord;
+use crate::execution::expr_evaluation::evaluate_expr_with_ctx;
+use crate::execution::AffectedTuplesResult;
+use crate::parser::ast::Expr;
+use crate::planner::plan::insert_plan::OnConflict;
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::error::{Result as StorageResult, StorageError};
+use crate::storage::storage_manager::{AttributeName, StorageManager, TableName};
+use crate::storage::tuple::{TupleId, TupleRecord};
+use crate::storage::tuple_serde::{serialize_tuple, StorageTupleValue};
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct InsertTupleOperation {
     pub table_name: TableName,
-    pub tuple: TupleRecord,
+    pub schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub tuples: Vec<TupleRecord>,
+    pub on_conflict: OnConflict,
+}
+
+// Where the row with a given key comes from while the statement runs.
+enum KeyOwner {
+    Stored(TupleId),
+    Inserted,
 }
 
 impl InsertTupleOperation {
-    pub fn execute(self, storage_manager: &mut StorageManager) -> EmptyResult {
+    // Rows are checked against the table and against the rows inserted before them. All
+    // changes are collected first so that a conflict leaves the table untouched.
+    pub fn execute(self, storage_manager: &mut StorageManager) -> AffectedTuplesResult {
         let mut storage = storage_manager
             .get_table_store(&self.table_name)
             .expect("[insert plan] table storage no longer exists?");
 
-        let _tuple_id = storage.insert_tuple(self.tuple);
+        let mut inserted = BTreeSet::new();
+        let mut inserts: Vec<(StorageTupleValue, TupleRecord)> = Vec::new();
+        let mut updates: HashMap<TupleId, (StorageTupleValue, TupleRecord)> = HashMap::new();
+        for tuple in self.tuples.iter() {
+            let key = self.primary_key_of(tuple)?;
+            let owner = match inserted.contains(&key) {
+                true => Some(KeyOwner::Inserted),
+                false => storage.get_tuple_id(&key).map(KeyOwner::Stored),
+            };
+            let (id, assignments) = match (owner, &self.on_conflict) {
+                (None, _) => {
+                    inserted.insert(key.clone());
+                    inserts.push((key, tuple.clone()));
+                    continue;
+                }
+                (Some(_), OnConflict::Fail) => {
+                    return Err(StorageError::AlreadyExists(format!(
+                        "row with {} = {:?} in table {:?}",
+                        self.primary_key.0, key, self.table_name.0
+                    )))
+                }
+                (Some(_), OnConflict::DoNothing) => continue,
+                // A key inserted or updated by an earlier row of the statement would be updated
+                // a second time, which of the rows wins is ambiguous.
+                (Some(KeyOwner::Inserted), OnConflict::DoUpdate(_)) => {
+                    return Err(self.affected_twice(&key))
+                }
+                (Some(KeyOwner::Stored(id)), OnConflict::DoUpdate(_))
+                    if updates.contains_key(&id) =>
+                {
+                    return Err(self.affected_twice(&key))
+                }
+                (Some(KeyOwner::Stored(id)), OnConflict::DoUpdate(assignments)) => {
+                    (id, assignments)
+                }
+            };
+            let current = storage.get_tuple(&id).expect("indexed tuples exist");
+            let updated = self.apply_assignments(assignments, &current, tuple)?;
+            updates.insert(id, (key, updated));
+        }
+
+        let mut affected = Vec::with_capacity(updates.len() + inserts.len());
+        for (id, (key, record)) in updates {
+            storage.update_tuple(&id, key, record.clone())?;
+            affected.push(record);
+        }
+        for (key, tuple) in inserts {
+            let _tuple_id = storage.insert_tuple(key, tuple.clone());
+            affected.push(tuple);
+        }
+
+        Ok(affected)
+    }
+
+    fn affected_twice(&self, key: &StorageTupleValue) -> StorageError {
+        StorageError::CardinalityViolation(format!(
+            "ON CONFLICT DO UPDATE command cannot affect row a second time, \
+             row with {} = {:?} in table {:?}",
+            self.primary_key.0, key, self.table_name.0
+        ))
+    }
+
+    fn primary_key_of(&self, record: &TupleRecord) -> StorageResult<StorageTupleValue> {
+        let values = record.to_values::<_, Vec<(AttributeName, StorageTupleValue)>>(
+            self.schema.attributes.attributes_iter(),
+        )?;
+        Ok(values
+            .into_iter()
+            .find(|(attr_name, _)| attr_name == &self.primary_key)
+            .map(|(_, value)| value)
+            .expect("[insert plan] the primary key is an attribute of the table"))
+    }
+
+    fn apply_assignments(
+        &self,
+        assignments: &[(AttributeName, Expr)],
+        current: &TupleRecord,
+        excluded: &TupleRecord,
+    ) -> StorageResult<TupleRecord> {
+        let attributes = self.schema.attributes.attributes_iter();
+        let current_values =
+            current.to_values::<_, Vec<(AttributeName, StorageTupleValue)>>(attributes)?;
+        let excluded_values = excluded
+            .to_values::<_, Vec<(AttributeName, StorageTupleValue)>>(
+                self.schema.attributes.attributes_iter(),
+            )?
+            .into_iter()
+            .map(|(attr_name, value)| (format!("excluded.{}", attr_name.0), value))
+            .collect::<Vec<_>>();
+        let ctx = current_values
+            .iter()
+            .map(|(attr_name, value)| (&attr_name.0, value))
+            .chain(excluded_values.iter().map(|(name, value)| (name, value)))
+            .collect();
+        let types = self
+            .schema
+            .attributes
+            .as_lookup_table()
+            .into_iter()
+            .chain(
+                excluded_values
+                    .iter()
+                    .zip(self.schema.attributes.attributes_iter())
+                    .map(|((name, _), (_, attr_type))| (name, attr_type)),
+            )
+            .collect();
+
+        let mut updated_values = Vec::with_capacity(current_values.len());
+        for ((attr_name, attr_type), (_, value)) in self
+            .schema
+            .attributes
+            .attributes_iter()
+            .zip(&current_values)
+        {
+            let value = match assignments.iter().find(|(name, _)| name == attr_name) {
+                Some((_, expr)) => attr_type
+                    .coerce(evaluate_expr_with_ctx(expr, &ctx, &types)?)
+                    .map_err(|err| {
+                        StorageError::InvalidValue(format!(
+                            "{} for attribute {:?}",
+                            err, attr_name.0
+                        ))
+                    })?,
+                None => value.clone(),
+            };
+            updated_values.push(value);
+        }
 
-        Ok(())
+        Ok(serialize_tuple(updated_values))
     }
 }
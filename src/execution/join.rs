@@ -1,100 +1,164 @@
 use crate::execution::expr_evaluation::evaluate_predicate_with_ctx;
-use crate::execution::{FilterOperation, NextTuple, SubQueryTuples};
-use crate::parser::ast::{AttributeValue, Expr};
+use crate::execution::{NextTuple, SubQueryTuples};
+use crate::parser::ast::{Expr, JoinType};
 use crate::planner::plan::query_plan::QueryResultSchema;
 use crate::storage::error::StorageError;
 use crate::storage::storage_manager::AttributeName;
 use crate::storage::tuple::TupleRecord;
-use crate::storage::tuple_serde::StorageTupleValue;
+use crate::storage::tuple_serde::{serialize_record, StorageTupleValue};
+use crate::storage::types::AttributeType;
 use std::collections::HashMap;
 
 struct TupleWithColumnLookup {
-    tuple: TupleRecord,
+    values: Vec<StorageTupleValue>,
     columns: HashMap<AttributeName, StorageTupleValue>,
+    matched: bool,
 }
 
-pub struct InnerJoinOperation {
+impl TupleWithColumnLookup {
+    fn new(tuple: TupleRecord, schema: &QueryResultSchema) -> Result<Self, StorageError> {
+        let values = tuple.to_values::<_, Vec<_>>(schema.attributes.attributes_iter())?;
+        Ok(TupleWithColumnLookup {
+            values: values.iter().map(|(_, value)| value.clone()).collect(),
+            columns: values.into_iter().collect(),
+            matched: false,
+        })
+    }
+}
+
+// Joins every record of the right input against the buffered records of the left input. The
+// records of the side an outer join keeps are also returned when they match no record of the
+// other side, extended with NULL for the attributes of the other side.
+pub struct JoinOperation {
+    join_type: JoinType,
     predicate: Expr,
+    schema: QueryResultSchema,
     left: SubQueryTuples,
     right: SubQueryTuples,
     left_tuple_buffer: Vec<TupleWithColumnLookup>,
     joined_tuples_buffer: Vec<TupleRecord>,
     pre_fetched_left: bool,
+    joined_unmatched_left: bool,
 }
 
-impl InnerJoinOperation {
-    pub fn new(left: SubQueryTuples, right: SubQueryTuples, predicate: Expr) -> Self {
-        InnerJoinOperation {
+impl JoinOperation {
+    pub fn new(
+        join_type: JoinType,
+        schema: QueryResultSchema,
+        left: SubQueryTuples,
+        right: SubQueryTuples,
+        predicate: Expr,
+    ) -> Self {
+        JoinOperation {
+            join_type,
             predicate,
+            schema,
             left,
             right,
             left_tuple_buffer: Vec::new(),
             joined_tuples_buffer: Vec::new(),
             pre_fetched_left: false,
+            joined_unmatched_left: false,
         }
     }
 
     fn pre_fetch_left(&mut self) -> Result<(), StorageError> {
         self.pre_fetched_left = true;
         while let Some(result) = self.left.tuples.next() {
-            let tuple = result?;
-            let columns = tuple
-                .to_values::<_, HashMap<_, _>>(self.left.schema.attributes.attributes_iter())?;
-            self.left_tuple_buffer
-                .push(TupleWithColumnLookup { tuple, columns });
+            let tuple = TupleWithColumnLookup::new(result?, &self.left.schema)?;
+            self.left_tuple_buffer.push(tuple);
         }
         Ok(())
     }
 
     fn join_next_tuple_from_right(&mut self) -> Result<(), StorageError> {
-        if self.left_tuple_buffer.is_empty() && !self.joined_tuples_buffer.is_empty() {
-            return Ok(());
-        }
-
-        while let Some(result) = self.right.tuples.next() {
-            let right = result?;
-            let right_columns = right
-                .to_values::<_, HashMap<_, _>>(self.right.schema.attributes.attributes_iter())?;
-            for left in &self.left_tuple_buffer {
-                let join_ctx = left
-                    .columns
-                    .iter()
-                    .chain(right_columns.iter())
-                    .map(|(attr_name, attr_type)| (&attr_name.0, attr_type))
-                    .collect::<HashMap<_, _>>();
-
-                let join_match = evaluate_predicate_with_ctx(&self.predicate, &join_ctx);
-                if join_match {
-                    self.joined_tuples_buffer
-                        .push(TupleRecord::concat(&left.tuple, &right));
+        let keeps_right = matches!(self.join_type, JoinType::Right | JoinType::Full);
+        while self.joined_tuples_buffer.is_empty() {
+            let right = match self.right.tuples.next() {
+                Some(result) => TupleWithColumnLookup::new(result?, &self.right.schema)?,
+                None => break,
+            };
+            let types = self.schema.attributes.as_lookup_table();
+            let mut matched = false;
+            for left in &mut self.left_tuple_buffer {
+                if join_matches(&self.predicate, &left.columns, &right.columns, &types)? {
+                    left.matched = true;
+                    matched = true;
+                    self.joined_tuples_buffer.push(serialize_record(
+                        [left.values.clone(), right.values.clone()].concat(),
+                        &self.schema.attributes,
+                    ));
                 }
             }
-            if !self.joined_tuples_buffer.is_empty() {
-                break;
+            if !matched && keeps_right {
+                let nulls = vec![
+                    StorageTupleValue::Null;
+                    self.left.schema.attributes.attributes_iter().count()
+                ];
+                self.joined_tuples_buffer.push(serialize_record(
+                    [nulls, right.values].concat(),
+                    &self.schema.attributes,
+                ));
             }
         }
         Ok(())
     }
+
+    // The left records that matched no right record, once all right records were joined.
+    fn join_unmatched_left(&mut self) {
+        self.joined_unmatched_left = true;
+        if !matches!(self.join_type, JoinType::Left | JoinType::Full) {
+            return;
+        }
+        let nulls =
+            vec![StorageTupleValue::Null; self.right.schema.attributes.attributes_iter().count()];
+        for left in self.left_tuple_buffer.iter().filter(|left| !left.matched) {
+            self.joined_tuples_buffer.push(serialize_record(
+                [left.values.clone(), nulls.clone()].concat(),
+                &self.schema.attributes,
+            ));
+        }
+    }
+}
+
+// Evaluates a join condition against the attributes of a pair of records.
+pub(crate) fn join_matches(
+    predicate: &Expr,
+    left: &HashMap<AttributeName, StorageTupleValue>,
+    right: &HashMap<AttributeName, StorageTupleValue>,
+    types: &HashMap<&String, &AttributeType>,
+) -> Result<bool, StorageError> {
+    let join_ctx = left
+        .iter()
+        .chain(right.iter())
+        .map(|(attr_name, value)| (&attr_name.0, value))
+        .collect::<HashMap<_, _>>();
+
+    evaluate_predicate_with_ctx(predicate, &join_ctx, types)
 }
 
-impl NextTuple for InnerJoinOperation {
+impl NextTuple for JoinOperation {
     fn next(&mut self) -> Option<Result<TupleRecord, StorageError>> {
         if !self.pre_fetched_left {
-            self.pre_fetch_left();
+            if let Err(err) = self.pre_fetch_left() {
+                return Some(Err(err));
+            }
+        }
+        if let Err(err) = self.join_next_tuple_from_right() {
+            return Some(Err(err));
         }
-        match self.join_next_tuple_from_right() {
-            Ok(()) => (),
-            Err(err) => return Some(Err(err)),
+        if self.joined_tuples_buffer.is_empty() && !self.joined_unmatched_left {
+            self.join_unmatched_left();
         }
-        self.joined_tuples_buffer.pop().map(|t| Ok(t))
+        self.joined_tuples_buffer.pop().map(Ok)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::execution::join::InnerJoinOperation;
+    use crate::execution::join::JoinOperation;
     use crate::execution::{NextTuple, ScanOperation, SubQueryTuples};
-    use crate::parser::ast::{BinaryExpr, BinaryOperation, Expr, LiteralExpr};
+    use crate::parser::ast::{BinaryExpr, BinaryOperation, Expr, JoinType, LiteralExpr};
     use crate::planner::plan::query_plan::QueryResultSchema;
     use crate::storage::storage_manager::{AttributeName, Attributes};
     use crate::storage::tuple_serde::{deserialize_tuple, serialize_tuple, StorageTupleValue};
@@ -165,7 +229,13 @@ mod test {
             ))),
         });
 
-        let mut j = InnerJoinOperation::new(left_input, right_input, predicate);
+        let mut j = JoinOperation::new(
+            JoinType::Inner,
+            join_schema.clone(),
+            left_input,
+            right_input,
+            predicate,
+        );
         let mut joined_tuples = Vec::new();
         while let Some(tuple) = j.next() {
             joined_tuples.push(tuple);
@@ -215,4 +285,106 @@ mod test {
             ]
         )
     }
+
+    #[test]
+    fn outer_join() {
+        let left_schema = QueryResultSchema::new(Attributes::new(vec![(
+            AttributeName("name".to_owned()),
+            AttributeType::Text,
+        )]))
+        .with_alias("person");
+        let right_schema = QueryResultSchema::new(Attributes::new(vec![(
+            AttributeName("id".to_owned()),
+            AttributeType::Text,
+        )]))
+        .with_alias("employee");
+        let text = |value: &str| StorageTupleValue::String(value.to_owned());
+        let run = |join_type: JoinType, left_nullable: bool, right_nullable: bool| {
+            let nullable = |attr_type: AttributeType, nullable: bool| {
+                if nullable {
+                    attr_type.nullable()
+                } else {
+                    attr_type
+                }
+            };
+            let join_schema = QueryResultSchema::new(Attributes::new(vec![
+                (
+                    AttributeName("person.name".to_owned()),
+                    nullable(AttributeType::Text, left_nullable),
+                ),
+                (
+                    AttributeName("employee.id".to_owned()),
+                    nullable(AttributeType::Text, right_nullable),
+                ),
+            ]));
+            let left_input = SubQueryTuples {
+                schema: left_schema.clone(),
+                tuples: Box::new(ScanOperation::new(vec![
+                    serialize_tuple(vec![text("a")]),
+                    serialize_tuple(vec![text("b")]),
+                ])),
+            };
+            let right_input = SubQueryTuples {
+                schema: right_schema.clone(),
+                tuples: Box::new(ScanOperation::new(vec![
+                    serialize_tuple(vec![text("a")]),
+                    serialize_tuple(vec![text("c")]),
+                ])),
+            };
+            let predicate = Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Literal(LiteralExpr::Identifier(
+                    "person.name".to_owned(),
+                ))),
+                op: BinaryOperation::Equal,
+                right: Box::new(Expr::Literal(LiteralExpr::Identifier(
+                    "employee.id".to_owned(),
+                ))),
+            });
+
+            let mut j = JoinOperation::new(
+                join_type,
+                join_schema.clone(),
+                left_input,
+                right_input,
+                predicate,
+            );
+            let mut joined_tuples = Vec::new();
+            while let Some(tuple) = j.next() {
+                let attr_types = join_schema
+                    .attributes
+                    .attributes_iter()
+                    .map(|(_, attr_type)| attr_type.clone())
+                    .collect();
+                joined_tuples.push(tuple.map(|tuple| deserialize_tuple(tuple, attr_types)));
+            }
+            joined_tuples.sort_by_key(|result| match result {
+                Ok(tuples) => format!("{:?}", tuples),
+                Err(_) => String::new(),
+            });
+            joined_tuples
+        };
+
+        assert_eq!(
+            run(JoinType::Left, false, true),
+            vec![
+                Ok(vec![text("a"), text("a")]),
+                Ok(vec![text("b"), StorageTupleValue::Null]),
+            ]
+        );
+        assert_eq!(
+            run(JoinType::Right, true, false),
+            vec![
+                Ok(vec![StorageTupleValue::Null, text("c")]),
+                Ok(vec![text("a"), text("a")]),
+            ]
+        );
+        assert_eq!(
+            run(JoinType::Full, true, true),
+            vec![
+                Ok(vec![StorageTupleValue::Null, text("c")]),
+                Ok(vec![text("a"), text("a")]),
+                Ok(vec![text("b"), StorageTupleValue::Null]),
+            ]
+        );
+    }
 }
@@ -0,0 +1,86 @@
+use crate::execution::{NextTuple, TupleResult};
+
+// Stops pulling from its input once `limit` records were returned, so the operators below it
+// do no more work than needed.
+pub struct LimitOperation {
+    pub limit: Option<usize>,
+    pub offset: usize,
+    pub input: Box<dyn NextTuple>,
+    returned: usize,
+}
+
+impl NextTuple for LimitOperation {
+    fn next(&mut self) -> TupleResult {
+        while self.offset > 0 {
+            self.offset -= 1;
+            match self.input.next() {
+                Some(Ok(_skipped)) => continue,
+                other => return other,
+            }
+        }
+
+        match self.limit {
+            Some(limit) if self.returned >= limit => None,
+            _ => {
+                self.returned += 1;
+                self.input.next()
+            }
+        }
+    }
+}
+
+impl LimitOperation {
+    pub fn new(limit: Option<usize>, offset: usize, input: Box<dyn NextTuple>) -> Self {
+        LimitOperation {
+            limit,
+            offset,
+            input,
+            returned: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::execution::limit::LimitOperation;
+    use crate::execution::{NextTuple, ScanOperation, TupleResult};
+    use crate::storage::tuple::TupleRecord;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct CountingOperation {
+        pulled: Rc<Cell<usize>>,
+        input: ScanOperation,
+    }
+
+    impl NextTuple for CountingOperation {
+        fn next(&mut self) -> TupleResult {
+            self.pulled.set(self.pulled.get() + 1);
+            self.input.next()
+        }
+    }
+
+    #[test]
+    fn limit() {
+        let run = |limit: Option<usize>, offset: usize| {
+            let pulled = Rc::new(Cell::new(0));
+            let input = CountingOperation {
+                pulled: pulled.clone(),
+                input: ScanOperation::new((0..5).map(|i| TupleRecord(vec![i])).collect()),
+            };
+            let mut l = LimitOperation::new(limit, offset, Box::new(input));
+            let mut items = Vec::new();
+            while let Some(item) = l.next() {
+                items.push(item.unwrap().0[0]);
+            }
+            (items, pulled.get())
+        };
+
+        assert_eq!(run(Some(2), 0), (vec![0, 1], 2));
+        assert_eq!(run(Some(2), 1), (vec![1, 2], 3));
+        assert_eq!(run(Some(0), 0), (vec![], 0));
+        assert_eq!(run(None, 3), (vec![3, 4], 6));
+        assert_eq!(run(Some(10), 4), (vec![4], 6));
+        assert_eq!(run(Some(1), 7), (vec![], 6));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::execution::expr_evaluation::{evaluate_expr_with_ctx, evaluate_predicate_with_ctx};
+use crate::execution::join::join_matches;
+use crate::execution::{MergeResult, SubQueryTuples};
+use crate::parser::ast::Expr;
+use crate::planner::plan::merge_plan::{MergeAction, MergeWhenClause};
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::error::{Result as StorageResult, StorageError};
+use crate::storage::storage_manager::{AttributeName, StorageManager, TableName};
+use crate::storage::tuple::TupleId;
+use crate::storage::tuple_serde::{serialize_tuple, StorageTupleValue};
+use crate::storage::types::AttributeType;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+pub struct MergeOperation {
+    pub table_name: TableName,
+    pub target_schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub source: SubQueryTuples,
+    pub predicate: Expr,
+    pub when_clauses: Vec<MergeWhenClause>,
+}
+
+// The number of rows of the target affected by each kind of action.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct MergeCounts {
+    pub inserted: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+struct TargetRow {
+    id: TupleId,
+    columns: HashMap<AttributeName, StorageTupleValue>,
+}
+
+impl MergeOperation {
+    // Source rows are matched against the target as it was before the statement. All changes
+    // are collected first so that a failing row leaves the table untouched.
+    pub fn execute(self, storage_manager: &mut StorageManager) -> MergeResult {
+        let MergeOperation {
+            table_name,
+            target_schema,
+            primary_key,
+            mut source,
+            predicate,
+            when_clauses,
+        } = self;
+        let mut storage = storage_manager
+            .get_table_store(&table_name)
+            .expect("[merge plan] table storage no longer exists?");
+
+        let mut targets = Vec::new();
+        let mut keys = BTreeSet::new();
+        for (id, record) in storage.scan() {
+            let columns =
+                record.to_values::<_, HashMap<_, _>>(target_schema.attributes.attributes_iter())?;
+            keys.insert(columns[&primary_key].clone());
+            targets.push(TargetRow {
+                id: id.clone(),
+                columns,
+            });
+        }
+
+        let types = target_schema
+            .attributes
+            .as_lookup_table()
+            .into_iter()
+            .chain(source.schema.attributes.as_lookup_table())
+            .collect();
+        let key_index = target_schema
+            .attributes
+            .attributes_iter()
+            .position(|(attr_name, _)| attr_name == &primary_key)
+            .expect("[merge plan] the primary key is an attribute of the table");
+        let mut affected = HashSet::new();
+        let mut updates = Vec::new();
+        let mut deletes = Vec::new();
+        let mut inserts = Vec::new();
+        while let Some(result) = source.tuples.next() {
+            let source_columns = result?
+                .to_values::<_, HashMap<_, _>>(source.schema.attributes.attributes_iter())?;
+
+            let mut matched = false;
+            for target in &targets {
+                if !join_matches(&predicate, &target.columns, &source_columns, &types)? {
+                    continue;
+                }
+                matched = true;
+
+                let ctx = target
+                    .columns
+                    .iter()
+                    .chain(source_columns.iter())
+                    .map(|(attr_name, value)| (&attr_name.0, value))
+                    .collect();
+                let action = match first_action(&when_clauses, true, &ctx, &types)? {
+                    Some(MergeAction::DoNothing) | None => continue,
+                    Some(action) => action,
+                };
+                if !affected.insert(target.id.clone()) {
+                    return Err(StorageError::CardinalityViolation(format!(
+                        "a row of table {:?} is matched by more than one source row",
+                        table_name.0
+                    )));
+                }
+
+                match action {
+                    MergeAction::Update(assignments) => {
+                        let mut updated_values = Vec::new();
+                        for (attr_name, attr_type) in target_schema.attributes.attributes_iter() {
+                            let value = match assignments.iter().find(|(name, _)| name == attr_name)
+                            {
+                                Some((_, expr)) => coerce(
+                                    attr_name,
+                                    attr_type,
+                                    evaluate_expr_with_ctx(expr, &ctx, &types)?,
+                                )?,
+                                None => target.columns[attr_name].clone(),
+                            };
+                            updated_values.push(value);
+                        }
+                        updates.push((
+                            target.id.clone(),
+                            target.columns[&primary_key].clone(),
+                            serialize_tuple(updated_values),
+                        ));
+                    }
+                    MergeAction::Delete => {
+                        keys.remove(&target.columns[&primary_key]);
+                        deletes.push(target.id.clone());
+                    }
+                    MergeAction::Insert(_) | MergeAction::DoNothing => {
+                        unreachable!("matched rows are only updated or deleted")
+                    }
+                }
+            }
+            if matched {
+                continue;
+            }
+
+            let ctx = source_columns
+                .iter()
+                .map(|(attr_name, value)| (&attr_name.0, value))
+                .collect();
+            if let Some(MergeAction::Insert(exprs)) =
+                first_action(&when_clauses, false, &ctx, &types)?
+            {
+                let mut values = Vec::with_capacity(exprs.len());
+                for ((attr_name, attr_type), expr) in
+                    target_schema.attributes.attributes_iter().zip(exprs)
+                {
+                    values.push(coerce(
+                        attr_name,
+                        attr_type,
+                        evaluate_expr_with_ctx(expr, &ctx, &types)?,
+                    )?);
+                }
+                let key = values[key_index].clone();
+                if !keys.insert(key.clone()) {
+                    return Err(StorageError::AlreadyExists(format!(
+                        "row with {} = {:?} in table {:?}",
+                        primary_key.0, key, table_name.0
+                    )));
+                }
+                inserts.push((key, serialize_tuple(values)));
+            }
+        }
+
+        let counts = MergeCounts {
+            inserted: inserts.len(),
+            updated: updates.len(),
+            deleted: deletes.len(),
+        };
+        for (id, key, record) in updates {
+            storage.update_tuple(&id, key, record)?;
+        }
+        for id in &deletes {
+            storage.delete_tuple(id)?;
+        }
+        for (key, tuple) in inserts {
+            let _tuple_id = storage.insert_tuple(key, tuple);
+        }
+
+        Ok(counts)
+    }
+}
+
+fn first_action<'a>(
+    when_clauses: &'a [MergeWhenClause],
+    matched: bool,
+    ctx: &HashMap<&String, &StorageTupleValue>,
+    types: &HashMap<&String, &AttributeType>,
+) -> StorageResult<Option<&'a MergeAction>> {
+    for when_clause in when_clauses {
+        if when_clause.matched != matched {
+            continue;
+        }
+        let holds = match &when_clause.condition {
+            Some(condition) => evaluate_predicate_with_ctx(condition, ctx, types)?,
+            None => true,
+        };
+        if holds {
+            return Ok(Some(&when_clause.action));
+        }
+    }
+    Ok(None)
+}
+
+fn coerce(
+    attr_name: &AttributeName,
+    attr_type: &AttributeType,
+    value: StorageTupleValue,
+) -> StorageResult<StorageTupleValue> {
+    attr_type.coerce(value).map_err(|err| {
+        StorageError::InvalidValue(format!("{} for attribute {:?}", err, attr_name.0))
+    })
+}
@@ -1,24 +1,50 @@
+mod aggregate;
 mod create;
+mod delete;
+mod distinct;
 mod engine;
-mod expr_evaluation;
+pub(crate) mod expr_evaluation;
 mod filter;
 mod insert;
 mod join;
+mod limit;
+mod merge;
 mod project;
 pub mod scan;
+mod set_operation;
+mod sort;
+mod subquery;
+mod truncate;
+mod update;
 
 use crate::planner::plan::query_plan::QueryResultSchema;
 use crate::storage::error::Result as StorageResult;
 use crate::storage::tuple::TupleRecord;
-pub(crate) use create::CreateTableOperation;
+pub(crate) use aggregate::AggregateOperation;
+pub(crate) use create::{CreateTableOperation, CreateTypeOperation};
+pub(crate) use delete::DeleteOperation;
+pub(crate) use distinct::DistinctOperation;
 pub(crate) use engine::{Engine, Operation};
 pub(crate) use filter::FilterOperation;
 pub(crate) use insert::InsertTupleOperation;
-pub(crate) use join::InnerJoinOperation;
+pub(crate) use join::JoinOperation;
+pub(crate) use limit::LimitOperation;
+pub(crate) use merge::{MergeCounts, MergeOperation};
 pub(crate) use project::ProjectOperation;
 pub(crate) use scan::{ScanOperation, Tuples};
+pub(crate) use set_operation::SetOperation;
+pub(crate) use sort::SortOperation;
+pub(crate) use subquery::{CreateSubquery, SubqueryOperation};
+pub(crate) use truncate::TruncateOperation;
+pub(crate) use update::UpdateOperation;
 
 pub(crate) type EmptyResult = StorageResult<()>;
+// The number of rows affected by a statement.
+pub(crate) type RowCountResult = StorageResult<usize>;
+// The records inserted, updated or deleted by a statement, as they were last stored.
+pub(crate) type AffectedTuplesResult = StorageResult<Vec<TupleRecord>>;
+// The rows affected by each kind of action of a MERGE statement.
+pub(crate) type MergeResult = StorageResult<MergeCounts>;
 pub(crate) type TupleResult = Option<StorageResult<TupleRecord>>;
 
 pub trait NextTuple {
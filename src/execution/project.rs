@@ -1,14 +1,17 @@
+use crate::execution::expr_evaluation::evaluate_expr_with_ctx;
 use crate::execution::NextTuple;
+use crate::parser::ast::Expr;
 use crate::planner::plan::query_plan::QueryResultSchema;
 use crate::storage::error::{Result as StorageResult, StorageError};
-use crate::storage::storage_manager::{AttributeName, Schema};
+use crate::storage::storage_manager::Schema;
 use crate::storage::tuple::TupleRecord;
-use crate::storage::tuple_serde::{serialize_tuple, StorageTupleValue};
+use crate::storage::tuple_serde::{serialize_record, StorageTupleValue};
 use std::collections::HashMap;
 
 pub struct ProjectOperation {
+    pub schema: QueryResultSchema,
     pub record_schema: QueryResultSchema,
-    pub projected_attributes: Vec<AttributeName>,
+    pub exprs: Vec<Expr>,
     pub input: Box<dyn NextTuple>,
 }
 
@@ -19,17 +22,17 @@ impl NextTuple for ProjectOperation {
                 let value_by_attr = record.to_values::<_, HashMap<_, _>>(
                     self.record_schema.attributes.attributes_iter(),
                 )?;
-                let projected_values = self
-                    .projected_attributes
+                let ctx = value_by_attr
                     .iter()
-                    .map(|attr_name| {
-                        value_by_attr
-                            .get(attr_name)
-                            .expect("we verified that the record has this attribute")
-                            .clone()
-                    })
+                    .map(|(attr_name, value)| (&attr_name.0, value))
                     .collect();
-                let record = serialize_tuple(projected_values);
+                let types = self.record_schema.attributes.as_lookup_table();
+                let projected_values = self
+                    .exprs
+                    .iter()
+                    .map(|expr| evaluate_expr_with_ctx(expr, &ctx, &types))
+                    .collect::<StorageResult<_>>()?;
+                let record = serialize_record(projected_values, &self.schema.attributes);
                 Ok(record)
             })
         })
@@ -93,10 +96,11 @@ mod test {
             ]),
         ]);
         let mut p = ProjectOperation {
+            schema: projection_schema.clone(),
             record_schema: schema.clone(),
-            projected_attributes: vec![
-                AttributeName("is_member".to_owned()),
-                AttributeName("age".to_owned()),
+            exprs: vec![
+                Expr::Literal(LiteralExpr::Identifier("is_member".to_owned())),
+                Expr::Literal(LiteralExpr::Identifier("age".to_owned())),
             ],
             input: Box::new(input),
         };
@@ -0,0 +1,209 @@
+use crate::execution::{NextTuple, SubQueryTuples, TupleResult};
+use crate::parser::ast::SetOperator;
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::error::Result as StorageResult;
+use crate::storage::tuple::TupleRecord;
+use crate::storage::tuple_serde::serialize_record;
+use std::collections::{HashMap, HashSet};
+
+// Combines the records of two inputs, records are equal when they are encoded the same. UNION
+// returns the records of the left input then those of the right input. INTERSECT and EXCEPT count
+// the records of the right input before returning the records of the left input they keep, with
+// ALL a record of the right input matches a single record of the left input.
+pub struct SetOperation {
+    op: SetOperator,
+    all: bool,
+    schema: QueryResultSchema,
+    left: SubQueryTuples,
+    right: SubQueryTuples,
+    right_counts: Option<HashMap<Vec<u8>, usize>>,
+    seen: HashSet<Vec<u8>>,
+    left_done: bool,
+}
+
+impl SetOperation {
+    pub fn new(
+        op: SetOperator,
+        all: bool,
+        schema: QueryResultSchema,
+        left: SubQueryTuples,
+        right: SubQueryTuples,
+    ) -> Self {
+        SetOperation {
+            op,
+            all,
+            schema,
+            left,
+            right,
+            right_counts: None,
+            seen: HashSet::new(),
+            left_done: false,
+        }
+    }
+
+    fn count_right(&mut self) -> StorageResult<HashMap<Vec<u8>, usize>> {
+        let mut counts = HashMap::new();
+        while let Some(result) = self.right.tuples.next() {
+            let record = encode(result?, &self.right.schema, &self.schema)?;
+            *counts.entry(record.0).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
+    fn next_record(&mut self) -> TupleResult {
+        if !self.left_done {
+            match self.left.tuples.next() {
+                Some(result) => {
+                    return Some(
+                        result.and_then(|record| encode(record, &self.left.schema, &self.schema)),
+                    )
+                }
+                None => self.left_done = true,
+            }
+        }
+        match self.op {
+            SetOperator::Union => self.right.tuples.next().map(|result| {
+                result.and_then(|record| encode(record, &self.right.schema, &self.schema))
+            }),
+            SetOperator::Intersect | SetOperator::Except => None,
+        }
+    }
+
+    fn keeps(&mut self, record: &TupleRecord) -> bool {
+        let all = self.all;
+        let matched = match self
+            .right_counts
+            .as_mut()
+            .and_then(|counts| counts.get_mut(&record.0))
+        {
+            Some(count) if *count > 0 => {
+                if all {
+                    *count -= 1;
+                }
+                true
+            }
+            _ => false,
+        };
+        let keeps = match self.op {
+            SetOperator::Union => true,
+            SetOperator::Intersect => matched,
+            SetOperator::Except => !matched,
+        };
+        keeps && (all || self.seen.insert(record.0.clone()))
+    }
+}
+
+// The records of both inputs are encoded with the attributes of the result, whose types can be
+// nullable when those of an input are not.
+fn encode(
+    record: TupleRecord,
+    record_schema: &QueryResultSchema,
+    schema: &QueryResultSchema,
+) -> StorageResult<TupleRecord> {
+    let values = record.to_values::<_, Vec<_>>(record_schema.attributes.attributes_iter())?;
+    Ok(serialize_record(
+        values.into_iter().map(|(_, value)| value).collect(),
+        &schema.attributes,
+    ))
+}
+
+impl NextTuple for SetOperation {
+    fn next(&mut self) -> TupleResult {
+        if self.right_counts.is_none() && self.op != SetOperator::Union {
+            match self.count_right() {
+                Ok(counts) => self.right_counts = Some(counts),
+                Err(err) => {
+                    self.right_counts = Some(HashMap::new());
+                    self.left_done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        loop {
+            match self.next_record()? {
+                Ok(record) => {
+                    if self.keeps(&record) {
+                        return Some(Ok(record));
+                    }
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::execution::set_operation::SetOperation;
+    use crate::execution::{NextTuple, ScanOperation, SubQueryTuples};
+    use crate::parser::ast::SetOperator;
+    use crate::planner::plan::query_plan::QueryResultSchema;
+    use crate::storage::storage_manager::{AttributeName, Attributes};
+    use crate::storage::tuple_serde::{serialize_record, serialize_tuple, StorageTupleValue};
+    use crate::storage::types::AttributeType;
+
+    #[test]
+    fn set_operations() {
+        let schema = QueryResultSchema::new(Attributes::new(vec![(
+            AttributeName("id".to_owned()),
+            AttributeType::Integer,
+        )]));
+        let nullable_schema = QueryResultSchema::new(Attributes::new(vec![(
+            AttributeName("id".to_owned()),
+            AttributeType::Integer.nullable(),
+        )]));
+        let row = |id: i32| serialize_tuple(vec![StorageTupleValue::Integer(id)]);
+        let nullable_row = |id: Option<i32>| {
+            serialize_record(
+                vec![id.map_or(StorageTupleValue::Null, StorageTupleValue::Integer)],
+                &nullable_schema.attributes,
+            )
+        };
+        // The left records are 1, 1, 1, 2 and 3, the right records are 1, 1, 3, 4 and NULL.
+        let run = |op: SetOperator, all: bool| {
+            let left = SubQueryTuples {
+                schema: schema.clone(),
+                tuples: Box::new(ScanOperation::new(vec![
+                    row(1),
+                    row(1),
+                    row(1),
+                    row(2),
+                    row(3),
+                ])),
+            };
+            let right = SubQueryTuples {
+                schema: nullable_schema.clone(),
+                tuples: Box::new(ScanOperation::new(vec![
+                    nullable_row(Some(1)),
+                    nullable_row(Some(1)),
+                    nullable_row(Some(3)),
+                    nullable_row(Some(4)),
+                    nullable_row(None),
+                ])),
+            };
+            let mut s = SetOperation::new(op, all, nullable_schema.clone(), left, right);
+            let mut items = Vec::new();
+            while let Some(item) = s.next() {
+                items.push(item.unwrap());
+            }
+            items
+        };
+        let rows = |ids: Vec<Option<i32>>| ids.into_iter().map(nullable_row).collect::<Vec<_>>();
+
+        assert_eq!(
+            run(SetOperator::Union, false),
+            rows(vec![Some(1), Some(2), Some(3), Some(4), None])
+        );
+        assert_eq!(run(SetOperator::Union, true).len(), 10);
+        assert_eq!(
+            run(SetOperator::Intersect, false),
+            rows(vec![Some(1), Some(3)])
+        );
+        assert_eq!(
+            run(SetOperator::Intersect, true),
+            rows(vec![Some(1), Some(1), Some(3)])
+        );
+        assert_eq!(run(SetOperator::Except, false), rows(vec![Some(2)]));
+        assert_eq!(run(SetOperator::Except, true), rows(vec![Some(1), Some(2)]));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::execution::expr_evaluation::evaluate_expr_with_ctx;
+use crate::execution::{NextTuple, ScanOperation, TupleResult};
+use crate::planner::plan::query_plan::{QueryResultSchema, SortKey};
+use crate::storage::error::Result as StorageResult;
+use crate::storage::tuple::TupleRecord;
+use crate::storage::tuple_serde::StorageTupleValue;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// Sorts all of its input the first time it is asked for a tuple. Records with equal sort keys
+// keep the order they came in.
+pub struct SortOperation {
+    pub record_schema: QueryResultSchema,
+    pub sort_keys: Vec<SortKey>,
+    pub input: Box<dyn NextTuple>,
+    sorted: Option<ScanOperation>,
+}
+
+impl NextTuple for SortOperation {
+    fn next(&mut self) -> TupleResult {
+        if self.sorted.is_none() {
+            match self.sort() {
+                Ok(records) => self.sorted = Some(ScanOperation::new(records)),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.sorted.as_mut().and_then(|sorted| sorted.next())
+    }
+}
+
+impl SortOperation {
+    pub fn new(
+        record_schema: QueryResultSchema,
+        sort_keys: Vec<SortKey>,
+        input: Box<dyn NextTuple>,
+    ) -> Self {
+        SortOperation {
+            record_schema,
+            sort_keys,
+            input,
+            sorted: None,
+        }
+    }
+
+    fn sort(&mut self) -> StorageResult<Vec<TupleRecord>> {
+        let mut keyed_records = Vec::new();
+        while let Some(record) = self.input.next() {
+            let record = record?;
+            let value_by_attr = record
+                .to_values::<_, HashMap<_, _>>(self.record_schema.attributes.attributes_iter())?;
+            let ctx = value_by_attr
+                .iter()
+                .map(|(attr_name, value)| (&attr_name.0, value))
+                .collect();
+            let types = self.record_schema.attributes.as_lookup_table();
+            let keys = self
+                .sort_keys
+                .iter()
+                .map(|sort_key| evaluate_expr_with_ctx(&sort_key.expr, &ctx, &types))
+                .collect::<StorageResult<Vec<_>>>()?;
+            keyed_records.push((keys, record));
+        }
+
+        let sort_keys = &self.sort_keys;
+        keyed_records.sort_by(|(left, _), (right, _)| compare_keys(sort_keys, left, right));
+        Ok(keyed_records
+            .into_iter()
+            .map(|(_keys, record)| record)
+            .collect())
+    }
+}
+
+fn compare_keys(
+    sort_keys: &[SortKey],
+    left: &[StorageTupleValue],
+    right: &[StorageTupleValue],
+) -> Ordering {
+    sort_keys
+        .iter()
+        .zip(left.iter().zip(right.iter()))
+        .map(|(sort_key, (left, right))| compare_values(sort_key, left, right))
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+// Where NULL goes does not depend on the direction of the sort. JSON null is a value like any
+// other and sorts before the other JSON values.
+fn compare_values(
+    sort_key: &SortKey,
+    left: &StorageTupleValue,
+    right: &StorageTupleValue,
+) -> Ordering {
+    let is_null = |value: &StorageTupleValue| *value == StorageTupleValue::Null;
+    let nulls = match sort_key.nulls_first {
+        true => Ordering::Less,
+        false => Ordering::Greater,
+    };
+    match (is_null(left), is_null(right)) {
+        (true, true) => Ordering::Equal,
+        (true, false) => nulls,
+        (false, true) => nulls.reverse(),
+        (false, false) if sort_key.descending => left.cmp(right).reverse(),
+        (false, false) => left.cmp(right),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::execution::sort::SortOperation;
+    use crate::execution::{NextTuple, ScanOperation};
+    use crate::parser::ast::{Expr, LiteralExpr};
+    use crate::planner::plan::query_plan::{QueryResultSchema, SortKey};
+    use crate::storage::json::JsonValue;
+    use crate::storage::storage_manager::{AttributeName, Attributes};
+    use crate::storage::tuple_serde::{deserialize_tuple, serialize_record, StorageTupleValue};
+    use crate::storage::types::AttributeType;
+
+    #[test]
+    fn sort() {
+        let schema = QueryResultSchema::new(Attributes::new(vec![
+            (AttributeName("name".to_owned()), AttributeType::Text),
+            (AttributeName("age".to_owned()), AttributeType::Integer),
+            (
+                AttributeName("data".to_owned()),
+                AttributeType::Nullable(Box::new(AttributeType::Json)),
+            ),
+        ]));
+        let row = |name: &str, age: i32, data: StorageTupleValue| {
+            serialize_record(
+                vec![
+                    StorageTupleValue::String(name.to_owned()),
+                    StorageTupleValue::Integer(age),
+                    data,
+                ],
+                &schema.attributes,
+            )
+        };
+        let sorted_names = |sort_keys: Vec<SortKey>| {
+            let input = ScanOperation::new(vec![
+                row("a", 10, StorageTupleValue::Null),
+                row("b", 12, StorageTupleValue::Json(JsonValue::Boolean(true))),
+                row("c", 10, StorageTupleValue::Json(JsonValue::Boolean(false))),
+                row("d", 9, StorageTupleValue::Json(JsonValue::Null)),
+            ]);
+            let mut s = SortOperation::new(schema.clone(), sort_keys, Box::new(input));
+            let mut names = Vec::new();
+            while let Some(tuple) = s.next() {
+                let values = deserialize_tuple(
+                    tuple.unwrap(),
+                    vec![
+                        AttributeType::Text,
+                        AttributeType::Integer,
+                        AttributeType::Nullable(Box::new(AttributeType::Json)),
+                    ],
+                );
+                match &values[0] {
+                    StorageTupleValue::String(name) => names.push(name.clone()),
+                    other => panic!("unexpected value {:?}", other),
+                }
+            }
+            names
+        };
+        let key = |name: &str, descending: bool, nulls_first: bool| SortKey {
+            expr: Expr::Literal(LiteralExpr::Identifier(name.to_owned())),
+            descending,
+            nulls_first,
+        };
+
+        assert_eq!(
+            sorted_names(vec![key("age", false, false)]),
+            vec!["d", "a", "c", "b"]
+        );
+        assert_eq!(
+            sorted_names(vec![key("age", true, true), key("name", true, true)]),
+            vec!["b", "c", "a", "d"]
+        );
+        // Only NULL follows NULLS FIRST and NULLS LAST, JSON null sorts before false.
+        assert_eq!(
+            sorted_names(vec![key("data", false, false), key("age", false, false)]),
+            vec!["d", "c", "b", "a"]
+        );
+        assert_eq!(
+            sorted_names(vec![key("data", true, false), key("age", false, false)]),
+            vec!["b", "c", "d", "a"]
+        );
+        assert_eq!(
+            sorted_names(vec![key("data", false, true), key("age", false, false)]),
+            vec!["a", "d", "c", "b"]
+        );
+    }
+}
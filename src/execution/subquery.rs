@@ -0,0 +1,252 @@
+use crate::execution::expr_evaluation::evaluate_expr_with_ctx;
+use crate::execution::{NextTuple, SubQueryTuples, TupleResult};
+use crate::parser::ast::SubqueryKind;
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::error::{Result as StorageResult, StorageError};
+use crate::storage::tuple::TupleRecord;
+use crate::storage::tuple_serde::{serialize_record, StorageTupleValue};
+use std::collections::HashMap;
+
+// Creates the operations of a subquery for a record of the query it is nested in.
+pub type CreateSubquery = Box<dyn Fn(&TupleRecord) -> SubQueryTuples>;
+
+// Appends the value of a subquery to each record of the input. A subquery that is not correlated
+// is only evaluated for the first record, its values are kept for the next records.
+pub struct SubqueryOperation {
+    kind: SubqueryKind,
+    correlated: bool,
+    schema: QueryResultSchema,
+    record_schema: QueryResultSchema,
+    query: CreateSubquery,
+    values: Option<Vec<StorageTupleValue>>,
+    input: Box<dyn NextTuple>,
+}
+
+impl SubqueryOperation {
+    pub fn new(
+        kind: SubqueryKind,
+        correlated: bool,
+        schema: QueryResultSchema,
+        record_schema: QueryResultSchema,
+        query: CreateSubquery,
+        input: Box<dyn NextTuple>,
+    ) -> Self {
+        SubqueryOperation {
+            kind,
+            correlated,
+            schema,
+            record_schema,
+            query,
+            values: None,
+            input,
+        }
+    }
+
+    // The first attribute of the records of the subquery. EXISTS only needs a record, and a
+    // scalar subquery fails when it has a second one.
+    fn query_values(&self, record: &TupleRecord) -> StorageResult<Vec<StorageTupleValue>> {
+        let limit = match self.kind {
+            SubqueryKind::Exists => 1,
+            SubqueryKind::Scalar => 2,
+            SubqueryKind::In(_) => usize::MAX,
+        };
+        let SubQueryTuples { schema, mut tuples } = (self.query)(record);
+        let mut values = Vec::new();
+        while values.len() < limit {
+            match tuples.next() {
+                Some(result) => {
+                    let query_record = result?;
+                    let query_values =
+                        query_record.to_values::<_, Vec<_>>(schema.attributes.attributes_iter())?;
+                    values.extend(query_values.into_iter().map(|(_, value)| value).take(1));
+                }
+                None => break,
+            }
+        }
+        Ok(values)
+    }
+
+    fn value(
+        &self,
+        record_values: &[(String, StorageTupleValue)],
+        values: &[StorageTupleValue],
+    ) -> StorageResult<StorageTupleValue> {
+        match &self.kind {
+            SubqueryKind::Exists => Ok(StorageTupleValue::Boolean(!values.is_empty())),
+            SubqueryKind::Scalar => match values {
+                [] => Ok(StorageTupleValue::Null),
+                [value] => Ok(value.clone()),
+                _ => Err(StorageError::CardinalityViolation(
+                    "more than one row returned by a subquery used as an expression".to_owned(),
+                )),
+            },
+            // NULL equals no value, so a NULL operand or value makes IN NULL when no value is
+            // equal to the operand.
+            SubqueryKind::In(expr) => {
+                let ctx = record_values
+                    .iter()
+                    .map(|(attr_name, value)| (attr_name, value))
+                    .collect::<HashMap<_, _>>();
+                let operand = evaluate_expr_with_ctx(
+                    expr,
+                    &ctx,
+                    &self.record_schema.attributes.as_lookup_table(),
+                )?;
+                Ok(if values.is_empty() {
+                    StorageTupleValue::Boolean(false)
+                } else if operand == StorageTupleValue::Null {
+                    StorageTupleValue::Null
+                } else if values.contains(&operand) {
+                    StorageTupleValue::Boolean(true)
+                } else if values.contains(&StorageTupleValue::Null) {
+                    StorageTupleValue::Null
+                } else {
+                    StorageTupleValue::Boolean(false)
+                })
+            }
+        }
+    }
+
+    fn append_value(&mut self, record: TupleRecord) -> StorageResult<TupleRecord> {
+        let values = match self.values.take() {
+            Some(values) => values,
+            None => self.query_values(&record)?,
+        };
+        let record_values = record
+            .to_values::<_, Vec<_>>(self.record_schema.attributes.attributes_iter())?
+            .into_iter()
+            .map(|(attr_name, value)| (attr_name.0, value))
+            .collect::<Vec<_>>();
+        let value = self.value(&record_values, &values)?;
+        if !self.correlated {
+            self.values = Some(values);
+        }
+
+        let mut values = record_values
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
+        values.push(value);
+        Ok(serialize_record(values, &self.schema.attributes))
+    }
+}
+
+impl NextTuple for SubqueryOperation {
+    fn next(&mut self) -> TupleResult {
+        self.input
+            .next()
+            .map(|result| result.and_then(|record| self.append_value(record)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::execution::subquery::SubqueryOperation;
+    use crate::execution::{NextTuple, ScanOperation, SubQueryTuples};
+    use crate::parser::ast::{Expr, LiteralExpr, SubqueryKind};
+    use crate::planner::plan::query_plan::QueryResultSchema;
+    use crate::storage::error::StorageError;
+    use crate::storage::storage_manager::{AttributeName, Attributes};
+    use crate::storage::tuple_serde::{serialize_record, StorageTupleValue};
+    use crate::storage::types::AttributeType;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn subqueries() {
+        let id = |name: &str| {
+            (
+                AttributeName(name.to_owned()),
+                AttributeType::Integer.nullable(),
+            )
+        };
+        let record_schema = QueryResultSchema::new(Attributes::new(vec![id("id")]));
+        let schema = |value_type: AttributeType| {
+            QueryResultSchema::new(Attributes::new(vec![
+                id("id"),
+                (AttributeName("$subquery1".to_owned()), value_type),
+            ]))
+        };
+        let rows = |schema: &QueryResultSchema, ids: &[Option<i32>]| {
+            ids.iter()
+                .map(|id| {
+                    serialize_record(
+                        vec![id.map_or(StorageTupleValue::Null, StorageTupleValue::Integer)],
+                        &schema.attributes,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        // The outer records are 1, 2 and NULL, the subquery returns its `values`, and counts how
+        // many times it is evaluated.
+        let run = |kind: SubqueryKind, correlated: bool, values: Vec<Option<i32>>| {
+            let evaluations = Rc::new(Cell::new(0));
+            let query_evaluations = evaluations.clone();
+            let query_schema = QueryResultSchema::new(Attributes::new(vec![id("value")]));
+            let value_type = match &kind {
+                SubqueryKind::Scalar => AttributeType::Integer.nullable(),
+                _ => AttributeType::Boolean.nullable(),
+            };
+            let schema = schema(value_type);
+            let mut operation = SubqueryOperation::new(
+                kind,
+                correlated,
+                schema.clone(),
+                record_schema.clone(),
+                Box::new(move |_| {
+                    query_evaluations.set(query_evaluations.get() + 1);
+                    SubQueryTuples {
+                        schema: query_schema.clone(),
+                        tuples: Box::new(ScanOperation::new(rows(&query_schema, &values))),
+                    }
+                }),
+                Box::new(ScanOperation::new(rows(
+                    &record_schema,
+                    &[Some(1), Some(2), None],
+                ))),
+            );
+            let mut results = Vec::new();
+            while let Some(result) = operation.next() {
+                results.push(result.map(|record| {
+                    record
+                        .to_values::<_, Vec<_>>(schema.attributes.attributes_iter())
+                        .unwrap()
+                        .pop()
+                        .unwrap()
+                        .1
+                }));
+            }
+            (results, evaluations.get())
+        };
+        let values = |values: Vec<StorageTupleValue>| {
+            values.into_iter().map(Ok).collect::<Vec<Result<_, _>>>()
+        };
+        let operand = || {
+            SubqueryKind::In(Box::new(Expr::Literal(LiteralExpr::Identifier(
+                "id".to_owned(),
+            ))))
+        };
+        use StorageTupleValue::{Boolean, Integer, Null};
+
+        let (results, evaluations) = run(SubqueryKind::Exists, false, vec![Some(1)]);
+        assert_eq!(results, values(vec![Boolean(true); 3]));
+        assert_eq!(evaluations, 1);
+        let (results, evaluations) = run(SubqueryKind::Exists, true, vec![]);
+        assert_eq!(results, values(vec![Boolean(false); 3]));
+        assert_eq!(evaluations, 3);
+
+        let (results, _) = run(SubqueryKind::Scalar, false, vec![Some(7)]);
+        assert_eq!(results, values(vec![Integer(7); 3]));
+        let (results, _) = run(SubqueryKind::Scalar, false, vec![]);
+        assert_eq!(results, values(vec![Null; 3]));
+        let (results, _) = run(SubqueryKind::Scalar, false, vec![Some(7), Some(8)]);
+        assert_matches!(results[0], Err(StorageError::CardinalityViolation(_)));
+
+        let (results, _) = run(operand(), false, vec![Some(1), Some(3)]);
+        assert_eq!(results, values(vec![Boolean(true), Boolean(false), Null]));
+        let (results, _) = run(operand(), false, vec![Some(1), None]);
+        assert_eq!(results, values(vec![Boolean(true), Null, Null]));
+        let (results, _) = run(operand(), false, vec![]);
+        assert_eq!(results, values(vec![Boolean(false); 3]));
+    }
+}
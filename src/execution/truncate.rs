@@ -0,0 +1,19 @@
+use crate::execution::EmptyResult;
+use crate::storage::storage_manager::{StorageManager, TableName};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct TruncateOperation {
+    pub table_names: Vec<TableName>,
+}
+
+impl TruncateOperation {
+    pub fn execute(self, storage_manager: &mut StorageManager) -> EmptyResult {
+        for table_name in &self.table_names {
+            storage_manager
+                .get_table_store(table_name)
+                .expect("[truncate plan] table storage no longer exists?")
+                .truncate();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::execution::expr_evaluation::{evaluate_expr_with_ctx, evaluate_predicate_with_ctx};
+use crate::execution::AffectedTuplesResult;
+use crate::parser::ast::Expr;
+use crate::planner::plan::query_plan::QueryResultSchema;
+use crate::storage::error::StorageError;
+use crate::storage::storage_manager::{AttributeName, StorageManager, TableName};
+use crate::storage::tuple_serde::{serialize_tuple, StorageTupleValue};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct UpdateOperation {
+    pub table_name: TableName,
+    pub schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub assignments: Vec<(AttributeName, Expr)>,
+    pub predicate: Option<Expr>,
+}
+
+impl UpdateOperation {
+    // All the new records are computed before any is written so that a failing row
+    // leaves the table untouched. The new keys must not be taken by the rows left as they are
+    // nor by another updated row.
+    pub fn execute(self, storage_manager: &mut StorageManager) -> AffectedTuplesResult {
+        let mut storage = storage_manager
+            .get_table_store(&self.table_name)
+            .expect("[update plan] table storage no longer exists?");
+
+        let types = self.schema.attributes.as_lookup_table();
+        let key_index = self
+            .schema
+            .attributes
+            .attributes_iter()
+            .position(|(attr_name, _)| attr_name == &self.primary_key)
+            .expect("[update plan] the primary key is an attribute of the table");
+        let mut keys = BTreeSet::new();
+        let mut updates = Vec::new();
+        for (id, record) in storage.scan() {
+            let values = record.to_values::<_, Vec<(AttributeName, StorageTupleValue)>>(
+                self.schema.attributes.attributes_iter(),
+            )?;
+            let ctx = values
+                .iter()
+                .map(|(attr_name, value)| (&attr_name.0, value))
+                .collect();
+
+            if let Some(predicate) = &self.predicate {
+                if !evaluate_predicate_with_ctx(predicate, &ctx, &types)? {
+                    keys.insert(values[key_index].1.clone());
+                    continue;
+                }
+            }
+
+            let mut updated_values = Vec::with_capacity(values.len());
+            for ((attr_name, attr_type), (_, value)) in
+                self.schema.attributes.attributes_iter().zip(&values)
+            {
+                let value = match self.assignments.iter().find(|(name, _)| name == attr_name) {
+                    Some((_, expr)) => attr_type
+                        .coerce(evaluate_expr_with_ctx(expr, &ctx, &types)?)
+                        .map_err(|err| {
+                            StorageError::InvalidValue(format!(
+                                "{} for attribute {:?}",
+                                err, attr_name.0
+                            ))
+                        })?,
+                    None => value.clone(),
+                };
+                updated_values.push(value);
+            }
+            updates.push((id.clone(), updated_values));
+        }
+
+        for (_, values) in &updates {
+            let key = &values[key_index];
+            if !keys.insert(key.clone()) {
+                return Err(StorageError::AlreadyExists(format!(
+                    "row with {} = {:?} in table {:?}",
+                    self.primary_key.0, key, self.table_name.0
+                )));
+            }
+        }
+
+        let mut updated = Vec::with_capacity(updates.len());
+        for (id, values) in updates {
+            let key = values[key_index].clone();
+            let record = serialize_tuple(values);
+            storage.update_tuple(&id, key, record.clone())?;
+            updated.push(record);
+        }
+
+        Ok(updated)
+    }
+}
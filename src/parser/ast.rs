@@ -1,8 +1,14 @@
 use crate::parser::lexer::token::Token;
+use std::fmt;
 
 pub enum Stmt {
     CreateTable(CreateTableStmt),
+    CreateType(CreateTypeStmt),
     Insert(InsertStmt),
+    Update(UpdateStmt),
+    Delete(DeleteStmt),
+    Truncate(TruncateStmt),
+    Merge(MergeStmt),
     Select(SelectStmt),
 }
 
@@ -24,6 +30,20 @@ pub struct AttributeDefinition {
 pub enum AttributeType {
     Integer,
     Text,
+    Varchar(u32),
+    Char(u32),
+    Binary,
+    Json,
+    Uuid,
+    // A user-defined type, such as an enum created with CREATE TYPE.
+    Named(String),
+}
+
+// Create Type
+#[derive(Debug, Eq, PartialEq)]
+pub struct CreateTypeStmt {
+    pub type_name: String,
+    pub labels: Vec<String>,
 }
 
 // Insert
@@ -31,7 +51,24 @@ pub enum AttributeType {
 pub struct InsertStmt {
     pub table_name: String,
     pub attribute_names: Vec<String>,
-    pub attribute_values: Vec<AttributeValue>,
+    // One list of values per inserted row.
+    pub rows: Vec<Vec<AttributeValue>>,
+    pub on_conflict: Option<OnConflict>,
+    pub returning: Option<SelectProperties>,
+}
+
+// What to do when an inserted row has the same key as an existing row.
+#[derive(Debug, Eq, PartialEq)]
+pub struct OnConflict {
+    pub target: Option<String>,
+    pub action: ConflictAction,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ConflictAction {
+    DoNothing,
+    // The proposed row can be referred to as `excluded`.
+    DoUpdate(Vec<Assignment>),
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -40,48 +77,219 @@ pub enum AttributeValue {
     Expr(Expr),
 }
 
-// Select
+// Update
 #[derive(Debug, Eq, PartialEq)]
-pub enum SelectStmt {
-    Select(SingleSelectStmt),
-    Join(JoinStmt),
+pub struct UpdateStmt {
+    pub table_name: String,
+    pub assignments: Vec<Assignment>,
+    pub where_clause: WhereClause,
+    pub returning: Option<SelectProperties>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
-pub struct SingleSelectStmt {
-    pub properties: SelectProperties,
-    pub from_clause: FromClause,
+pub struct Assignment {
+    pub attribute_name: String,
+    pub value: Expr,
+}
+
+// Delete
+#[derive(Debug, Eq, PartialEq)]
+pub struct DeleteStmt {
+    pub table_name: String,
     pub where_clause: WhereClause,
-    pub alias: Option<String>,
+    pub returning: Option<SelectProperties>,
 }
 
+// Truncate
 #[derive(Debug, Eq, PartialEq)]
-pub struct JoinStmt {
-    pub join_type: JoinType,
-    pub properties: SelectProperties,
-    pub left: SingleSelectStmt,
-    pub right: SingleSelectStmt,
+pub struct TruncateStmt {
+    pub table_names: Vec<String>,
+    pub restart_identity: bool,
+}
+
+// Merge
+#[derive(Debug, Eq, PartialEq)]
+pub struct MergeStmt {
+    pub target_table: String,
+    pub target_alias: Option<String>,
+    pub source: FromClause,
+    pub source_alias: Option<String>,
     pub predicate: WhereClause,
+    // Tried in order, the first clause whose condition holds applies to a row.
+    pub when_clauses: Vec<MergeWhenClause>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
-pub enum JoinType {
-    InnerJoin,
+pub struct MergeWhenClause {
+    pub matched: bool,
+    pub condition: Option<Expr>,
+    pub action: MergeAction,
 }
 
 #[derive(Debug, Eq, PartialEq)]
+pub enum MergeAction {
+    Update(Vec<Assignment>),
+    Delete,
+    // Without attribute names the values are given in schema order.
+    Insert {
+        attribute_names: Option<Vec<String>>,
+        values: Vec<Expr>,
+    },
+    DoNothing,
+}
+
+// Select
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SelectStmt {
+    Select(Box<SingleSelectStmt>),
+    SetOperation(SetOperationStmt),
+}
+
+// Combines the records of two queries, e.g. `select .. union all select ..`. The ORDER BY and
+// LIMIT clauses after the last query apply to the combined records.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SetOperationStmt {
+    pub op: SetOperator,
+    // Keeps duplicate records, otherwise the result has none.
+    pub all: bool,
+    pub left: Box<SelectStmt>,
+    pub right: Box<SelectStmt>,
+    pub order_by: Vec<OrderByExpr>,
+    pub limit: Option<LimitClause>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SetOperator {
+    // The records of either query.
+    Union,
+    // The records of the left query that are also records of the right query.
+    Intersect,
+    // The records of the left query that are not records of the right query.
+    Except,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SingleSelectStmt {
+    pub properties: SelectProperties,
+    pub from_clause: FromClause,
+    pub where_clause: WhereClause,
+    pub order_by: Vec<OrderByExpr>,
+    pub limit: Option<LimitClause>,
+    pub distinct: Option<Distinct>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum JoinType {
+    Inner,
+    // The outer joins also return the records of one or both sides that match no record of
+    // the other side, with NULL for the attributes of the other side.
+    Left,
+    Right,
+    Full,
+    // Joins every record of one side with every record of the other side, it has no condition.
+    Cross,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub enum SelectProperties {
     Star,
     Identifiers(Vec<String>),
+    Exprs(Vec<SelectItem>),
 }
 
-#[derive(Debug, Eq, PartialEq)]
+// An expression of a select list, the attribute it produces is named by its alias if it has one.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SelectItem {
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+impl SelectItem {
+    // Attributes are named by their alias, an attribute name, or the expression as SQL.
+    pub fn name(&self) -> String {
+        match (&self.alias, &self.expr) {
+            (Some(alias), _) => alias.clone(),
+            (None, Expr::Literal(LiteralExpr::Identifier(attr_name))) => attr_name.clone(),
+            (None, expr) => expr.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Distinct {
+    // Duplicate rows are dropped.
+    Rows,
+    // Only the first row of each set of rows for which the expressions are equal is kept.
+    On(Vec<Expr>),
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct OrderByExpr {
+    pub expr: Expr,
+    pub order: SortOrder,
+    // Defaults to nulls last in ascending order, and nulls first in descending order.
+    pub nulls: Option<NullsOrder>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+// `LIMIT count OFFSET offset`, or `OFFSET offset ROWS FETCH FIRST count ROWS ONLY`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LimitClause {
+    pub count: Option<u32>,
+    pub offset: u32,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub enum FromClause {
     Select(Box<SelectStmt>),
     Table(String),
+    Join(Box<JoinClause>),
 }
 
-#[derive(Debug, Eq, PartialEq)]
+// A join of two table references, either of which can be a join itself. A comma separated list
+// of table references is a chain of cross joins.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct JoinClause {
+    pub join_type: JoinType,
+    pub left: TableReference,
+    pub right: TableReference,
+    pub constraint: JoinConstraint,
+}
+
+// How the records of the sides of a join are matched.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum JoinConstraint {
+    On(Expr),
+    // `USING (a, b)` matches the records whose attributes of the same names are equal, and merges
+    // each pair of those attributes into a single attribute.
+    Using(Vec<String>),
+    // Like USING the names of the attributes both sides have.
+    Natural,
+    // Cross joins have no constraint.
+    None,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TableReference {
+    pub from_clause: FromClause,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub enum WhereClause {
     None,
     Expr(Expr),
@@ -99,6 +307,24 @@ pub enum BinaryOperation {
     GreaterThan,
     LessThanOrEqual,
     GreaterThanOrEqual,
+    JsonExtract,
+    JsonExtractText,
+    And,
+    Or,
+}
+
+impl BinaryOperation {
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Equal
+                | Self::NotEqual
+                | Self::LessThan
+                | Self::LessThanOrEqual
+                | Self::GreaterThan
+                | Self::GreaterThanOrEqual
+        )
+    }
 }
 
 impl From<Token> for BinaryOperation {
@@ -109,15 +335,42 @@ impl From<Token> for BinaryOperation {
             Token::Star => Self::Multiplication,
             Token::Slash => Self::Division,
             Token::Equal => Self::Equal,
+            Token::NotEqual => Self::NotEqual,
             Token::LessThan => Self::LessThan,
             Token::GreaterThan => Self::GreaterThan,
             Token::LessThanOrEqual => Self::LessThanOrEqual,
             Token::GreaterThanOrEqual => Self::GreaterThanOrEqual,
-            _ => unreachable!(format!("[{}] is not a binary operation!", t)),
+            Token::Arrow => Self::JsonExtract,
+            Token::LongArrow => Self::JsonExtractText,
+            Token::And => Self::And,
+            Token::Or => Self::Or,
+            _ => unreachable!("[{}] is not a binary operation!", t),
         }
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum UnaryOperation {
+    Not,
+    Negation,
+}
+
+impl From<Token> for UnaryOperation {
+    fn from(t: Token) -> Self {
+        match t {
+            Token::Not => Self::Not,
+            Token::Minus => Self::Negation,
+            _ => unreachable!("[{}] is not a unary operation!", t),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct UnaryExpr {
+    pub op: UnaryOperation,
+    pub operand: Box<Expr>,
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct BinaryExpr {
     pub left: Box<Expr>,
@@ -130,11 +383,194 @@ pub enum LiteralExpr {
     Integer(i32),
     Boolean(bool),
     String(String),
+    Binary(Vec<u8>),
     Identifier(String),
 }
 
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct FunctionCall {
+    pub name: String,
+    pub args: Vec<Expr>,
+}
+
+// An aggregate function over the records of a group, `COUNT(*)` has no argument.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AggregateCall {
+    pub function: AggregateFunction,
+    pub arg: Option<Box<Expr>>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "count" => Some(Self::Count),
+            "sum" => Some(Self::Sum),
+            "avg" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Expr {
     Binary(BinaryExpr),
+    Unary(UnaryExpr),
     Literal(LiteralExpr),
+    Function(FunctionCall),
+    Aggregate(AggregateCall),
+    Subquery(SubqueryExpr),
+}
+
+// A query nested in an expression, it can refer to the attributes of the records of the query
+// it is nested in.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SubqueryExpr {
+    pub kind: SubqueryKind,
+    pub query: Box<SelectStmt>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SubqueryKind {
+    // `(select ..)` is the single attribute of the single record of the query, or NULL when the
+    // query has no record.
+    Scalar,
+    // `EXISTS (select ..)` is whether the query has any record.
+    Exists,
+    // `expr IN (select ..)` is whether the expression equals the single attribute of a record of
+    // the query, `expr NOT IN (select ..)` is its negation.
+    In(Box<Expr>),
+}
+
+impl Expr {
+    pub fn contains_aggregate(&self) -> bool {
+        match self {
+            Self::Binary(expr) => expr.left.contains_aggregate() || expr.right.contains_aggregate(),
+            Self::Unary(expr) => expr.operand.contains_aggregate(),
+            Self::Literal(_) => false,
+            Self::Function(call) => call.args.iter().any(|arg| arg.contains_aggregate()),
+            Self::Aggregate(_) => true,
+            // The aggregates of a subquery are computed over the records of the subquery.
+            Self::Subquery(subquery) => match &subquery.kind {
+                SubqueryKind::In(expr) => expr.contains_aggregate(),
+                SubqueryKind::Scalar | SubqueryKind::Exists => false,
+            },
+        }
+    }
+
+    pub fn contains_subquery(&self) -> bool {
+        match self {
+            Self::Binary(expr) => expr.left.contains_subquery() || expr.right.contains_subquery(),
+            Self::Unary(expr) => expr.operand.contains_subquery(),
+            Self::Literal(_) => false,
+            Self::Function(call) => call.args.iter().any(|arg| arg.contains_subquery()),
+            Self::Aggregate(call) => call.arg.iter().any(|arg| arg.contains_subquery()),
+            Self::Subquery(_) => true,
+        }
+    }
+}
+
+// Expressions are displayed as SQL, this is used to name computed attributes.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary(expr) => write!(f, "{} {} {}", expr.left, expr.op, expr.right),
+            Self::Unary(expr) => match expr.op {
+                UnaryOperation::Not => write!(f, "{} {}", expr.op, expr.operand),
+                UnaryOperation::Negation => write!(f, "{}{}", expr.op, expr.operand),
+            },
+            Self::Literal(literal) => write!(f, "{}", literal),
+            Self::Function(call) => {
+                write!(f, "{}(", call.name)?;
+                for (i, arg) in call.args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+            Self::Aggregate(call) => match &call.arg {
+                Some(arg) => write!(f, "{}({})", call.function, arg),
+                None => write!(f, "{}(*)", call.function),
+            },
+            Self::Subquery(subquery) => match &subquery.kind {
+                SubqueryKind::Scalar => write!(f, "(subquery)"),
+                SubqueryKind::Exists => write!(f, "EXISTS (subquery)"),
+                SubqueryKind::In(expr) => write!(f, "{} IN (subquery)", expr),
+            },
+        }
+    }
+}
+
+impl fmt::Display for AggregateFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for LiteralExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Boolean(value) => write!(f, "{}", value),
+            Self::String(value) => write!(f, "'{}'", value),
+            Self::Binary(value) => {
+                write!(f, "x'")?;
+                for byte in value {
+                    write!(f, "{:02X}", byte)?;
+                }
+                write!(f, "'")
+            }
+            Self::Identifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl fmt::Display for BinaryOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Self::Addition => "+",
+            Self::Subtraction => "-",
+            Self::Multiplication => "*",
+            Self::Division => "/",
+            Self::Equal => "=",
+            Self::NotEqual => "!=",
+            Self::LessThan => "<",
+            Self::GreaterThan => ">",
+            Self::LessThanOrEqual => "<=",
+            Self::GreaterThanOrEqual => ">=",
+            Self::JsonExtract => "->",
+            Self::JsonExtractText => "->>",
+            Self::And => "AND",
+            Self::Or => "OR",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+impl fmt::Display for UnaryOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Not => write!(f, "NOT"),
+            Self::Negation => write!(f, "-"),
+        }
+    }
 }
@@ -2,18 +2,67 @@ use crate::parser::ast::*;
 use crate::parser::lexer::token::Token;
 use crate::parser::parse::ParseHelper;
 use crate::parser::parse::{Input, ParseError};
+use crate::parser::query_parser::Parser as QueryParser;
+use std::convert::TryFrom;
 
 pub struct Parser {}
 type Result<T> = std::result::Result<T, ParseError>;
 
 impl Parser {
     pub fn expr(input: &mut Input) -> Result<Expr> {
+        // or -> or OR and | and
+        // and -> and AND not | not
+        // not -> NOT not | l0
         // l0 -> l0 == != l1 | l1
-        // l1 -> l1 < > <= >= l2 | l2
-        // l2 -> l2 +- l3 | l3
-        // l3 -> l3 */ l4 | l4
-        // l4 -> (l0) | identifier | number | true | false | -l0 | !l0
-        Parser::l0_expr(input)
+        // l1 -> l1 < > <= >= in | in
+        // in -> l2 IN (select) | l2 NOT IN (select) | l2
+        // l2 -> l2 -> ->> l3 | l3
+        // l3 -> l3 +- l4 | l4
+        // l4 -> l4 */ l5 | l5
+        // l5 -> (or) | (select) | EXISTS (select) | identifier | identifier(or, ...)
+        //       | aggregate(or) | COUNT(*) | number | string | binary | true | false | -l5
+        Parser::or_expr(input)
+    }
+
+    pub fn or_expr(input: &mut Input) -> Result<Expr> {
+        let mut curr = Parser::and_expr(input)?;
+
+        while let Some(&Token::Or) = input.peek() {
+            let tok = input.next().unwrap();
+            curr = Expr::Binary(BinaryExpr {
+                left: Box::new(curr),
+                op: BinaryOperation::from(tok.clone()),
+                right: Box::new(Parser::and_expr(input)?),
+            });
+        }
+        Ok(curr)
+    }
+
+    pub fn and_expr(input: &mut Input) -> Result<Expr> {
+        let mut curr = Parser::not_expr(input)?;
+
+        while let Some(&Token::And) = input.peek() {
+            let tok = input.next().unwrap();
+            curr = Expr::Binary(BinaryExpr {
+                left: Box::new(curr),
+                op: BinaryOperation::from(tok.clone()),
+                right: Box::new(Parser::not_expr(input)?),
+            });
+        }
+        Ok(curr)
+    }
+
+    pub fn not_expr(input: &mut Input) -> Result<Expr> {
+        match input.peek() {
+            Some(&Token::Not) => {
+                let tok = input.next().unwrap();
+                Ok(Expr::Unary(UnaryExpr {
+                    op: UnaryOperation::from(tok.clone()),
+                    operand: Box::new(Parser::not_expr(input)?),
+                }))
+            }
+            _ => Parser::l0_expr(input),
+        }
     }
 
     pub fn l0_expr(input: &mut Input) -> Result<Expr> {
@@ -31,7 +80,7 @@ impl Parser {
     }
 
     pub fn l1_expr(input: &mut Input) -> Result<Expr> {
-        let mut curr = Parser::l2_expr(input)?;
+        let mut curr = Parser::in_expr(input)?;
 
         while let Some(&Token::LessThan)
         | Some(&Token::GreaterThan)
@@ -42,16 +91,38 @@ impl Parser {
             curr = Expr::Binary(BinaryExpr {
                 left: Box::new(curr),
                 op: BinaryOperation::from(tok.clone()),
-                right: Box::new(Parser::l2_expr(input)?),
+                right: Box::new(Parser::in_expr(input)?),
             });
         }
         Ok(curr)
     }
 
+    pub fn in_expr(input: &mut Input) -> Result<Expr> {
+        let expr = Parser::l2_expr(input)?;
+        let negated = match input.peek() {
+            Some(&Token::KeywordIn) => false,
+            Some(&Token::KeywordNotIn) => true,
+            _ => return Ok(expr),
+        };
+        let _in = input.next();
+        ParseHelper::match_token(Token::LeftParen, input.next())?;
+        let expr = Expr::Subquery(SubqueryExpr {
+            kind: SubqueryKind::In(Box::new(expr)),
+            query: Parser::subquery(input)?,
+        });
+        match negated {
+            true => Ok(Expr::Unary(UnaryExpr {
+                op: UnaryOperation::Not,
+                operand: Box::new(expr),
+            })),
+            false => Ok(expr),
+        }
+    }
+
     pub fn l2_expr(input: &mut Input) -> Result<Expr> {
         let mut curr = Parser::l3_expr(input)?;
 
-        while let Some(&Token::Plus) | Some(&Token::Minus) = input.peek() {
+        while let Some(&Token::Arrow) | Some(&Token::LongArrow) = input.peek() {
             let tok = input.next().unwrap();
             curr = Expr::Binary(BinaryExpr {
                 left: Box::new(curr),
@@ -65,7 +136,7 @@ impl Parser {
     pub fn l3_expr(input: &mut Input) -> Result<Expr> {
         let mut curr = Parser::l4_expr(input)?;
 
-        while let Some(&Token::Star) | Some(&Token::Slash) = input.peek() {
+        while let Some(&Token::Plus) | Some(&Token::Minus) = input.peek() {
             let tok = input.next().unwrap();
             curr = Expr::Binary(BinaryExpr {
                 left: Box::new(curr),
@@ -77,15 +148,61 @@ impl Parser {
     }
 
     pub fn l4_expr(input: &mut Input) -> Result<Expr> {
+        let mut curr = Parser::l5_expr(input)?;
+
+        while let Some(&Token::Star) | Some(&Token::Slash) = input.peek() {
+            let tok = input.next().unwrap();
+            curr = Expr::Binary(BinaryExpr {
+                left: Box::new(curr),
+                op: BinaryOperation::from(tok.clone()),
+                right: Box::new(Parser::l5_expr(input)?),
+            });
+        }
+        Ok(curr)
+    }
+
+    pub fn l5_expr(input: &mut Input) -> Result<Expr> {
         match input.next() {
+            Some(Token::Identifier(id)) if input.peek() == Some(&Token::LeftParen) => {
+                match AggregateFunction::from_name(&id) {
+                    Some(function) => Ok(Expr::Aggregate(Parser::aggregate_call(function, input)?)),
+                    None => Ok(Expr::Function(Parser::function_call(id, input)?)),
+                }
+            }
             Some(Token::Identifier(id)) => {
                 Ok(Expr::Literal(LiteralExpr::Identifier(id.to_owned())))
             }
-            Some(Token::Integer(num)) => Ok(Expr::Literal(LiteralExpr::Integer(num))),
+            Some(Token::Integer(num)) => Parser::integer_literal(i64::from(num)),
+            // A negated number is a literal, which is the only way to write i32::MIN.
+            Some(Token::Minus) => match input.peek() {
+                Some(&Token::Integer(num)) => {
+                    let _num = input.next();
+                    Parser::integer_literal(-i64::from(num))
+                }
+                _ => Ok(Expr::Unary(UnaryExpr {
+                    op: UnaryOperation::Negation,
+                    operand: Box::new(Parser::l5_expr(input)?),
+                })),
+            },
+            Some(Token::StringLiteral(text)) => Ok(Expr::Literal(LiteralExpr::String(text))),
+            Some(Token::BinaryLiteral(bytes)) => Ok(Expr::Literal(LiteralExpr::Binary(bytes))),
             Some(Token::True) => Ok(Expr::Literal(LiteralExpr::Boolean(true))),
             Some(Token::False) => Ok(Expr::Literal(LiteralExpr::Boolean(false))),
+            Some(Token::LeftParen) if input.peek() == Some(&Token::Select) => {
+                Ok(Expr::Subquery(SubqueryExpr {
+                    kind: SubqueryKind::Scalar,
+                    query: Parser::subquery(input)?,
+                }))
+            }
+            Some(Token::KeywordExists) => {
+                ParseHelper::match_token(Token::LeftParen, input.next())?;
+                Ok(Expr::Subquery(SubqueryExpr {
+                    kind: SubqueryKind::Exists,
+                    query: Parser::subquery(input)?,
+                }))
+            }
             Some(Token::LeftParen) => {
-                let expr = Parser::l0_expr(input)?;
+                let expr = Parser::or_expr(input)?;
                 let _ = ParseHelper::match_token(Token::RightParen, input.next());
                 Ok(expr)
             }
@@ -98,6 +215,61 @@ impl Parser {
             ))),
         }
     }
+
+    // A query followed by a closing parenthesis, it's parsed by the query parser which takes the
+    // tokens by value.
+    fn subquery(input: &mut Input) -> Result<Box<SelectStmt>> {
+        let tokens = std::mem::replace(input, Input::new(vec![]));
+        let (query, mut rest) = QueryParser::new().select_stmt(tokens, false)?;
+        ParseHelper::match_token(Token::RightParen, rest.next())?;
+        *input = rest;
+        Ok(Box::new(query))
+    }
+
+    fn integer_literal(value: i64) -> Result<Expr> {
+        match i32::try_from(value) {
+            Ok(value) => Ok(Expr::Literal(LiteralExpr::Integer(value))),
+            Err(_) => Err(ParseError {
+                details: format!("integer {} is out of range", value),
+            }),
+        }
+    }
+
+    fn aggregate_call(function: AggregateFunction, input: &mut Input) -> Result<AggregateCall> {
+        ParseHelper::match_token(Token::LeftParen, input.next())?;
+        let arg = match input.peek() {
+            Some(&Token::Star) if function == AggregateFunction::Count => {
+                let _star = input.next();
+                None
+            }
+            _ => Some(Box::new(Parser::or_expr(input)?)),
+        };
+        ParseHelper::match_token(Token::RightParen, input.next())?;
+
+        Ok(AggregateCall { function, arg })
+    }
+
+    fn function_call(name: String, input: &mut Input) -> Result<FunctionCall> {
+        ParseHelper::match_token(Token::LeftParen, input.next())?;
+        let mut args = Vec::new();
+        if input.peek() != Some(&Token::RightParen) {
+            loop {
+                args.push(Parser::or_expr(input)?);
+                match input.peek() {
+                    Some(&Token::Comma) => {
+                        let _comma = input.next();
+                    }
+                    _ => break,
+                }
+            }
+        }
+        ParseHelper::match_token(Token::RightParen, input.next())?;
+
+        Ok(FunctionCall {
+            name: name.to_lowercase(),
+            args,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -105,6 +277,88 @@ mod test {
     use super::*;
     use crate::parser::ast::Expr;
 
+    #[test]
+    fn parse_precedence_boolean() -> Result<()> {
+        // not a = 1 or b and c != 2
+        let mut input = Input::new(vec![
+            Token::Not,
+            Token::Identifier("a".to_owned()),
+            Token::Equal,
+            Token::Integer(1),
+            Token::Or,
+            Token::Identifier("b".to_owned()),
+            Token::And,
+            Token::Identifier("c".to_owned()),
+            Token::NotEqual,
+            Token::Integer(2),
+        ]);
+
+        let e = Parser::expr(&mut input)?;
+        assert_eq!(
+            e,
+            Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Unary(UnaryExpr {
+                    op: UnaryOperation::Not,
+                    operand: Box::new(Expr::Binary(BinaryExpr {
+                        left: Box::new(Expr::Literal(LiteralExpr::Identifier("a".to_owned()))),
+                        op: BinaryOperation::Equal,
+                        right: Box::new(Expr::Literal(LiteralExpr::Integer(1))),
+                    })),
+                })),
+                op: BinaryOperation::Or,
+                right: Box::new(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Literal(LiteralExpr::Identifier("b".to_owned()))),
+                    op: BinaryOperation::And,
+                    right: Box::new(Expr::Binary(BinaryExpr {
+                        left: Box::new(Expr::Literal(LiteralExpr::Identifier("c".to_owned()))),
+                        op: BinaryOperation::NotEqual,
+                        right: Box::new(Expr::Literal(LiteralExpr::Integer(2))),
+                    })),
+                })),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_boolean_parenthesis() -> Result<()> {
+        // (a or b) and not not c
+        let mut input = Input::new(vec![
+            Token::LeftParen,
+            Token::Identifier("a".to_owned()),
+            Token::Or,
+            Token::Identifier("b".to_owned()),
+            Token::RightParen,
+            Token::And,
+            Token::Not,
+            Token::Not,
+            Token::Identifier("c".to_owned()),
+        ]);
+
+        let e = Parser::expr(&mut input)?;
+        assert_eq!(
+            e,
+            Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Literal(LiteralExpr::Identifier("a".to_owned()))),
+                    op: BinaryOperation::Or,
+                    right: Box::new(Expr::Literal(LiteralExpr::Identifier("b".to_owned()))),
+                })),
+                op: BinaryOperation::And,
+                right: Box::new(Expr::Unary(UnaryExpr {
+                    op: UnaryOperation::Not,
+                    operand: Box::new(Expr::Unary(UnaryExpr {
+                        op: UnaryOperation::Not,
+                        operand: Box::new(Expr::Literal(LiteralExpr::Identifier("c".to_owned()))),
+                    })),
+                })),
+            })
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn parse_simple_addition() -> Result<()> {
         let mut input = Input::new(vec![Token::Integer(1), Token::Plus, Token::Integer(2)]);
@@ -248,4 +502,274 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn parse_json_access_precedence() -> Result<()> {
+        // data -> 'a' ->> 'b' = 'c'
+        let mut input = Input::new(vec![
+            Token::Identifier("data".to_owned()),
+            Token::Arrow,
+            Token::StringLiteral("a".to_owned()),
+            Token::LongArrow,
+            Token::StringLiteral("b".to_owned()),
+            Token::Equal,
+            Token::StringLiteral("c".to_owned()),
+        ]);
+
+        let e = Parser::expr(&mut input)?;
+        assert_eq!(
+            e,
+            Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Binary(BinaryExpr {
+                        left: Box::new(Expr::Literal(LiteralExpr::Identifier("data".to_owned()))),
+                        op: BinaryOperation::JsonExtract,
+                        right: Box::new(Expr::Literal(LiteralExpr::String("a".to_owned()))),
+                    })),
+                    op: BinaryOperation::JsonExtractText,
+                    right: Box::new(Expr::Literal(LiteralExpr::String("b".to_owned()))),
+                })),
+                op: BinaryOperation::Equal,
+                right: Box::new(Expr::Literal(LiteralExpr::String("c".to_owned()))),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_aggregate() -> Result<()> {
+        // count(*) + sum(a)
+        let mut input = Input::new(vec![
+            Token::Identifier("count".to_owned()),
+            Token::LeftParen,
+            Token::Star,
+            Token::RightParen,
+            Token::Plus,
+            Token::Identifier("sum".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("a".to_owned()),
+            Token::RightParen,
+        ]);
+
+        let e = Parser::expr(&mut input)?;
+        assert_eq!(
+            e,
+            Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Aggregate(AggregateCall {
+                    function: AggregateFunction::Count,
+                    arg: None,
+                })),
+                op: BinaryOperation::Addition,
+                right: Box::new(Expr::Aggregate(AggregateCall {
+                    function: AggregateFunction::Sum,
+                    arg: Some(Box::new(Expr::Literal(LiteralExpr::Identifier(
+                        "a".to_owned()
+                    )))),
+                })),
+            })
+        );
+        assert_eq!(e.to_string(), "count(*) + sum(a)");
+
+        // sum(*)
+        let mut input = Input::new(vec![
+            Token::Identifier("sum".to_owned()),
+            Token::LeftParen,
+            Token::Star,
+            Token::RightParen,
+        ]);
+        assert!(Parser::expr(&mut input).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_negation() -> Result<()> {
+        // 2 * -a - -3
+        let mut input = Input::new(vec![
+            Token::Integer(2),
+            Token::Star,
+            Token::Minus,
+            Token::Identifier("a".to_owned()),
+            Token::Minus,
+            Token::Minus,
+            Token::Integer(3),
+        ]);
+
+        let e = Parser::expr(&mut input)?;
+        assert_eq!(
+            e,
+            Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Literal(LiteralExpr::Integer(2))),
+                    op: BinaryOperation::Multiplication,
+                    right: Box::new(Expr::Unary(UnaryExpr {
+                        op: UnaryOperation::Negation,
+                        operand: Box::new(Expr::Literal(LiteralExpr::Identifier("a".to_owned()))),
+                    })),
+                })),
+                op: BinaryOperation::Subtraction,
+                right: Box::new(Expr::Literal(LiteralExpr::Integer(-3))),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_integer_range() -> Result<()> {
+        let mut input = Input::new(vec![Token::Minus, Token::Integer(2147483648)]);
+        assert_eq!(
+            Parser::expr(&mut input)?,
+            Expr::Literal(LiteralExpr::Integer(i32::MIN))
+        );
+
+        let mut input = Input::new(vec![Token::Integer(2147483648)]);
+        assert!(Parser::expr(&mut input).is_err());
+
+        let mut input = Input::new(vec![Token::Minus, Token::Integer(2147483649)]);
+        assert!(Parser::expr(&mut input).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_function_call() -> Result<()> {
+        // JSON_EXTRACT(data, '$.a') + f()
+        let mut input = Input::new(vec![
+            Token::Identifier("JSON_EXTRACT".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("data".to_owned()),
+            Token::Comma,
+            Token::StringLiteral("$.a".to_owned()),
+            Token::RightParen,
+            Token::Plus,
+            Token::Identifier("f".to_owned()),
+            Token::LeftParen,
+            Token::RightParen,
+        ]);
+
+        let e = Parser::expr(&mut input)?;
+        assert_eq!(
+            e,
+            Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Function(FunctionCall {
+                    name: "json_extract".to_owned(),
+                    args: vec![
+                        Expr::Literal(LiteralExpr::Identifier("data".to_owned())),
+                        Expr::Literal(LiteralExpr::String("$.a".to_owned())),
+                    ],
+                })),
+                op: BinaryOperation::Addition,
+                right: Box::new(Expr::Function(FunctionCall {
+                    name: "f".to_owned(),
+                    args: vec![],
+                })),
+            })
+        );
+
+        let mut input = Input::new(vec![
+            Token::Identifier("f".to_owned()),
+            Token::LeftParen,
+            Token::Integer(1),
+            Token::Comma,
+            Token::RightParen,
+        ]);
+        assert!(Parser::expr(&mut input).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_subqueries() -> Result<()> {
+        // a + 1 not in (select id from t) or exists (select * from t) and (select id from t) > 1
+        let select_from = |properties: Token| {
+            vec![
+                Token::LeftParen,
+                Token::Select,
+                properties,
+                Token::From,
+                Token::Identifier("t".to_owned()),
+                Token::RightParen,
+            ]
+        };
+        let mut input = Input::new(
+            [
+                vec![
+                    Token::Identifier("a".to_owned()),
+                    Token::Plus,
+                    Token::Integer(1),
+                    Token::KeywordNotIn,
+                ],
+                select_from(Token::Identifier("id".to_owned())),
+                vec![Token::Or, Token::KeywordExists],
+                select_from(Token::Star),
+                vec![Token::And],
+                select_from(Token::Identifier("id".to_owned())),
+                vec![Token::GreaterThan, Token::Integer(1)],
+            ]
+            .concat(),
+        );
+
+        let query = |properties: SelectProperties| {
+            Box::new(SelectStmt::Select(Box::new(SingleSelectStmt {
+                properties,
+                from_clause: FromClause::Table("t".to_owned()),
+                where_clause: WhereClause::None,
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: None,
+            })))
+        };
+        let ids = || SelectProperties::Identifiers(vec!["id".to_owned()]);
+
+        let e = Parser::expr(&mut input)?;
+        assert_eq!(
+            e,
+            Expr::Binary(BinaryExpr {
+                left: Box::new(Expr::Unary(UnaryExpr {
+                    op: UnaryOperation::Not,
+                    operand: Box::new(Expr::Subquery(SubqueryExpr {
+                        kind: SubqueryKind::In(Box::new(Expr::Binary(BinaryExpr {
+                            left: Box::new(Expr::Literal(LiteralExpr::Identifier("a".to_owned()))),
+                            op: BinaryOperation::Addition,
+                            right: Box::new(Expr::Literal(LiteralExpr::Integer(1))),
+                        }))),
+                        query: query(ids()),
+                    })),
+                })),
+                op: BinaryOperation::Or,
+                right: Box::new(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Subquery(SubqueryExpr {
+                        kind: SubqueryKind::Exists,
+                        query: query(SelectProperties::Star),
+                    })),
+                    op: BinaryOperation::And,
+                    right: Box::new(Expr::Binary(BinaryExpr {
+                        left: Box::new(Expr::Subquery(SubqueryExpr {
+                            kind: SubqueryKind::Scalar,
+                            query: query(ids()),
+                        })),
+                        op: BinaryOperation::GreaterThan,
+                        right: Box::new(Expr::Literal(LiteralExpr::Integer(1))),
+                    })),
+                })),
+            })
+        );
+        assert!(input.next().is_none());
+
+        let mut input = Input::new(vec![
+            Token::Identifier("a".to_owned()),
+            Token::KeywordIn,
+            Token::LeftParen,
+            Token::Integer(1),
+            Token::RightParen,
+        ]);
+        assert!(Parser::expr(&mut input).is_err());
+
+        Ok(())
+    }
 }
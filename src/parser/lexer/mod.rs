@@ -35,17 +35,55 @@ impl Lexer {
         {
             keywords.insert("create", Token::Create);
             keywords.insert("table", Token::Table);
+            keywords.insert("type", Token::Type);
             keywords.insert("insert", Token::Insert);
+            keywords.insert("update", Token::Update);
+            keywords.insert("delete", Token::Delete);
+            keywords.insert("truncate", Token::Truncate);
+            keywords.insert("merge", Token::Merge);
             keywords.insert("select", Token::Select);
+            keywords.insert("distinct", Token::Distinct);
             keywords.insert("from", Token::From);
             keywords.insert("where", Token::Where);
             keywords.insert("integer", Token::KeywordInteger);
             keywords.insert("varchar", Token::KeywordVarchar);
+            keywords.insert("char", Token::KeywordChar);
+            keywords.insert("blob", Token::KeywordBlob);
+            keywords.insert("bytea", Token::KeywordBlob);
+            keywords.insert("json", Token::KeywordJson);
+            keywords.insert("uuid", Token::KeywordUuid);
+            keywords.insert("enum", Token::KeywordEnum);
             keywords.insert("into", Token::KeywordInto);
             keywords.insert("values", Token::KeywordValues);
+            keywords.insert("set", Token::KeywordSet);
+            keywords.insert("returning", Token::KeywordReturning);
+            keywords.insert("using", Token::KeywordUsing);
+            keywords.insert("when", Token::KeywordWhen);
+            keywords.insert("matched", Token::KeywordMatched);
+            keywords.insert("then", Token::KeywordThen);
+            keywords.insert("having", Token::KeywordHaving);
+            keywords.insert("asc", Token::KeywordAsc);
+            keywords.insert("desc", Token::KeywordDesc);
+            keywords.insert("limit", Token::KeywordLimit);
+            keywords.insert("offset", Token::KeywordOffset);
+            keywords.insert("row", Token::KeywordRows);
+            keywords.insert("rows", Token::KeywordRows);
+            keywords.insert("only", Token::KeywordOnly);
+            keywords.insert("and", Token::And);
+            keywords.insert("or", Token::Or);
+            keywords.insert("not", Token::Not);
             keywords.insert("as", Token::KeywordAs);
             keywords.insert("on", Token::KeywordOn);
+            keywords.insert("do", Token::KeywordDo);
             keywords.insert("inner join", Token::KeywordOn);
+            keywords.insert("join", Token::KeywordInnerJoin);
+            keywords.insert("natural", Token::KeywordNatural);
+            keywords.insert("union", Token::KeywordUnion);
+            keywords.insert("intersect", Token::KeywordIntersect);
+            keywords.insert("except", Token::KeywordExcept);
+            keywords.insert("all", Token::KeywordAll);
+            keywords.insert("in", Token::KeywordIn);
+            keywords.insert("exists", Token::KeywordExists);
             keywords.insert("true", Token::True);
             keywords.insert("false", Token::False);
         }
@@ -53,6 +91,22 @@ impl Lexer {
         {
             double_word_keywords.insert(("primary", "key"), Token::KeywordPrimaryKey);
             double_word_keywords.insert(("inner", "join"), Token::KeywordInnerJoin);
+            double_word_keywords.insert(("left", "join"), Token::KeywordLeftJoin);
+            double_word_keywords.insert(("left", "outer join"), Token::KeywordLeftJoin);
+            double_word_keywords.insert(("right", "join"), Token::KeywordRightJoin);
+            double_word_keywords.insert(("right", "outer join"), Token::KeywordRightJoin);
+            double_word_keywords.insert(("full", "join"), Token::KeywordFullJoin);
+            double_word_keywords.insert(("full", "outer join"), Token::KeywordFullJoin);
+            double_word_keywords.insert(("cross", "join"), Token::KeywordCrossJoin);
+            double_word_keywords.insert(("restart", "identity"), Token::KeywordRestartIdentity);
+            double_word_keywords.insert(("not", "matched"), Token::KeywordNotMatched);
+            double_word_keywords.insert(("not", "in"), Token::KeywordNotIn);
+            double_word_keywords.insert(("group", "by"), Token::KeywordGroupBy);
+            double_word_keywords.insert(("order", "by"), Token::KeywordOrderBy);
+            double_word_keywords.insert(("nulls", "first"), Token::KeywordNullsFirst);
+            double_word_keywords.insert(("nulls", "last"), Token::KeywordNullsLast);
+            double_word_keywords.insert(("fetch", "first"), Token::KeywordFetchFirst);
+            double_word_keywords.insert(("fetch", "next"), Token::KeywordFetchFirst);
         }
         Lexer {
             keywords,
@@ -85,6 +139,13 @@ impl Lexer {
             details: "unexpected EOF".to_string(),
         })?;
 
+        if input.starts_with("->>") {
+            return Ok((Token::LongArrow, 3));
+        }
+        if input.starts_with("->") {
+            return Ok((Token::Arrow, 2));
+        }
+
         let one_char_token = match c {
             '(' => Some(Token::LeftParen),
             ')' => Some(Token::RightParen),
@@ -97,6 +158,7 @@ impl Lexer {
             '=' => Some(Token::Equal),
             '<' if input[1..].chars().peekable().peek() != Some(&'=') => Some(Token::LessThan),
             '>' if input[1..].chars().peekable().peek() != Some(&'=') => Some(Token::GreaterThan),
+            '!' if !input[1..].starts_with('=') => Some(Token::Not),
             _ => None,
         };
         if one_char_token.is_some() {
@@ -112,17 +174,25 @@ impl Lexer {
                 Lexer::must('=', input[1..].chars().next())?;
                 Some(Token::GreaterThanOrEqual)
             }
-            '!' => {
-                Lexer::must('=', input[1..].chars().next())?;
-                Some(Token::NotEqual)
-            }
+            '!' if input[1..].starts_with('=') => Some(Token::NotEqual),
             _ => None,
         };
         if two_char_token.is_some() {
             return Ok((two_char_token.unwrap(), 2));
         }
 
-        if c.is_alphabetic() {
+        // A hex encoded binary literal e.g x'CAFE'
+        if (c == 'x' || c == 'X') && input[1..].starts_with('\'') {
+            let digits = input[2..]
+                .chars()
+                .take_while(|ch| ch != &'\'')
+                .collect::<String>();
+            let length = digits.len() + 3;
+            Self::must('\'', input[length - 1..].chars().next())?;
+            return Ok((Token::BinaryLiteral(Lexer::decode_hex(&digits)?), length));
+        }
+
+        if c.is_alphabetic() || c == '_' {
             let identifier =
                 Lexer::scan_identifier(&input).expect("id already has at least length 1");
 
@@ -143,17 +213,23 @@ impl Lexer {
 
             let length = identifier.len();
 
-            // Match the suffix of a 2-part keyword e.g the ' JOIN' of an 'INNER JOIN'
+            // Match the suffix of a 2-part keyword e.g the ' JOIN' of an 'INNER JOIN', the suffix
+            // can be several words e.g the ' OUTER JOIN' of a 'LEFT OUTER JOIN'
             fn match_whitespace_and_keyword(input: &str, keyword: &str) -> Option<usize> {
-                let whitespace_count = Lexer::scan_whitespace(input);
-                if whitespace_count > 0
-                    && Lexer::scan_identifier(&input[whitespace_count..])
-                        .map(|id| id.to_lowercase())
-                        == Some(keyword.to_string())
-                {
-                    return Some(whitespace_count + keyword.len());
+                let mut matched_length = 0;
+                for word in keyword.split(' ') {
+                    let input = &input[matched_length..];
+                    let whitespace_count = Lexer::scan_whitespace(input);
+                    if whitespace_count == 0
+                        || Lexer::scan_identifier(&input[whitespace_count..])
+                            .map(|id| id.to_lowercase())
+                            != Some(word.to_string())
+                    {
+                        return None;
+                    }
+                    matched_length += whitespace_count + word.len();
                 }
-                None
+                Some(matched_length)
             }
 
             // Is this a 2-part keyword e.g 'INNER JOIN'
@@ -179,7 +255,9 @@ impl Lexer {
                 .take_while(|ch| ch.is_numeric())
                 .collect::<String>();
             let length = digits.len();
-            let integer = digits.parse().expect("string consists only of digits");
+            let integer = digits.parse().map_err(|_| LexerError {
+                details: format!("integer {} is out of range", digits),
+            })?;
             return Ok((Token::Integer(integer), length));
         }
 
@@ -209,21 +287,41 @@ impl Lexer {
         }
     }
 
+    fn decode_hex(digits: &str) -> Result<Vec<u8>> {
+        if !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return Err(LexerError {
+                details: format!("invalid hex digits {:?} in binary literal", digits),
+            });
+        }
+        if !digits.len().is_multiple_of(2) {
+            return Err(LexerError {
+                details: format!("odd number of digits in binary literal {:?}", digits),
+            });
+        }
+
+        let mut bytes = Vec::with_capacity(digits.len() / 2);
+        let mut chars = digits.chars().filter_map(|ch| ch.to_digit(16));
+        while let (Some(high), Some(low)) = (chars.next(), chars.next()) {
+            bytes.push((high * 16 + low) as u8);
+        }
+        Ok(bytes)
+    }
+
     fn scan_whitespace(input: &str) -> usize {
         input.chars().take_while(|ch| ch.is_whitespace()).count()
     }
 
     fn scan_identifier(input: &str) -> Option<String> {
+        match input.chars().next() {
+            Some(ch) if ch.is_alphabetic() || ch == '_' => (),
+            _ => return None,
+        }
+
         let str = input
             .chars()
-            .take_while(|ch| ch.is_alphabetic())
+            .take_while(|ch| ch.is_alphanumeric() || ch == &'_')
             .collect::<String>();
-
-        if !str.is_empty() {
-            Some(str)
-        } else {
-            None
-        }
+        Some(str)
     }
 }
 
@@ -234,7 +332,7 @@ mod test {
     #[test]
     fn one_char_token() -> Result<()> {
         let l = Lexer::new();
-        let tokens = l.scan("(),;*+-/=<>")?;
+        let tokens = l.scan("(),;*+-/=<>!")?;
         assert_eq!(
             tokens,
             vec![
@@ -249,6 +347,7 @@ mod test {
                 Token::Equal,
                 Token::LessThan,
                 Token::GreaterThan,
+                Token::Not,
                 Token::EOF,
             ]
         );
@@ -258,13 +357,15 @@ mod test {
     #[test]
     fn two_char_token() -> Result<()> {
         let l = Lexer::new();
-        let tokens = l.scan("<=>=!=")?;
+        let tokens = l.scan("<=>=!=->->>")?;
         assert_eq!(
             tokens,
             vec![
                 Token::LessThanOrEqual,
                 Token::GreaterThanOrEqual,
                 Token::NotEqual,
+                Token::Arrow,
+                Token::LongArrow,
                 Token::EOF,
             ]
         );
@@ -274,7 +375,7 @@ mod test {
     #[test]
     fn identifiers() -> Result<()> {
         let l = Lexer::new();
-        let tokens = l.scan("cat bat a rat foo.bar qux")?;
+        let tokens = l.scan("cat bat a rat foo.bar qux is_member _a1 t2.col_3")?;
         assert_eq!(
             tokens,
             vec![
@@ -284,18 +385,22 @@ mod test {
                 Token::Identifier("rat".to_owned()),
                 Token::Identifier("foo.bar".to_owned()),
                 Token::Identifier("qux".to_owned()),
+                Token::Identifier("is_member".to_owned()),
+                Token::Identifier("_a1".to_owned()),
+                Token::Identifier("t2.col_3".to_owned()),
                 Token::EOF,
             ]
         );
 
         assert!(l.scan("cat bat foo. bar").is_err());
+        assert!(l.scan("foo.1").is_err());
         Ok(())
     }
 
     #[test]
     fn keywords() -> Result<()> {
         let l = Lexer::new();
-        let tokens = l.scan("create insert INSERT table CREATE select from where integer varchar primary KEy into values as inner join on true false")?;
+        let tokens = l.scan("create insert INSERT table CREATE select distinct from where integer varchar char blob bytea json uuid type enum primary KEy into values update set delete truncate merge returning restart identity using when matched not matched then group by having order by asc desc nulls first nulls last limit offset fetch first fetch next row rows only and or not as inner join on true false")?;
         assert_eq!(
             tokens,
             vec![
@@ -305,13 +410,50 @@ mod test {
                 Token::Table,
                 Token::Create,
                 Token::Select,
+                Token::Distinct,
                 Token::From,
                 Token::Where,
                 Token::KeywordInteger,
                 Token::KeywordVarchar,
+                Token::KeywordChar,
+                Token::KeywordBlob,
+                Token::KeywordBlob,
+                Token::KeywordJson,
+                Token::KeywordUuid,
+                Token::Type,
+                Token::KeywordEnum,
                 Token::KeywordPrimaryKey,
                 Token::KeywordInto,
                 Token::KeywordValues,
+                Token::Update,
+                Token::KeywordSet,
+                Token::Delete,
+                Token::Truncate,
+                Token::Merge,
+                Token::KeywordReturning,
+                Token::KeywordRestartIdentity,
+                Token::KeywordUsing,
+                Token::KeywordWhen,
+                Token::KeywordMatched,
+                Token::KeywordNotMatched,
+                Token::KeywordThen,
+                Token::KeywordGroupBy,
+                Token::KeywordHaving,
+                Token::KeywordOrderBy,
+                Token::KeywordAsc,
+                Token::KeywordDesc,
+                Token::KeywordNullsFirst,
+                Token::KeywordNullsLast,
+                Token::KeywordLimit,
+                Token::KeywordOffset,
+                Token::KeywordFetchFirst,
+                Token::KeywordFetchFirst,
+                Token::KeywordRows,
+                Token::KeywordRows,
+                Token::KeywordOnly,
+                Token::And,
+                Token::Or,
+                Token::Not,
                 Token::KeywordAs,
                 Token::KeywordInnerJoin,
                 Token::KeywordOn,
@@ -323,6 +465,57 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn join_keywords() -> Result<()> {
+        let l = Lexer::new();
+        let tokens = l.scan(
+            "left join LEFT OUTER join right join right outer join full join full outer JOIN \
+             cross join join natural join left outer right",
+        )?;
+        assert_eq!(
+            tokens,
+            vec![
+                Token::KeywordLeftJoin,
+                Token::KeywordLeftJoin,
+                Token::KeywordRightJoin,
+                Token::KeywordRightJoin,
+                Token::KeywordFullJoin,
+                Token::KeywordFullJoin,
+                Token::KeywordCrossJoin,
+                Token::KeywordInnerJoin,
+                Token::KeywordNatural,
+                Token::KeywordInnerJoin,
+                Token::Identifier("left".to_owned()),
+                Token::Identifier("outer".to_owned()),
+                Token::Identifier("right".to_owned()),
+                Token::EOF,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn subquery_keywords() -> Result<()> {
+        let l = Lexer::new();
+        let tokens = l.scan("a in b NOT IN not exists exists not inside")?;
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier("a".to_owned()),
+                Token::KeywordIn,
+                Token::Identifier("b".to_owned()),
+                Token::KeywordNotIn,
+                Token::Not,
+                Token::KeywordExists,
+                Token::KeywordExists,
+                Token::Not,
+                Token::Identifier("inside".to_owned()),
+                Token::EOF,
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn numbers() -> Result<()> {
         let l = Lexer::new();
@@ -337,6 +530,11 @@ mod test {
                 Token::EOF,
             ]
         );
+        assert_eq!(
+            l.scan("4294967295")?,
+            vec![Token::Integer(u32::MAX), Token::EOF]
+        );
+        assert!(l.scan("4294967296").is_err());
         Ok(())
     }
 
@@ -355,4 +553,35 @@ mod test {
         );
         Ok(())
     }
+
+    #[test]
+    fn binary_literals() -> Result<()> {
+        let l = Lexer::new();
+        let tokens = l.scan("x'DEADbeef' X'' xy")?;
+        assert_eq!(
+            tokens,
+            vec![
+                Token::BinaryLiteral(vec![0xde, 0xad, 0xbe, 0xef]),
+                Token::BinaryLiteral(vec![]),
+                Token::Identifier("xy".to_owned()),
+                Token::EOF,
+            ]
+        );
+
+        assert!(l.scan("x'abc'").is_err());
+        assert!(l.scan("x'zz'").is_err());
+        assert!(l.scan("x'ab").is_err());
+        assert!(l
+            .scan("x'é1'")
+            .unwrap_err()
+            .details
+            .contains("invalid hex digits"));
+        assert!(l
+            .scan("x'éé'")
+            .unwrap_err()
+            .details
+            .contains("invalid hex digits"));
+
+        Ok(())
+    }
 }
@@ -5,21 +5,69 @@ pub enum Token {
     // Keywords
     Create,
     Table,
+    Type,
     Insert,
+    Update,
+    Delete,
+    Truncate,
+    Merge,
     Select,
+    Distinct,
     From,
     Where,
     KeywordInteger,
     KeywordVarchar,
+    KeywordChar,
+    KeywordBlob,
+    KeywordJson,
+    KeywordUuid,
+    KeywordEnum,
     KeywordPrimaryKey,
     KeywordInto,
     KeywordValues,
+    KeywordSet,
     KeywordAs,
     KeywordInnerJoin,
+    KeywordLeftJoin,
+    KeywordRightJoin,
+    KeywordFullJoin,
+    KeywordCrossJoin,
+    KeywordNatural,
+    KeywordUnion,
+    KeywordIntersect,
+    KeywordExcept,
+    KeywordAll,
+    KeywordIn,
+    KeywordNotIn,
+    KeywordExists,
     KeywordOn,
+    KeywordDo,
+    KeywordReturning,
+    KeywordRestartIdentity,
+    KeywordUsing,
+    KeywordWhen,
+    KeywordMatched,
+    KeywordNotMatched,
+    KeywordThen,
+    KeywordGroupBy,
+    KeywordHaving,
+    KeywordOrderBy,
+    KeywordAsc,
+    KeywordDesc,
+    KeywordNullsFirst,
+    KeywordNullsLast,
+    KeywordLimit,
+    KeywordOffset,
+    KeywordFetchFirst,
+    KeywordRows,
+    KeywordOnly,
+    And,
+    Or,
+    Not,
 
     Identifier(String),
     StringLiteral(String),
+    BinaryLiteral(Vec<u8>),
 
     LeftParen,
     RightParen,
@@ -29,6 +77,8 @@ pub enum Token {
     Plus,
     Minus,
     Slash,
+    Arrow,
+    LongArrow,
 
     Equal,
     NotEqual,
@@ -37,7 +87,8 @@ pub enum Token {
     LessThanOrEqual,
     GreaterThanOrEqual,
 
-    Integer(i32),
+    // Unsigned, a sign is parsed as a unary operator so that `-2147483648` fits an INTEGER.
+    Integer(u32),
 
     True,
     False,
@@ -51,20 +102,68 @@ impl fmt::Display for Token {
         let s = match self {
             Self::Create => Borrowed("CREATE"),
             Self::Table => Borrowed("TABLE"),
+            Self::Type => Borrowed("TYPE"),
             Self::Insert => Borrowed("INSERT"),
+            Self::Update => Borrowed("UPDATE"),
+            Self::Delete => Borrowed("DELETE"),
+            Self::Truncate => Borrowed("TRUNCATE"),
+            Self::Merge => Borrowed("MERGE"),
             Self::Select => Borrowed("SELECT"),
+            Self::Distinct => Borrowed("DISTINCT"),
             Self::From => Borrowed("FROM"),
             Self::Where => Borrowed("WHERE"),
             Self::KeywordInteger => Borrowed("INTEGER"),
             Self::KeywordVarchar => Borrowed("VARCHAR"),
+            Self::KeywordChar => Borrowed("CHAR"),
+            Self::KeywordBlob => Borrowed("BLOB"),
+            Self::KeywordJson => Borrowed("JSON"),
+            Self::KeywordUuid => Borrowed("UUID"),
+            Self::KeywordEnum => Borrowed("ENUM"),
             Self::KeywordPrimaryKey => Borrowed("PRIMARY KEY"),
             Self::KeywordInto => Borrowed("INTO"),
             Self::KeywordValues => Borrowed("VALUES"),
+            Self::KeywordSet => Borrowed("SET"),
             Self::KeywordAs => Borrowed("AS"),
             Self::KeywordInnerJoin => Borrowed("INNER JOIN"),
+            Self::KeywordLeftJoin => Borrowed("LEFT JOIN"),
+            Self::KeywordRightJoin => Borrowed("RIGHT JOIN"),
+            Self::KeywordFullJoin => Borrowed("FULL JOIN"),
+            Self::KeywordCrossJoin => Borrowed("CROSS JOIN"),
+            Self::KeywordNatural => Borrowed("NATURAL"),
+            Self::KeywordUnion => Borrowed("UNION"),
+            Self::KeywordIntersect => Borrowed("INTERSECT"),
+            Self::KeywordExcept => Borrowed("EXCEPT"),
+            Self::KeywordAll => Borrowed("ALL"),
+            Self::KeywordIn => Borrowed("IN"),
+            Self::KeywordNotIn => Borrowed("NOT IN"),
+            Self::KeywordExists => Borrowed("EXISTS"),
             Self::KeywordOn => Borrowed("ON"),
+            Self::KeywordDo => Borrowed("DO"),
+            Self::KeywordReturning => Borrowed("RETURNING"),
+            Self::KeywordRestartIdentity => Borrowed("RESTART IDENTITY"),
+            Self::KeywordUsing => Borrowed("USING"),
+            Self::KeywordWhen => Borrowed("WHEN"),
+            Self::KeywordMatched => Borrowed("MATCHED"),
+            Self::KeywordNotMatched => Borrowed("NOT MATCHED"),
+            Self::KeywordThen => Borrowed("THEN"),
+            Self::KeywordGroupBy => Borrowed("GROUP BY"),
+            Self::KeywordHaving => Borrowed("HAVING"),
+            Self::KeywordOrderBy => Borrowed("ORDER BY"),
+            Self::KeywordAsc => Borrowed("ASC"),
+            Self::KeywordDesc => Borrowed("DESC"),
+            Self::KeywordNullsFirst => Borrowed("NULLS FIRST"),
+            Self::KeywordNullsLast => Borrowed("NULLS LAST"),
+            Self::KeywordLimit => Borrowed("LIMIT"),
+            Self::KeywordOffset => Borrowed("OFFSET"),
+            Self::KeywordFetchFirst => Borrowed("FETCH FIRST"),
+            Self::KeywordRows => Borrowed("ROWS"),
+            Self::KeywordOnly => Borrowed("ONLY"),
+            Self::And => Borrowed("AND"),
+            Self::Or => Borrowed("OR"),
+            Self::Not => Borrowed("NOT"),
             Self::Identifier(id) => Owned(format!("Identifier({})", id)),
             Self::StringLiteral(_) => Borrowed("\"<string>\""),
+            Self::BinaryLiteral(_) => Borrowed("x'<bytes>'"),
             Self::LeftParen => Borrowed("("),
             Self::RightParen => Borrowed(")"),
             Self::Comma => Borrowed(","),
@@ -73,6 +172,8 @@ impl fmt::Display for Token {
             Self::Plus => Borrowed("+"),
             Self::Minus => Borrowed("-"),
             Self::Slash => Borrowed("/"),
+            Self::Arrow => Borrowed("->"),
+            Self::LongArrow => Borrowed("->>"),
             Self::Equal => Borrowed("="),
             Self::NotEqual => Borrowed("!="),
             Self::LessThan => Borrowed(">"),
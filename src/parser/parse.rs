@@ -56,6 +56,13 @@ impl TokenStream {
         self.tokens.get(self.curr_index).and_then(|t| t.as_ref())
     }
 
+    // Looks past the next token, `peek_nth(0)` is the same as `peek()`.
+    pub fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens
+            .get(self.curr_index + n)
+            .and_then(|t| t.as_ref())
+    }
+
     pub fn next(&mut self) -> Option<Token> {
         if self.curr_index < self.tokens.len() {
             self.curr_index += 1;
@@ -77,6 +84,24 @@ impl ParseHelper {
         }
     }
 
+    // CONFLICT and NOTHING are only keywords after ON and DO, elsewhere they are identifiers.
+    pub fn is_word(word: &str, token: Option<&Token>) -> bool {
+        matches!(token, Some(Token::Identifier(id)) if id.eq_ignore_ascii_case(word))
+    }
+
+    pub fn match_word(word: &str, got: Option<Token>) -> std::result::Result<(), ParseError> {
+        match got {
+            Some(got) if Self::is_word(word, Some(&got)) => Ok(()),
+            Some(got) => Err(ParseError::token_mismatch(
+                Token::Identifier(word.to_owned()),
+                got,
+            )),
+            None => Err(ParseError::unexpected_eof(Token::Identifier(
+                word.to_owned(),
+            ))),
+        }
+    }
+
     pub fn match_identifier(got: Option<Token>) -> std::result::Result<String, ParseError> {
         match got {
             Some(Token::Identifier(id)) => Ok(id),
@@ -17,8 +17,15 @@ impl Parser {
 
     pub fn parse(&mut self, input: Input) -> std::result::Result<Stmt, ParseError> {
         match input.peek() {
+            Some(&Token::Create) if input.peek_nth(1) == Some(&Token::Type) => {
+                Ok(Stmt::CreateType(self.create_type_stmt(input)?.0))
+            }
             Some(&Token::Create) => Ok(Stmt::CreateTable(self.create_table_stmt(input)?.0)),
             Some(&Token::Insert) => Ok(Stmt::Insert(self.insert_stmt(input)?.0)),
+            Some(&Token::Update) => Ok(Stmt::Update(self.update_stmt(input)?.0)),
+            Some(&Token::Delete) => Ok(Stmt::Delete(self.delete_stmt(input)?.0)),
+            Some(&Token::Truncate) => Ok(Stmt::Truncate(self.truncate_stmt(input)?.0)),
+            Some(&Token::Merge) => Ok(Stmt::Merge(self.merge_stmt(input)?.0)),
             Some(&Token::Select) => Ok(Stmt::Select(self.select_stmt(input, true)?.0)),
             Some(token) => Err(ParseError {
                 details: format!("invalid start of query {:?}", token),
@@ -47,22 +54,50 @@ impl Parser {
         ))
     }
 
-    pub fn attribute_definitions(&mut self, mut input: Input) -> Result<Vec<AttributeDefinition>> {
-        let mut definitions = Vec::new();
+    pub fn create_type_stmt(&mut self, mut input: Input) -> Result<CreateTypeStmt> {
+        let _ = ParseHelper::match_token(Token::Create, input.next())?;
+        let _ = ParseHelper::match_token(Token::Type, input.next())?;
+        let type_name = ParseHelper::match_identifier(input.next())?;
+        let _ = ParseHelper::match_token(Token::KeywordAs, input.next())?;
+        let _ = ParseHelper::match_token(Token::KeywordEnum, input.next())?;
+        let _ = ParseHelper::match_token(Token::LeftParen, input.next())?;
 
+        let mut labels = Vec::new();
         loop {
-            let name = ParseHelper::match_identifier(input.next())?;
-            let attribute_type = match input.next() {
-                Some(Token::KeywordInteger) => AttributeType::Integer,
-                Some(Token::KeywordVarchar) => AttributeType::Text,
+            match input.next() {
+                Some(Token::StringLiteral(label)) => labels.push(label),
                 Some(got) => {
                     return Err(ParseError::token_mismatch(
-                        Token::KeywordVarchar,
-                        got.clone(),
+                        Token::StringLiteral(String::new()),
+                        got,
                     ))
                 }
-                None => return Err(ParseError::unexpected_eof(Token::KeywordVarchar)),
-            };
+                None => {
+                    return Err(ParseError::unexpected_eof(Token::StringLiteral(
+                        String::new(),
+                    )))
+                }
+            }
+            match input.peek() {
+                Some(&Token::Comma) => {
+                    let _comma = input.next();
+                }
+                _ => break,
+            }
+        }
+        let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+        let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
+
+        Ok((CreateTypeStmt { type_name, labels }, input))
+    }
+
+    pub fn attribute_definitions(&mut self, mut input: Input) -> Result<Vec<AttributeDefinition>> {
+        let mut definitions = Vec::new();
+
+        loop {
+            let name = ParseHelper::match_identifier(input.next())?;
+            let (attribute_type, returned_input) = self.attribute_type(input)?;
+            input = returned_input;
             let is_primary_key = match input.peek() {
                 Some(&Token::KeywordPrimaryKey) => {
                     let _primary_key = input.next();
@@ -86,6 +121,52 @@ impl Parser {
         }
     }
 
+    fn attribute_type(&self, mut input: Input) -> Result<AttributeType> {
+        match input.next() {
+            Some(Token::KeywordInteger) => Ok((AttributeType::Integer, input)),
+            Some(Token::KeywordVarchar) => match self.type_length(input)? {
+                (Some(length), input) => Ok((AttributeType::Varchar(length), input)),
+                (None, input) => Ok((AttributeType::Text, input)),
+            },
+            Some(Token::KeywordChar) => {
+                // A CHAR without an explicit length holds a single character.
+                let (length, input) = self.type_length(input)?;
+                Ok((AttributeType::Char(length.unwrap_or(1)), input))
+            }
+            Some(Token::KeywordBlob) => Ok((AttributeType::Binary, input)),
+            Some(Token::KeywordJson) => Ok((AttributeType::Json, input)),
+            Some(Token::KeywordUuid) => Ok((AttributeType::Uuid, input)),
+            Some(Token::Identifier(type_name)) => Ok((AttributeType::Named(type_name), input)),
+            Some(got) => Err(ParseError::token_mismatch(Token::KeywordVarchar, got)),
+            None => Err(ParseError::unexpected_eof(Token::KeywordVarchar)),
+        }
+    }
+
+    // Parses the optional `(n)` length limit that follows a character type.
+    fn type_length(&self, mut input: Input) -> Result<Option<u32>> {
+        if input.peek() != Some(&Token::LeftParen) {
+            return Ok((None, input));
+        }
+        let _left_paren = input.next();
+
+        let length = match input.next() {
+            Some(Token::Integer(length)) if length > 0 => length,
+            Some(Token::Integer(length)) => {
+                return Err(ParseError {
+                    details: format!(
+                        "length for a character type must be positive, got {}",
+                        length
+                    ),
+                })
+            }
+            Some(got) => return Err(ParseError::token_mismatch(Token::Integer(1), got)),
+            None => return Err(ParseError::unexpected_eof(Token::Integer(1))),
+        };
+        ParseHelper::match_token(Token::RightParen, input.next())?;
+
+        Ok((Some(length), input))
+    }
+
     pub fn insert_stmt(&mut self, mut input: Input) -> Result<InsertStmt> {
         let _ = ParseHelper::match_token(Token::Insert, input.next())?;
         let _ = ParseHelper::match_token(Token::KeywordInto, input.next())?;
@@ -94,16 +175,33 @@ impl Parser {
         let (attribute_names, mut input) = self.identifiers(input)?;
         let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
         let _ = ParseHelper::match_token(Token::KeywordValues, input.next())?;
-        let _ = ParseHelper::match_token(Token::LeftParen, input.next())?;
-        let (attribute_values, mut input) = self.attribute_values(input)?;
-        let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+
+        let mut rows = Vec::new();
+        loop {
+            let _ = ParseHelper::match_token(Token::LeftParen, input.next())?;
+            let (attribute_values, returned_input) = self.attribute_values(input)?;
+            input = returned_input;
+            let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+            rows.push(attribute_values);
+
+            match input.peek() {
+                Some(&Token::Comma) => {
+                    let _comma = input.next();
+                }
+                _ => break,
+            }
+        }
+        let (on_conflict, input) = self.on_conflict(input)?;
+        let (returning, mut input) = self.returning(input)?;
         let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
 
         Ok((
             InsertStmt {
                 table_name,
                 attribute_names,
-                attribute_values,
+                rows,
+                on_conflict,
+                returning,
             },
             input,
         ))
@@ -124,6 +222,239 @@ impl Parser {
         }
     }
 
+    fn on_conflict(&mut self, mut input: Input) -> Result<Option<OnConflict>> {
+        if input.peek() != Some(&Token::KeywordOn)
+            || !ParseHelper::is_word("conflict", input.peek_nth(1))
+        {
+            return Ok((None, input));
+        }
+        let _on = input.next();
+        let _conflict = input.next();
+
+        let target = match input.peek() {
+            Some(&Token::LeftParen) => {
+                let _left_paren = input.next();
+                let target = ParseHelper::match_identifier(input.next())?;
+                let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+                Some(target)
+            }
+            _ => None,
+        };
+
+        let _ = ParseHelper::match_token(Token::KeywordDo, input.next())?;
+        let action = match input.next() {
+            Some(Token::Update) => {
+                let _ = ParseHelper::match_token(Token::KeywordSet, input.next())?;
+                let (assignments, returned_input) = self.assignments(input)?;
+                input = returned_input;
+                ConflictAction::DoUpdate(assignments)
+            }
+            got @ Some(Token::Identifier(_)) => {
+                ParseHelper::match_word("nothing", got)?;
+                ConflictAction::DoNothing
+            }
+            Some(got) => return Err(ParseError::token_mismatch(Token::Update, got)),
+            None => return Err(ParseError::unexpected_eof(Token::Update)),
+        };
+
+        Ok((Some(OnConflict { target, action }), input))
+    }
+
+    pub fn update_stmt(&mut self, mut input: Input) -> Result<UpdateStmt> {
+        let _ = ParseHelper::match_token(Token::Update, input.next())?;
+        let table_name = ParseHelper::match_identifier(input.next())?;
+        let _ = ParseHelper::match_token(Token::KeywordSet, input.next())?;
+        let (assignments, input) = self.assignments(input)?;
+        let (where_clause, input) = self.where_clause(input)?;
+        let (returning, mut input) = self.returning(input)?;
+        let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
+
+        Ok((
+            UpdateStmt {
+                table_name,
+                assignments,
+                where_clause,
+                returning,
+            },
+            input,
+        ))
+    }
+
+    // Parses the `col = <expr>, ...` list of a SET clause.
+    fn assignments(&mut self, mut input: Input) -> Result<Vec<Assignment>> {
+        let mut assignments = Vec::new();
+        loop {
+            let attribute_name = ParseHelper::match_identifier(input.next())?;
+            let _ = ParseHelper::match_token(Token::Equal, input.next())?;
+            let value = ExprParser::expr(&mut input)?;
+            assignments.push(Assignment {
+                attribute_name,
+                value,
+            });
+
+            match input.peek() {
+                Some(&Token::Comma) => {
+                    let _comma = input.next();
+                }
+                _ => return Ok((assignments, input)),
+            }
+        }
+    }
+
+    pub fn delete_stmt(&mut self, mut input: Input) -> Result<DeleteStmt> {
+        let _ = ParseHelper::match_token(Token::Delete, input.next())?;
+        let _ = ParseHelper::match_token(Token::From, input.next())?;
+        let table_name = ParseHelper::match_identifier(input.next())?;
+        let (where_clause, input) = self.where_clause(input)?;
+        let (returning, mut input) = self.returning(input)?;
+        let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
+
+        Ok((
+            DeleteStmt {
+                table_name,
+                where_clause,
+                returning,
+            },
+            input,
+        ))
+    }
+
+    pub fn truncate_stmt(&mut self, mut input: Input) -> Result<TruncateStmt> {
+        let _ = ParseHelper::match_token(Token::Truncate, input.next())?;
+        if input.peek() == Some(&Token::Table) {
+            let _table = input.next();
+        }
+        let (table_names, mut input) = self.identifiers(input)?;
+        let restart_identity = input.peek() == Some(&Token::KeywordRestartIdentity);
+        if restart_identity {
+            let _restart_identity = input.next();
+        }
+        let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
+
+        Ok((
+            TruncateStmt {
+                table_names,
+                restart_identity,
+            },
+            input,
+        ))
+    }
+
+    pub fn merge_stmt(&mut self, mut input: Input) -> Result<MergeStmt> {
+        let _ = ParseHelper::match_token(Token::Merge, input.next())?;
+        let _ = ParseHelper::match_token(Token::KeywordInto, input.next())?;
+        let target_table = ParseHelper::match_identifier(input.next())?;
+        let (target_alias, mut input) = self.match_alias(input)?;
+        let _ = ParseHelper::match_token(Token::KeywordUsing, input.next())?;
+        let (
+            TableReference {
+                from_clause: source,
+                alias: source_alias,
+            },
+            input,
+        ) = self.table_reference(input)?;
+        let (predicate, mut input) = self.join_predicate(input)?;
+
+        let mut when_clauses = Vec::new();
+        while input.peek() == Some(&Token::KeywordWhen) {
+            let (when_clause, returned_input) = self.merge_when_clause(input)?;
+            input = returned_input;
+            when_clauses.push(when_clause);
+        }
+        let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
+
+        Ok((
+            MergeStmt {
+                target_table,
+                target_alias,
+                source,
+                source_alias,
+                predicate,
+                when_clauses,
+            },
+            input,
+        ))
+    }
+
+    fn merge_when_clause(&mut self, mut input: Input) -> Result<MergeWhenClause> {
+        let _ = ParseHelper::match_token(Token::KeywordWhen, input.next())?;
+        let matched = match input.next() {
+            Some(Token::KeywordMatched) => true,
+            Some(Token::KeywordNotMatched) => false,
+            Some(got) => return Err(ParseError::token_mismatch(Token::KeywordMatched, got)),
+            None => return Err(ParseError::unexpected_eof(Token::KeywordMatched)),
+        };
+        let condition = match input.peek() {
+            Some(&Token::And) => {
+                let _and = input.next();
+                Some(ExprParser::expr(&mut input)?)
+            }
+            _ => None,
+        };
+        let _ = ParseHelper::match_token(Token::KeywordThen, input.next())?;
+
+        // Rows of the target can be updated or deleted, rows only in the source inserted.
+        let action = match (matched, input.next()) {
+            (_, Some(Token::KeywordDo)) => {
+                ParseHelper::match_word("nothing", input.next())?;
+                MergeAction::DoNothing
+            }
+            (true, Some(Token::Update)) => {
+                let _ = ParseHelper::match_token(Token::KeywordSet, input.next())?;
+                let (assignments, returned_input) = self.assignments(input)?;
+                input = returned_input;
+                MergeAction::Update(assignments)
+            }
+            (true, Some(Token::Delete)) => MergeAction::Delete,
+            (false, Some(Token::Insert)) => {
+                let attribute_names = match input.peek() {
+                    Some(&Token::LeftParen) => {
+                        let _left_paren = input.next();
+                        let (attribute_names, returned_input) = self.identifiers(input)?;
+                        input = returned_input;
+                        let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+                        Some(attribute_names)
+                    }
+                    _ => None,
+                };
+                let _ = ParseHelper::match_token(Token::KeywordValues, input.next())?;
+                let _ = ParseHelper::match_token(Token::LeftParen, input.next())?;
+                let mut values = vec![ExprParser::expr(&mut input)?];
+                while let Some(&Token::Comma) = input.peek() {
+                    let _comma = input.next();
+                    values.push(ExprParser::expr(&mut input)?);
+                }
+                let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+                MergeAction::Insert {
+                    attribute_names,
+                    values,
+                }
+            }
+            (true, Some(got)) => return Err(ParseError::token_mismatch(Token::Update, got)),
+            (false, Some(got)) => return Err(ParseError::token_mismatch(Token::Insert, got)),
+            (_, None) => return Err(ParseError::unexpected_eof(Token::KeywordDo)),
+        };
+
+        Ok((
+            MergeWhenClause {
+                matched,
+                condition,
+                action,
+            },
+            input,
+        ))
+    }
+
+    // The optional `RETURNING` list of a statement that modifies a table.
+    fn returning(&mut self, mut input: Input) -> Result<Option<SelectProperties>> {
+        if input.peek() != Some(&Token::KeywordReturning) {
+            return Ok((None, input));
+        }
+        let _returning = input.next();
+        let (properties, input) = self.select_properties(input)?;
+        Ok((Some(properties), input))
+    }
+
     pub fn attribute_values(&mut self, mut input: Input) -> Result<Vec<AttributeValue>> {
         let mut values = Vec::new();
 
@@ -147,120 +478,249 @@ impl Parser {
         }
     }
 
-    pub fn select_stmt(&mut self, mut input: Input, is_stmt: bool) -> Result<SelectStmt> {
+    pub fn select_stmt(&mut self, input: Input, is_stmt: bool) -> Result<SelectStmt> {
+        let (stmt, input) = self.set_operation(input, false)?;
+        let (order_by, input) = self.order_by(input)?;
+        let (limit, mut input) = self.limit_clause(input)?;
+
+        if is_stmt {
+            let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
+        }
+
+        let stmt = match stmt {
+            SelectStmt::Select(stmt) => SelectStmt::Select(Box::new(SingleSelectStmt {
+                order_by,
+                limit,
+                ..*stmt
+            })),
+            SelectStmt::SetOperation(stmt) => SelectStmt::SetOperation(SetOperationStmt {
+                order_by,
+                limit,
+                ..stmt
+            }),
+        };
+        Ok((stmt, input))
+    }
+
+    // INTERSECT binds tighter than UNION and EXCEPT, set operations of the same precedence are
+    // applied from left to right.
+    fn set_operation(&mut self, input: Input, intersect: bool) -> Result<SelectStmt> {
+        let operand = |parser: &mut Self, input| match intersect {
+            true => parser
+                .single_select(input)
+                .map(|(stmt, input)| (SelectStmt::Select(Box::new(stmt)), input)),
+            false => parser.set_operation(input, true),
+        };
+        let (mut stmt, mut input) = operand(self, input)?;
+        loop {
+            let op = match (input.peek(), intersect) {
+                (Some(Token::KeywordUnion), false) => SetOperator::Union,
+                (Some(Token::KeywordExcept), false) => SetOperator::Except,
+                (Some(Token::KeywordIntersect), true) => SetOperator::Intersect,
+                _ => return Ok((stmt, input)),
+            };
+            let _op = input.next();
+            let all = input.peek() == Some(&Token::KeywordAll);
+            if all || input.peek() == Some(&Token::Distinct) {
+                let _quantifier = input.next();
+            }
+            let (right, rest) = operand(self, input)?;
+            input = rest;
+            stmt = SelectStmt::SetOperation(SetOperationStmt {
+                op,
+                all,
+                left: Box::new(stmt),
+                right: Box::new(right),
+                order_by: vec![],
+                limit: None,
+            });
+        }
+    }
+
+    // A query without ORDER BY and LIMIT, which apply to all the queries of a set operation.
+    fn single_select(&mut self, mut input: Input) -> Result<SingleSelectStmt> {
         let _ = ParseHelper::match_token(Token::Select, input.next())?;
+        let (distinct, input) = self.distinct(input)?;
         let (properties, mut input) = self.select_properties(input)?;
 
         let _ = ParseHelper::match_token(Token::From, input.next())?;
-        let ((from_clause, alias), mut input) = self.parse_from_clause(input)?;
+        let (TableReference { from_clause, alias }, input) = self.table_references(input)?;
+        let (where_clause, input) = self.where_clause(input)?;
+        let ((group_by, having), input) = self.group_by(input)?;
 
-        fn from_clause_to_join_query(
-            from_clause: FromClause,
-            alias: Option<String>,
-        ) -> SingleSelectStmt {
+        Ok((
             SingleSelectStmt {
-                properties: SelectProperties::Star,
+                properties,
                 from_clause,
-                where_clause: WhereClause::None,
+                where_clause,
+                order_by: vec![],
+                limit: None,
+                distinct,
+                group_by,
+                having,
                 alias,
-            }
+            },
+            input,
+        ))
+    }
+
+    fn distinct(&self, mut input: Input) -> Result<Option<Distinct>> {
+        if input.peek() != Some(&Token::Distinct) {
+            return Ok((None, input));
         }
+        let _distinct = input.next();
+        if input.peek() != Some(&Token::KeywordOn) {
+            return Ok((Some(Distinct::Rows), input));
+        }
+        let _on = input.next();
 
-        let (rh_join, where_clause, mut input) = match input.peek() {
-            Some(Token::KeywordInnerJoin) => {
-                let _ = ParseHelper::match_token(Token::KeywordInnerJoin, input.next())?;
-                let ((from_clause, alias), input) = self.parse_from_clause(input)?;
+        let _ = ParseHelper::match_token(Token::LeftParen, input.next())?;
+        let mut exprs = vec![ExprParser::expr(&mut input)?];
+        while let Some(&Token::Comma) = input.peek() {
+            let _comma = input.next();
+            exprs.push(ExprParser::expr(&mut input)?);
+        }
+        let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+        Ok((Some(Distinct::On(exprs)), input))
+    }
 
-                // Wrap right hand side of join inside a select statement.
-                (
-                    Some(from_clause_to_join_query(from_clause, alias)),
-                    None,
-                    input,
-                )
-            }
-            _ => {
-                let (where_clause, input) = self.where_clause(input)?;
-                (None, Some(where_clause), input)
-            }
-        };
+    fn select_properties(&self, mut input: Input) -> Result<SelectProperties> {
+        if input.peek() == Some(&Token::Star) {
+            let _star = input.next();
+            return Ok((SelectProperties::Star, input));
+        }
 
-        let (stmt, mut input) = match rh_join {
-            Some(rh_join) => {
-                let (where_clause, input) = self.join_predicate(input)?;
-                (
-                    SelectStmt::Join(JoinStmt {
-                        join_type: JoinType::InnerJoin,
-                        properties,
-                        // Wrap left hand side of join inside a select statement.
-                        left: from_clause_to_join_query(from_clause, alias),
-                        right: rh_join,
-                        predicate: where_clause,
-                    }),
-                    input,
-                )
+        let mut items = Vec::new();
+        loop {
+            let expr = ExprParser::expr(&mut input)?;
+            let (alias, rest) = self.match_alias(input)?;
+            input = rest;
+            items.push(SelectItem { expr, alias });
+            match input.peek() {
+                Some(&Token::Comma) => {
+                    let _comma = input.next();
+                }
+                _ => break,
             }
-            None => (
-                SelectStmt::Select(SingleSelectStmt {
-                    properties,
-                    from_clause,
-                    where_clause: where_clause.expect("we either have a join or where clause set."),
-                    alias,
-                }),
-                input,
-            ),
-        };
+        }
 
-        if is_stmt {
-            let _ = ParseHelper::match_token(Token::Semicolon, input.next())?;
+        // A list of plain attribute names is a simple projection.
+        let ids = items
+            .iter()
+            .map(|item| match item {
+                SelectItem {
+                    expr: Expr::Literal(LiteralExpr::Identifier(id)),
+                    alias: None,
+                } => Some(id.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        match ids {
+            Some(ids) => Ok((SelectProperties::Identifiers(ids), input)),
+            None => Ok((SelectProperties::Exprs(items), input)),
         }
+    }
 
-        Ok((stmt, input))
+    // The table references of a FROM clause are separated by commas and cross joined.
+    fn table_references(&mut self, input: Input) -> Result<TableReference> {
+        let (mut reference, mut input) = self.joined_table(input)?;
+        while let Some(&Token::Comma) = input.peek() {
+            let _comma = input.next();
+            let (right, rest) = self.joined_table(input)?;
+            input = rest;
+            reference = Self::join(JoinType::Cross, reference, right, JoinConstraint::None);
+        }
+        Ok((reference, input))
     }
 
-    fn select_properties(&self, mut input: Input) -> Result<SelectProperties> {
-        match input.next() {
-            Some(Token::Star) => Ok((SelectProperties::Star, input)),
-            Some(Token::Identifier(id)) => {
-                let mut ids = vec![id.clone()];
-                while let Some(&Token::Comma) = input.peek() {
-                    let _comma = input.next();
-                    match input.next() {
-                        Some(Token::Identifier(id)) => ids.push(id.clone()),
-                        Some(unexpected) => {
-                            return Err(ParseError::token_mismatch(
-                                Token::Identifier("<attribute_name>".to_owned()),
-                                unexpected.clone(),
-                            ))
-                        }
-                        None => {
-                            return Err(ParseError::unexpected_eof(Token::Identifier(
-                                "<attribute_name>".to_owned(),
-                            )))
-                        }
-                    }
-                }
-                Ok((SelectProperties::Identifiers(ids), input))
+    // A table reference followed by any number of joins, `a join b on .. join c on ..` joins
+    // the join of `a` and `b` with `c`.
+    fn joined_table(&mut self, input: Input) -> Result<TableReference> {
+        let (mut reference, mut input) = self.table_reference(input)?;
+        loop {
+            let natural = input.peek() == Some(&Token::KeywordNatural);
+            if natural {
+                let _natural = input.next();
             }
-            Some(got) => Err(ParseError::token_mismatch(Token::Star, got.clone())),
-            None => Err(ParseError::unexpected_eof(Token::Star)),
+            let join_type = match input.peek() {
+                Some(Token::KeywordInnerJoin) => JoinType::Inner,
+                Some(Token::KeywordLeftJoin) => JoinType::Left,
+                Some(Token::KeywordRightJoin) => JoinType::Right,
+                Some(Token::KeywordFullJoin) => JoinType::Full,
+                Some(Token::KeywordCrossJoin) if !natural => JoinType::Cross,
+                Some(unexpected) if natural => {
+                    return Err(ParseError::token_mismatch(
+                        Token::KeywordInnerJoin,
+                        unexpected.clone(),
+                    ))
+                }
+                None if natural => return Err(ParseError::unexpected_eof(Token::KeywordInnerJoin)),
+                _ => return Ok((reference, input)),
+            };
+            let _join = input.next();
+            let (right, rest) = self.table_reference(input)?;
+            let (constraint, rest) = match join_type {
+                JoinType::Cross => (JoinConstraint::None, rest),
+                _ if natural => (JoinConstraint::Natural, rest),
+                _ => self.join_constraint(rest)?,
+            };
+            input = rest;
+            reference = Self::join(join_type, reference, right, constraint);
+        }
+    }
+
+    fn join(
+        join_type: JoinType,
+        left: TableReference,
+        right: TableReference,
+        constraint: JoinConstraint,
+    ) -> TableReference {
+        TableReference {
+            from_clause: FromClause::Join(Box::new(JoinClause {
+                join_type,
+                left,
+                right,
+                constraint,
+            })),
+            alias: None,
+        }
+    }
+
+    fn join_constraint(&mut self, mut input: Input) -> Result<JoinConstraint> {
+        if input.peek() == Some(&Token::KeywordUsing) {
+            let _using = input.next();
+            let _ = ParseHelper::match_token(Token::LeftParen, input.next())?;
+            let (attribute_names, mut input) = self.identifiers(input)?;
+            let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
+            return Ok((JoinConstraint::Using(attribute_names), input));
+        }
+        match self.join_predicate(input)? {
+            (WhereClause::Expr(expr), input) => Ok((JoinConstraint::On(expr), input)),
+            (WhereClause::None, input) => Ok((JoinConstraint::None, input)),
         }
     }
 
-    fn parse_from_clause(&mut self, mut input: Input) -> Result<(FromClause, Option<String>)> {
+    fn table_reference(&mut self, mut input: Input) -> Result<TableReference> {
         let has_parenthesis = input.peek() == Some(&&Token::LeftParen);
         if has_parenthesis {
             let _left_paren = input.next();
         }
 
         let res = match input.peek() {
+            Some(Token::Select) => {
+                let (select_stmt, input) = self.select_stmt(input, false)?;
+                Ok((FromClause::Select(Box::new(select_stmt)), None, input))
+            }
+            // Joins can be parenthesized to nest them to the right.
+            Some(Token::Identifier(_)) | Some(Token::LeftParen) if has_parenthesis => {
+                let (TableReference { from_clause, alias }, input) = self.joined_table(input)?;
+                Ok((from_clause, alias, input))
+            }
             Some(Token::Identifier(_)) => Ok((
                 FromClause::Table(ParseHelper::match_identifier(input.next())?),
+                None,
                 input,
             )),
-            Some(Token::Select) => {
-                let (select_stmt, mut input) = self.select_stmt(input, false)?;
-                Ok((FromClause::Select(Box::new(select_stmt)), input))
-            }
             Some(unexpected) => Err(ParseError::token_mismatch(
                 Token::Identifier("<table>".to_owned()),
                 unexpected.clone(),
@@ -270,12 +730,15 @@ impl Parser {
             ))),
         };
 
-        res.and_then(|(from_clause, mut input)| {
+        res.and_then(|(from_clause, alias, mut input)| {
             if has_parenthesis {
                 let _ = ParseHelper::match_token(Token::RightParen, input.next())?;
             }
-            let (alias, input) = self.match_alias(input)?;
-            Ok(((from_clause, alias), input))
+            let (alias, input) = match alias {
+                Some(alias) => (Some(alias), input),
+                None => self.table_alias(input)?,
+            };
+            Ok((TableReference { from_clause, alias }, input))
         })
     }
 
@@ -297,40 +760,160 @@ impl Parser {
         }
     }
 
-    fn match_alias(&self, mut input: Input) -> Result<Option<String>> {
-        match input.peek() {
-            Some(&Token::KeywordAs) => {
-                let _as = input.next();
-                let alias = ParseHelper::match_identifier(input.next())?;
-                Ok((Some(alias), input))
+    fn group_by(&self, mut input: Input) -> Result<(Vec<Expr>, Option<Expr>)> {
+        let mut group_by = Vec::new();
+        if input.peek() == Some(&Token::KeywordGroupBy) {
+            let _group_by = input.next();
+            group_by.push(ExprParser::expr(&mut input)?);
+            while let Some(&Token::Comma) = input.peek() {
+                let _comma = input.next();
+                group_by.push(ExprParser::expr(&mut input)?);
             }
-            _ => Ok((None, input)),
         }
+
+        let having = match input.peek() {
+            Some(&Token::KeywordHaving) => {
+                let _having = input.next();
+                Some(ExprParser::expr(&mut input)?)
+            }
+            _ => None,
+        };
+        Ok(((group_by, having), input))
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    type Result<T> = std::result::Result<T, ParseError>;
+    fn order_by(&self, mut input: Input) -> Result<Vec<OrderByExpr>> {
+        if input.peek() != Some(&Token::KeywordOrderBy) {
+            return Ok((vec![], input));
+        }
+        let _order_by = input.next();
 
-    #[test]
-    fn create_table() -> Result<()> {
-        let mut p = Parser::new();
-        let mut input = Input::new(vec![
-            Token::Create,
-            Token::Table,
-            Token::Identifier("person".to_owned()),
-            Token::LeftParen,
-            Token::Identifier("name".to_owned()),
-            Token::KeywordVarchar,
-            Token::KeywordPrimaryKey,
-            Token::Comma,
-            Token::Identifier("age".to_owned()),
-            Token::KeywordInteger,
-            Token::RightParen,
-            Token::Semicolon,
-            Token::EOF,
+        let mut order_by = Vec::new();
+        loop {
+            let expr = ExprParser::expr(&mut input)?;
+            let order = match input.peek() {
+                Some(&Token::KeywordDesc) => {
+                    let _desc = input.next();
+                    SortOrder::Descending
+                }
+                Some(&Token::KeywordAsc) => {
+                    let _asc = input.next();
+                    SortOrder::Ascending
+                }
+                _ => SortOrder::Ascending,
+            };
+            let nulls = match input.peek() {
+                Some(&Token::KeywordNullsFirst) => {
+                    let _nulls_first = input.next();
+                    Some(NullsOrder::First)
+                }
+                Some(&Token::KeywordNullsLast) => {
+                    let _nulls_last = input.next();
+                    Some(NullsOrder::Last)
+                }
+                _ => None,
+            };
+            order_by.push(OrderByExpr { expr, order, nulls });
+
+            match input.peek() {
+                Some(&Token::Comma) => {
+                    let _comma = input.next();
+                }
+                _ => return Ok((order_by, input)),
+            }
+        }
+    }
+
+    fn limit_clause(&self, mut input: Input) -> Result<Option<LimitClause>> {
+        let mut count = None;
+        let mut offset = None;
+        loop {
+            match input.peek() {
+                Some(&Token::KeywordLimit) if count.is_none() => {
+                    let _limit = input.next();
+                    count = Some(self.row_count(input.next())?);
+                }
+                Some(&Token::KeywordFetchFirst) if count.is_none() => {
+                    let _fetch_first = input.next();
+                    count = Some(self.row_count(input.next())?);
+                    let _ = ParseHelper::match_token(Token::KeywordRows, input.next())?;
+                    let _ = ParseHelper::match_token(Token::KeywordOnly, input.next())?;
+                }
+                Some(&Token::KeywordOffset) if offset.is_none() => {
+                    let _offset = input.next();
+                    offset = Some(self.row_count(input.next())?);
+                    if input.peek() == Some(&Token::KeywordRows) {
+                        let _rows = input.next();
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        match (count, offset) {
+            (None, None) => Ok((None, input)),
+            (count, offset) => Ok((
+                Some(LimitClause {
+                    count,
+                    offset: offset.unwrap_or(0),
+                }),
+                input,
+            )),
+        }
+    }
+
+    fn row_count(&self, token: Option<Token>) -> std::result::Result<u32, ParseError> {
+        match token {
+            Some(Token::Integer(count)) => Ok(count),
+            Some(got) => Err(ParseError::token_mismatch(Token::Integer(0), got)),
+            None => Err(ParseError::unexpected_eof(Token::Integer(0))),
+        }
+    }
+
+    // The AS of a table alias is optional e.g `from person p`.
+    fn table_alias(&self, mut input: Input) -> Result<Option<String>> {
+        match input.peek() {
+            Some(Token::Identifier(_)) => {
+                let alias = ParseHelper::match_identifier(input.next())?;
+                Ok((Some(alias), input))
+            }
+            _ => self.match_alias(input),
+        }
+    }
+
+    fn match_alias(&self, mut input: Input) -> Result<Option<String>> {
+        match input.peek() {
+            Some(&Token::KeywordAs) => {
+                let _as = input.next();
+                let alias = ParseHelper::match_identifier(input.next())?;
+                Ok((Some(alias), input))
+            }
+            _ => Ok((None, input)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    type Result<T> = std::result::Result<T, ParseError>;
+
+    #[test]
+    fn create_table() -> Result<()> {
+        let mut p = Parser::new();
+        let mut input = Input::new(vec![
+            Token::Create,
+            Token::Table,
+            Token::Identifier("person".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("name".to_owned()),
+            Token::KeywordVarchar,
+            Token::KeywordPrimaryKey,
+            Token::Comma,
+            Token::Identifier("age".to_owned()),
+            Token::KeywordInteger,
+            Token::RightParen,
+            Token::Semicolon,
+            Token::EOF,
         ]);
 
         let (create, _) = p.create_table_stmt(input)?;
@@ -357,57 +940,1092 @@ mod test {
     }
 
     #[test]
-    fn insert() -> Result<()> {
+    fn create_type() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Create,
+            Token::Type,
+            Token::Identifier("mood".to_owned()),
+            Token::KeywordAs,
+            Token::KeywordEnum,
+            Token::LeftParen,
+            Token::StringLiteral("sad".to_owned()),
+            Token::Comma,
+            Token::StringLiteral("ok".to_owned()),
+            Token::Comma,
+            Token::StringLiteral("happy".to_owned()),
+            Token::RightParen,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        match p.parse(input)? {
+            Stmt::CreateType(stmt) => assert_eq!(
+                stmt,
+                CreateTypeStmt {
+                    type_name: "mood".to_owned(),
+                    labels: vec!["sad".to_owned(), "ok".to_owned(), "happy".to_owned()],
+                }
+            ),
+            _ => panic!("expected a CREATE TYPE statement"),
+        }
+
+        let input = Input::new(vec![
+            Token::Create,
+            Token::Table,
+            Token::Identifier("person".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("current_mood".to_owned()),
+            Token::Identifier("mood".to_owned()),
+            Token::RightParen,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        let (create, _) = p.create_table_stmt(input)?;
+        assert_eq!(
+            create.attribute_definitions[0].attribute_type,
+            AttributeType::Named("mood".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_table_with_lengths() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Create,
+            Token::Table,
+            Token::Identifier("person".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("name".to_owned()),
+            Token::KeywordVarchar,
+            Token::LeftParen,
+            Token::Integer(10),
+            Token::RightParen,
+            Token::KeywordPrimaryKey,
+            Token::Comma,
+            Token::Identifier("code".to_owned()),
+            Token::KeywordChar,
+            Token::LeftParen,
+            Token::Integer(3),
+            Token::RightParen,
+            Token::Comma,
+            Token::Identifier("grade".to_owned()),
+            Token::KeywordChar,
+            Token::RightParen,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (create, _) = p.create_table_stmt(input)?;
+        assert_eq!(
+            create
+                .attribute_definitions
+                .into_iter()
+                .map(|def| def.attribute_type)
+                .collect::<Vec<_>>(),
+            vec![
+                AttributeType::Varchar(10),
+                AttributeType::Char(3),
+                AttributeType::Char(1),
+            ]
+        );
+
+        let input = Input::new(vec![
+            Token::Create,
+            Token::Table,
+            Token::Identifier("person".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("name".to_owned()),
+            Token::KeywordVarchar,
+            Token::LeftParen,
+            Token::Integer(0),
+            Token::RightParen,
+            Token::RightParen,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        assert!(p.create_table_stmt(input).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert() -> Result<()> {
+        let mut p = Parser::new();
+        let mut input = Input::new(vec![
+            Token::Insert,
+            Token::KeywordInto,
+            Token::Identifier("person".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("name".to_owned()),
+            Token::Comma,
+            Token::Identifier("age".to_owned()),
+            Token::RightParen,
+            Token::KeywordValues,
+            Token::LeftParen,
+            Token::StringLiteral("bob".to_owned()),
+            Token::Comma,
+            Token::Integer(10),
+            Token::Plus,
+            Token::Integer(20),
+            Token::RightParen,
+            Token::Comma,
+            Token::LeftParen,
+            Token::StringLiteral("alice".to_owned()),
+            Token::Comma,
+            Token::Integer(7),
+            Token::RightParen,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (insert, _) = p.insert_stmt(input)?;
+        assert_eq!(
+            insert,
+            InsertStmt {
+                table_name: "person".to_owned(),
+                attribute_names: vec!["name".to_owned(), "age".to_owned()],
+                rows: vec![
+                    vec![
+                        AttributeValue::String("bob".to_owned()),
+                        AttributeValue::Expr(Expr::Binary(BinaryExpr {
+                            left: Box::new(Expr::Literal(LiteralExpr::Integer(10))),
+                            op: BinaryOperation::Addition,
+                            right: Box::new(Expr::Literal(LiteralExpr::Integer(20))),
+                        }))
+                    ],
+                    vec![
+                        AttributeValue::String("alice".to_owned()),
+                        AttributeValue::Expr(Expr::Literal(LiteralExpr::Integer(7))),
+                    ]
+                ],
+                on_conflict: None,
+                returning: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_on_conflict() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Insert,
+            Token::KeywordInto,
+            Token::Identifier("person".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("name".to_owned()),
+            Token::Comma,
+            Token::Identifier("age".to_owned()),
+            Token::RightParen,
+            Token::KeywordValues,
+            Token::LeftParen,
+            Token::StringLiteral("bob".to_owned()),
+            Token::Comma,
+            Token::Integer(10),
+            Token::RightParen,
+            Token::KeywordOn,
+            Token::Identifier("conflict".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("name".to_owned()),
+            Token::RightParen,
+            Token::KeywordDo,
+            Token::Update,
+            Token::KeywordSet,
+            Token::Identifier("age".to_owned()),
+            Token::Equal,
+            Token::Identifier("excluded.age".to_owned()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (insert, _) = p.insert_stmt(input)?;
+        assert_eq!(
+            insert.on_conflict,
+            Some(OnConflict {
+                target: Some("name".to_owned()),
+                action: ConflictAction::DoUpdate(vec![Assignment {
+                    attribute_name: "age".to_owned(),
+                    value: Expr::Literal(LiteralExpr::Identifier("excluded.age".to_owned())),
+                }]),
+            })
+        );
+
+        let input = Input::new(vec![
+            Token::Insert,
+            Token::KeywordInto,
+            Token::Identifier("person".to_owned()),
+            Token::LeftParen,
+            Token::Identifier("name".to_owned()),
+            Token::RightParen,
+            Token::KeywordValues,
+            Token::LeftParen,
+            Token::StringLiteral("bob".to_owned()),
+            Token::RightParen,
+            Token::KeywordOn,
+            Token::Identifier("CONFLICT".to_owned()),
+            Token::KeywordDo,
+            Token::Identifier("nothing".to_owned()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (insert, _) = p.insert_stmt(input)?;
+        assert_eq!(
+            insert.on_conflict,
+            Some(OnConflict {
+                target: None,
+                action: ConflictAction::DoNothing,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Update,
+            Token::Identifier("person".to_owned()),
+            Token::KeywordSet,
+            Token::Identifier("age".to_owned()),
+            Token::Equal,
+            Token::Identifier("age".to_owned()),
+            Token::Plus,
+            Token::Integer(1),
+            Token::Comma,
+            Token::Identifier("name".to_owned()),
+            Token::Equal,
+            Token::StringLiteral("bob".to_owned()),
+            Token::Where,
+            Token::Identifier("age".to_owned()),
+            Token::GreaterThan,
+            Token::Integer(10),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (update, _) = p.update_stmt(input)?;
+        assert_eq!(
+            update,
+            UpdateStmt {
+                table_name: "person".to_owned(),
+                assignments: vec![
+                    Assignment {
+                        attribute_name: "age".to_owned(),
+                        value: Expr::Binary(BinaryExpr {
+                            left: Box::new(Expr::Literal(LiteralExpr::Identifier(
+                                "age".to_owned()
+                            ))),
+                            op: BinaryOperation::Addition,
+                            right: Box::new(Expr::Literal(LiteralExpr::Integer(1))),
+                        }),
+                    },
+                    Assignment {
+                        attribute_name: "name".to_owned(),
+                        value: Expr::Literal(LiteralExpr::String("bob".to_owned())),
+                    },
+                ],
+                where_clause: WhereClause::Expr(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Literal(LiteralExpr::Identifier("age".to_owned()))),
+                    op: BinaryOperation::GreaterThan,
+                    right: Box::new(Expr::Literal(LiteralExpr::Integer(10))),
+                })),
+                returning: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn delete() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Delete,
+            Token::From,
+            Token::Identifier("person".to_owned()),
+            Token::Where,
+            Token::Identifier("age".to_owned()),
+            Token::LessThan,
+            Token::Integer(10),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (delete, _) = p.delete_stmt(input)?;
+        assert_eq!(
+            delete,
+            DeleteStmt {
+                table_name: "person".to_owned(),
+                where_clause: WhereClause::Expr(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Literal(LiteralExpr::Identifier("age".to_owned()))),
+                    op: BinaryOperation::LessThan,
+                    right: Box::new(Expr::Literal(LiteralExpr::Integer(10))),
+                })),
+                returning: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncate() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Truncate,
+            Token::Table,
+            Token::Identifier("person".to_owned()),
+            Token::Comma,
+            Token::Identifier("account".to_owned()),
+            Token::KeywordRestartIdentity,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (truncate, _) = p.truncate_stmt(input)?;
+        assert_eq!(
+            truncate,
+            TruncateStmt {
+                table_names: vec!["person".to_owned(), "account".to_owned()],
+                restart_identity: true,
+            }
+        );
+
+        let input = Input::new(vec![
+            Token::Truncate,
+            Token::Identifier("person".to_owned()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (truncate, _) = p.truncate_stmt(input)?;
+        assert_eq!(
+            truncate,
+            TruncateStmt {
+                table_names: vec!["person".to_owned()],
+                restart_identity: false,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Merge,
+            Token::KeywordInto,
+            Token::Identifier("account".to_owned()),
+            Token::KeywordAs,
+            Token::Identifier("t".to_owned()),
+            Token::KeywordUsing,
+            Token::Identifier("staging".to_owned()),
+            Token::KeywordOn,
+            Token::Identifier("t.id".to_owned()),
+            Token::Equal,
+            Token::Identifier("staging.id".to_owned()),
+            Token::KeywordWhen,
+            Token::KeywordMatched,
+            Token::And,
+            Token::Identifier("staging.balance".to_owned()),
+            Token::Equal,
+            Token::Integer(0),
+            Token::KeywordThen,
+            Token::Delete,
+            Token::KeywordWhen,
+            Token::KeywordMatched,
+            Token::KeywordThen,
+            Token::Update,
+            Token::KeywordSet,
+            Token::Identifier("balance".to_owned()),
+            Token::Equal,
+            Token::Identifier("staging.balance".to_owned()),
+            Token::KeywordWhen,
+            Token::KeywordNotMatched,
+            Token::KeywordThen,
+            Token::Insert,
+            Token::KeywordValues,
+            Token::LeftParen,
+            Token::Identifier("staging.id".to_owned()),
+            Token::Comma,
+            Token::Identifier("staging.balance".to_owned()),
+            Token::RightParen,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let identifier = |id: &str| Expr::Literal(LiteralExpr::Identifier(id.to_owned()));
+        let (merge, _) = p.merge_stmt(input)?;
+        assert_eq!(
+            merge,
+            MergeStmt {
+                target_table: "account".to_owned(),
+                target_alias: Some("t".to_owned()),
+                source: FromClause::Table("staging".to_owned()),
+                source_alias: None,
+                predicate: WhereClause::Expr(Expr::Binary(BinaryExpr {
+                    left: Box::new(identifier("t.id")),
+                    op: BinaryOperation::Equal,
+                    right: Box::new(identifier("staging.id")),
+                })),
+                when_clauses: vec![
+                    MergeWhenClause {
+                        matched: true,
+                        condition: Some(Expr::Binary(BinaryExpr {
+                            left: Box::new(identifier("staging.balance")),
+                            op: BinaryOperation::Equal,
+                            right: Box::new(Expr::Literal(LiteralExpr::Integer(0))),
+                        })),
+                        action: MergeAction::Delete,
+                    },
+                    MergeWhenClause {
+                        matched: true,
+                        condition: None,
+                        action: MergeAction::Update(vec![Assignment {
+                            attribute_name: "balance".to_owned(),
+                            value: identifier("staging.balance"),
+                        }]),
+                    },
+                    MergeWhenClause {
+                        matched: false,
+                        condition: None,
+                        action: MergeAction::Insert {
+                            attribute_names: None,
+                            values: vec![identifier("staging.id"), identifier("staging.balance")],
+                        },
+                    },
+                ],
+            }
+        );
+
+        // Unmatched rows can only be inserted.
+        let input = Input::new(vec![
+            Token::Merge,
+            Token::KeywordInto,
+            Token::Identifier("account".to_owned()),
+            Token::KeywordUsing,
+            Token::Identifier("staging".to_owned()),
+            Token::KeywordOn,
+            Token::True,
+            Token::KeywordWhen,
+            Token::KeywordNotMatched,
+            Token::KeywordThen,
+            Token::Delete,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        assert!(p.merge_stmt(input).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn returning() -> Result<()> {
+        let mut p = Parser::new();
+        let input = Input::new(vec![
+            Token::Delete,
+            Token::From,
+            Token::Identifier("person".to_owned()),
+            Token::KeywordReturning,
+            Token::Star,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (delete, _) = p.delete_stmt(input)?;
+        assert_eq!(delete.returning, Some(SelectProperties::Star));
+
+        let input = Input::new(vec![
+            Token::Update,
+            Token::Identifier("person".to_owned()),
+            Token::KeywordSet,
+            Token::Identifier("age".to_owned()),
+            Token::Equal,
+            Token::Integer(1),
+            Token::KeywordReturning,
+            Token::Identifier("name".to_owned()),
+            Token::Comma,
+            Token::Identifier("age".to_owned()),
+            Token::Plus,
+            Token::Integer(1),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (update, _) = p.update_stmt(input)?;
+        assert_eq!(
+            update.returning,
+            Some(SelectProperties::Exprs(vec![
+                SelectItem {
+                    expr: Expr::Literal(LiteralExpr::Identifier("name".to_owned())),
+                    alias: None,
+                },
+                SelectItem {
+                    expr: Expr::Binary(BinaryExpr {
+                        left: Box::new(Expr::Literal(LiteralExpr::Identifier("age".to_owned()))),
+                        op: BinaryOperation::Addition,
+                        right: Box::new(Expr::Literal(LiteralExpr::Integer(1))),
+                    }),
+                    alias: None,
+                },
+            ]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_select_exprs_with_alias() -> Result<()> {
+        let mut p = Parser::new();
+        let properties_of = |select: SelectStmt| match select {
+            SelectStmt::Select(select) => select.properties,
+            other => panic!("unexpected statement {:?}", other),
+        };
+
+        // select age + 1 as next_age, name from person;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Identifier("age".to_string()),
+            Token::Plus,
+            Token::Integer(1),
+            Token::KeywordAs,
+            Token::Identifier("next_age".to_string()),
+            Token::Comma,
+            Token::Identifier("name".to_string()),
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        let (select, _) = p.select_stmt(input, true)?;
+        assert_eq!(
+            properties_of(select),
+            SelectProperties::Exprs(vec![
+                SelectItem {
+                    expr: Expr::Binary(BinaryExpr {
+                        left: Box::new(Expr::Literal(LiteralExpr::Identifier("age".to_owned()))),
+                        op: BinaryOperation::Addition,
+                        right: Box::new(Expr::Literal(LiteralExpr::Integer(1))),
+                    }),
+                    alias: Some("next_age".to_owned()),
+                },
+                SelectItem {
+                    expr: Expr::Literal(LiteralExpr::Identifier("name".to_owned())),
+                    alias: None,
+                },
+            ])
+        );
+
+        // select name as n from person;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Identifier("name".to_string()),
+            Token::KeywordAs,
+            Token::Identifier("n".to_string()),
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        let (select, _) = p.select_stmt(input, true)?;
+        assert_eq!(
+            properties_of(select),
+            SelectProperties::Exprs(vec![SelectItem {
+                expr: Expr::Literal(LiteralExpr::Identifier("name".to_owned())),
+                alias: Some("n".to_owned()),
+            }])
+        );
+
+        // select name as from person;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Identifier("name".to_string()),
+            Token::KeywordAs,
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        assert!(p.select_stmt(input, true).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_select_star_from() -> Result<()> {
+        let mut p = Parser::new();
+        let mut input = Input::new(vec![
+            Token::Select,
+            Token::Star,
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (select, _) = p.select_stmt(input, true)?;
+        assert_eq!(
+            select,
+            SelectStmt::Select(Box::new(SingleSelectStmt {
+                properties: SelectProperties::Star,
+                from_clause: FromClause::Table("person".to_string()),
+                where_clause: WhereClause::None,
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: None,
+            }))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_select_attributes_from() -> Result<()> {
+        fn run_test(with_parenthesis: bool) -> Result<()> {
+            let mut p = Parser::new();
+            let mut input = Input::new(
+                [
+                    vec![
+                        Token::Select,
+                        Token::Identifier("name".to_string()),
+                        Token::Comma,
+                        Token::Identifier("age".to_string()),
+                        Token::From,
+                        Token::Identifier("person".to_string()),
+                        Token::Semicolon,
+                        Token::EOF,
+                    ],
+                    if with_parenthesis {
+                        vec![Token::LeftParen]
+                    } else {
+                        vec![]
+                    },
+                    vec![Token::Identifier("person".to_string())],
+                    if with_parenthesis {
+                        vec![Token::RightParen]
+                    } else {
+                        vec![]
+                    },
+                    vec![Token::Semicolon, Token::EOF],
+                ]
+                .concat(),
+            );
+
+            let (select, _) = p.select_stmt(input, true)?;
+            assert_eq!(
+                select,
+                SelectStmt::Select(Box::new(SingleSelectStmt {
+                    properties: SelectProperties::Identifiers(vec![
+                        "name".to_owned(),
+                        "age".to_owned()
+                    ]),
+                    from_clause: FromClause::Table("person".to_string()),
+                    where_clause: WhereClause::None,
+                    order_by: vec![],
+                    limit: None,
+                    distinct: None,
+                    group_by: vec![],
+                    having: None,
+                    alias: None,
+                }))
+            );
+
+            Ok(())
+        }
+
+        run_test(true)?;
+        run_test(false)
+    }
+
+    #[test]
+    fn parse_select_attributes_from_where() -> Result<()> {
+        let mut p = Parser::new();
+        let mut input = Input::new(vec![
+            Token::Select,
+            Token::Identifier("name".to_string()),
+            Token::Comma,
+            Token::Identifier("age".to_string()),
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Where,
+            Token::True,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (select, _) = p.select_stmt(input, true)?;
+        assert_eq!(
+            select,
+            SelectStmt::Select(Box::new(SingleSelectStmt {
+                properties: SelectProperties::Identifiers(vec![
+                    "name".to_owned(),
+                    "age".to_owned()
+                ]),
+                from_clause: FromClause::Table("person".to_string()),
+                where_clause: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(true))),
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: None,
+            }))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_select_order_by() -> Result<()> {
+        // select name from person where true order by age desc nulls first, -age, name asc;
+        let mut p = Parser::new();
+        let mut input = Input::new(vec![
+            Token::Select,
+            Token::Identifier("name".to_string()),
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Where,
+            Token::True,
+            Token::KeywordOrderBy,
+            Token::Identifier("age".to_string()),
+            Token::KeywordDesc,
+            Token::KeywordNullsFirst,
+            Token::Comma,
+            Token::Minus,
+            Token::Identifier("age".to_string()),
+            Token::Comma,
+            Token::Identifier("name".to_string()),
+            Token::KeywordAsc,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (select, _) = p.select_stmt(input, true)?;
+        assert_eq!(
+            select,
+            SelectStmt::Select(Box::new(SingleSelectStmt {
+                properties: SelectProperties::Identifiers(vec!["name".to_owned()]),
+                from_clause: FromClause::Table("person".to_string()),
+                where_clause: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(true))),
+                order_by: vec![
+                    OrderByExpr {
+                        expr: Expr::Literal(LiteralExpr::Identifier("age".to_owned())),
+                        order: SortOrder::Descending,
+                        nulls: Some(NullsOrder::First),
+                    },
+                    OrderByExpr {
+                        expr: Expr::Unary(UnaryExpr {
+                            op: UnaryOperation::Negation,
+                            operand: Box::new(Expr::Literal(LiteralExpr::Identifier(
+                                "age".to_owned()
+                            ))),
+                        }),
+                        order: SortOrder::Ascending,
+                        nulls: None,
+                    },
+                    OrderByExpr {
+                        expr: Expr::Literal(LiteralExpr::Identifier("name".to_owned())),
+                        order: SortOrder::Ascending,
+                        nulls: None,
+                    },
+                ],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: None,
+            }))
+        );
+
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Star,
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::KeywordOrderBy,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        assert!(p.select_stmt(input, true).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_select_limit() -> Result<()> {
+        let mut p = Parser::new();
+        let select_with = |tail: Vec<Token>| {
+            let mut tokens = vec![
+                Token::Select,
+                Token::Star,
+                Token::From,
+                Token::Identifier("person".to_string()),
+            ];
+            tokens.extend(tail);
+            tokens.extend(vec![Token::Semicolon, Token::EOF]);
+            Input::new(tokens)
+        };
+        let limit_of = |select: SelectStmt| match select {
+            SelectStmt::Select(select) => select.limit,
+            other => panic!("unexpected statement {:?}", other),
+        };
+
+        // limit 10 offset 5
+        let (select, _) = p.select_stmt(
+            select_with(vec![
+                Token::KeywordLimit,
+                Token::Integer(10),
+                Token::KeywordOffset,
+                Token::Integer(5),
+            ]),
+            true,
+        )?;
+        assert_eq!(
+            limit_of(select),
+            Some(LimitClause {
+                count: Some(10),
+                offset: 5
+            })
+        );
+
+        // offset 5 rows fetch first 1 row only
+        let (select, _) = p.select_stmt(
+            select_with(vec![
+                Token::KeywordOffset,
+                Token::Integer(5),
+                Token::KeywordRows,
+                Token::KeywordFetchFirst,
+                Token::Integer(1),
+                Token::KeywordRows,
+                Token::KeywordOnly,
+            ]),
+            true,
+        )?;
+        assert_eq!(
+            limit_of(select),
+            Some(LimitClause {
+                count: Some(1),
+                offset: 5
+            })
+        );
+
+        // offset 2
+        let (select, _) = p.select_stmt(
+            select_with(vec![Token::KeywordOffset, Token::Integer(2)]),
+            true,
+        )?;
+        assert_eq!(
+            limit_of(select),
+            Some(LimitClause {
+                count: None,
+                offset: 2
+            })
+        );
+
+        // limit 1 limit 2
+        assert!(p
+            .select_stmt(
+                select_with(vec![
+                    Token::KeywordLimit,
+                    Token::Integer(1),
+                    Token::KeywordLimit,
+                    Token::Integer(2),
+                ]),
+                true,
+            )
+            .is_err());
+        // fetch first 1 rows
+        assert!(p
+            .select_stmt(
+                select_with(vec![
+                    Token::KeywordFetchFirst,
+                    Token::Integer(1),
+                    Token::KeywordRows,
+                ]),
+                true,
+            )
+            .is_err());
+        // limit -1
+        assert!(p
+            .select_stmt(
+                select_with(vec![Token::KeywordLimit, Token::Minus, Token::Integer(1)]),
+                true,
+            )
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_select_distinct() -> Result<()> {
+        let mut p = Parser::new();
+        let distinct_of = |select: SelectStmt| match select {
+            SelectStmt::Select(select) => (select.distinct, select.properties),
+            other => panic!("unexpected statement {:?}", other),
+        };
+
+        // select distinct age from person;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Distinct,
+            Token::Identifier("age".to_string()),
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        let (select, _) = p.select_stmt(input, true)?;
+        assert_eq!(
+            distinct_of(select),
+            (
+                Some(Distinct::Rows),
+                SelectProperties::Identifiers(vec!["age".to_owned()])
+            )
+        );
+
+        // select distinct on (age, name) * from person;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Distinct,
+            Token::KeywordOn,
+            Token::LeftParen,
+            Token::Identifier("age".to_string()),
+            Token::Comma,
+            Token::Identifier("name".to_string()),
+            Token::RightParen,
+            Token::Star,
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        let (select, _) = p.select_stmt(input, true)?;
+        assert_eq!(
+            distinct_of(select),
+            (
+                Some(Distinct::On(vec![
+                    Expr::Literal(LiteralExpr::Identifier("age".to_owned())),
+                    Expr::Literal(LiteralExpr::Identifier("name".to_owned())),
+                ])),
+                SelectProperties::Star
+            )
+        );
+
+        // select distinct on age * from person;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Distinct,
+            Token::KeywordOn,
+            Token::Identifier("age".to_string()),
+            Token::Star,
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        assert!(p.select_stmt(input, true).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_select_group_by() -> Result<()> {
         let mut p = Parser::new();
-        let mut input = Input::new(vec![
-            Token::Insert,
-            Token::KeywordInto,
-            Token::Identifier("person".to_owned()),
-            Token::LeftParen,
-            Token::Identifier("name".to_owned()),
+        let identifier = |name: &str| Expr::Literal(LiteralExpr::Identifier(name.to_owned()));
+
+        // select city, count(*) from person group by city having count(*) > 1;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Identifier("city".to_string()),
             Token::Comma,
-            Token::Identifier("age".to_owned()),
+            Token::Identifier("count".to_string()),
+            Token::LeftParen,
+            Token::Star,
             Token::RightParen,
-            Token::KeywordValues,
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::KeywordGroupBy,
+            Token::Identifier("city".to_string()),
+            Token::KeywordHaving,
+            Token::Identifier("count".to_string()),
             Token::LeftParen,
-            Token::StringLiteral("bob".to_owned()),
-            Token::Comma,
-            Token::Integer(10),
-            Token::Plus,
-            Token::Integer(20),
+            Token::Star,
             Token::RightParen,
+            Token::GreaterThan,
+            Token::Integer(1),
             Token::Semicolon,
             Token::EOF,
         ]);
-
-        let (insert, _) = p.insert_stmt(input)?;
-        assert_eq!(
-            insert,
-            InsertStmt {
-                table_name: "person".to_owned(),
-                attribute_names: vec!["name".to_owned(), "age".to_owned()],
-                attribute_values: vec![
-                    AttributeValue::String("bob".to_owned()),
-                    AttributeValue::Expr(Expr::Binary(BinaryExpr {
-                        left: Box::new(Expr::Literal(LiteralExpr::Integer(10))),
-                        op: BinaryOperation::Addition,
-                        right: Box::new(Expr::Literal(LiteralExpr::Integer(20))),
+        let count = || {
+            Expr::Aggregate(AggregateCall {
+                function: AggregateFunction::Count,
+                arg: None,
+            })
+        };
+        let (select, _) = p.select_stmt(input, true)?;
+        match select {
+            SelectStmt::Select(select) => {
+                assert_eq!(
+                    select.properties,
+                    SelectProperties::Exprs(vec![
+                        SelectItem {
+                            expr: identifier("city"),
+                            alias: None,
+                        },
+                        SelectItem {
+                            expr: count(),
+                            alias: None,
+                        },
+                    ])
+                );
+                assert_eq!(select.group_by, vec![identifier("city")]);
+                assert_eq!(
+                    select.having,
+                    Some(Expr::Binary(BinaryExpr {
+                        left: Box::new(count()),
+                        op: BinaryOperation::GreaterThan,
+                        right: Box::new(Expr::Literal(LiteralExpr::Integer(1))),
                     }))
-                ]
+                );
             }
-        );
+            other => panic!("unexpected statement {:?}", other),
+        }
+
+        // select city from person group by;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Identifier("city".to_string()),
+            Token::From,
+            Token::Identifier("person".to_string()),
+            Token::KeywordGroupBy,
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+        assert!(p.select_stmt(input, true).is_err());
 
         Ok(())
     }
 
     #[test]
-    fn parse_select_star_from() -> Result<()> {
+    fn parse_select_star_from_as() -> Result<()> {
         let mut p = Parser::new();
         let mut input = Input::new(vec![
             Token::Select,
             Token::Star,
             Token::From,
             Token::Identifier("person".to_string()),
+            Token::KeywordAs,
+            Token::Identifier("employee".to_string()),
             Token::Semicolon,
             Token::EOF,
         ]);
@@ -415,81 +2033,48 @@ mod test {
         let (select, _) = p.select_stmt(input, true)?;
         assert_eq!(
             select,
-            SelectStmt::Select(SingleSelectStmt {
+            SelectStmt::Select(Box::new(SingleSelectStmt {
                 properties: SelectProperties::Star,
                 from_clause: FromClause::Table("person".to_string()),
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: Some("employee".to_string()),
                 where_clause: WhereClause::None,
-                alias: None,
-            })
+            }))
         );
 
         Ok(())
     }
 
     #[test]
-    fn parse_select_attributes_from() -> Result<()> {
-        fn run_test(with_parenthesis: bool) -> Result<()> {
-            let mut p = Parser::new();
-            let mut input = Input::new(
-                [
-                    vec![
-                        Token::Select,
-                        Token::Identifier("name".to_string()),
-                        Token::Comma,
-                        Token::Identifier("age".to_string()),
-                        Token::From,
-                        Token::Identifier("person".to_string()),
-                        Token::Semicolon,
-                        Token::EOF,
-                    ],
-                    if with_parenthesis {
-                        vec![Token::LeftParen]
-                    } else {
-                        vec![]
-                    },
-                    vec![Token::Identifier("person".to_string())],
-                    if with_parenthesis {
-                        vec![Token::RightParen]
-                    } else {
-                        vec![]
-                    },
-                    vec![Token::Semicolon, Token::EOF],
-                ]
-                .concat(),
-            );
-
-            let (select, _) = p.select_stmt(input, true)?;
-            assert_eq!(
-                select,
-                SelectStmt::Select(SingleSelectStmt {
-                    properties: SelectProperties::Identifiers(vec![
-                        "name".to_owned(),
-                        "age".to_owned()
-                    ]),
-                    from_clause: FromClause::Table("person".to_string()),
-                    where_clause: WhereClause::None,
-                    alias: None,
-                })
-            );
-
-            Ok(())
-        }
-
-        run_test(true)?;
-        run_test(false)
-    }
-
-    #[test]
-    fn parse_select_attributes_from_where() -> Result<()> {
+    fn parse_inner_join() -> Result<()> {
         let mut p = Parser::new();
+        // select person.age, employee.id from foo as person
+        //  inner join (select * from bar where false) as employee on true;
         let mut input = Input::new(vec![
             Token::Select,
-            Token::Identifier("name".to_string()),
+            Token::Identifier("person.age".to_string()),
             Token::Comma,
-            Token::Identifier("age".to_string()),
+            Token::Identifier("employee.id".to_string()),
             Token::From,
+            Token::Identifier("foo".to_string()),
+            Token::KeywordAs,
             Token::Identifier("person".to_string()),
+            Token::KeywordInnerJoin,
+            Token::LeftParen,
+            Token::Select,
+            Token::Star,
+            Token::From,
+            Token::Identifier("bar".to_string()),
             Token::Where,
+            Token::False,
+            Token::RightParen,
+            Token::KeywordAs,
+            Token::Identifier("employee".to_string()),
+            Token::KeywordOn,
             Token::True,
             Token::Semicolon,
             Token::EOF,
@@ -498,113 +2083,336 @@ mod test {
         let (select, _) = p.select_stmt(input, true)?;
         assert_eq!(
             select,
-            SelectStmt::Select(SingleSelectStmt {
+            SelectStmt::Select(Box::new(SingleSelectStmt {
                 properties: SelectProperties::Identifiers(vec![
-                    "name".to_owned(),
-                    "age".to_owned()
+                    "person.age".to_owned(),
+                    "employee.id".to_owned()
                 ]),
-                from_clause: FromClause::Table("person".to_string()),
-                where_clause: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(true))),
-                alias: None,
-            })
+                from_clause: FromClause::Join(Box::new(JoinClause {
+                    join_type: JoinType::Inner,
+                    left: TableReference {
+                        from_clause: FromClause::Table("foo".to_owned()),
+                        alias: Some("person".to_owned())
+                    },
+                    right: TableReference {
+                        from_clause: FromClause::Select(Box::new(SelectStmt::Select(Box::new(
+                            SingleSelectStmt {
+                                properties: SelectProperties::Star,
+                                from_clause: FromClause::Table("bar".to_owned()),
+                                where_clause: WhereClause::Expr(Expr::Literal(
+                                    LiteralExpr::Boolean(false)
+                                )),
+                                order_by: vec![],
+                                limit: None,
+                                distinct: None,
+                                group_by: vec![],
+                                having: None,
+                                alias: None
+                            }
+                        )))),
+                        alias: Some("employee".to_owned())
+                    },
+                    constraint: JoinConstraint::On(Expr::Literal(LiteralExpr::Boolean(true)))
+                })),
+                where_clause: WhereClause::None,
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: None
+            }))
         );
 
         Ok(())
     }
 
     #[test]
-    fn parse_select_star_from_as() -> Result<()> {
+    fn parse_outer_joins() -> Result<()> {
         let mut p = Parser::new();
-        let mut input = Input::new(vec![
+        for (token, join_type) in [
+            (Token::KeywordLeftJoin, JoinType::Left),
+            (Token::KeywordRightJoin, JoinType::Right),
+            (Token::KeywordFullJoin, JoinType::Full),
+        ] {
+            // select * from foo left join bar on true;
+            let input = Input::new(vec![
+                Token::Select,
+                Token::Star,
+                Token::From,
+                Token::Identifier("foo".to_string()),
+                token,
+                Token::Identifier("bar".to_string()),
+                Token::KeywordOn,
+                Token::True,
+                Token::Semicolon,
+                Token::EOF,
+            ]);
+
+            let (select, _) = p.select_stmt(input, true)?;
+            match select {
+                SelectStmt::Select(select) => match select.from_clause {
+                    FromClause::Join(join) => assert_eq!(join.join_type, join_type),
+                    from_clause => panic!("expected a join, got {:?}", from_clause),
+                },
+                stmt => panic!("expected a join, got {:?}", stmt),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_join_list() -> Result<()> {
+        let mut p = Parser::new();
+        // select * from foo, bar join baz on true cross join (qux as q);
+        let input = Input::new(vec![
             Token::Select,
             Token::Star,
             Token::From,
-            Token::Identifier("person".to_string()),
+            Token::Identifier("foo".to_string()),
+            Token::Comma,
+            Token::Identifier("bar".to_string()),
+            Token::KeywordInnerJoin,
+            Token::Identifier("baz".to_string()),
+            Token::KeywordOn,
+            Token::True,
+            Token::KeywordCrossJoin,
+            Token::LeftParen,
+            Token::Identifier("qux".to_string()),
             Token::KeywordAs,
-            Token::Identifier("employee".to_string()),
+            Token::Identifier("q".to_string()),
+            Token::RightParen,
             Token::Semicolon,
             Token::EOF,
         ]);
 
+        let table = |name: &str, alias: Option<&str>| TableReference {
+            from_clause: FromClause::Table(name.to_owned()),
+            alias: alias.map(|alias| alias.to_owned()),
+        };
+        let join = |join_type, left, right, constraint| TableReference {
+            from_clause: FromClause::Join(Box::new(JoinClause {
+                join_type,
+                left,
+                right,
+                constraint,
+            })),
+            alias: None,
+        };
         let (select, _) = p.select_stmt(input, true)?;
         assert_eq!(
             select,
-            SelectStmt::Select(SingleSelectStmt {
+            SelectStmt::Select(Box::new(SingleSelectStmt {
                 properties: SelectProperties::Star,
-                from_clause: FromClause::Table("person".to_string()),
-                alias: Some("employee".to_string()),
+                from_clause: join(
+                    JoinType::Cross,
+                    table("foo", None),
+                    join(
+                        JoinType::Cross,
+                        join(
+                            JoinType::Inner,
+                            table("bar", None),
+                            table("baz", None),
+                            JoinConstraint::On(Expr::Literal(LiteralExpr::Boolean(true)))
+                        ),
+                        table("qux", Some("q")),
+                        JoinConstraint::None
+                    ),
+                    JoinConstraint::None
+                )
+                .from_clause,
                 where_clause: WhereClause::None,
-            })
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: None,
+            }))
         );
 
         Ok(())
     }
 
     #[test]
-    fn parse_inner_join() -> Result<()> {
+    fn parse_join_using() -> Result<()> {
         let mut p = Parser::new();
-        // select person.age, employee.id from foo as person
-        //  inner join (select * from bar where false) as employee on true;
-        let mut input = Input::new(vec![
+        // select * from foo join bar using (id, name) natural left join baz;
+        let input = Input::new(vec![
             Token::Select,
-            Token::Identifier("person.age".to_string()),
-            Token::Comma,
-            Token::Identifier("employee.id".to_string()),
+            Token::Star,
             Token::From,
             Token::Identifier("foo".to_string()),
-            Token::KeywordAs,
-            Token::Identifier("person".to_string()),
             Token::KeywordInnerJoin,
+            Token::Identifier("bar".to_string()),
+            Token::KeywordUsing,
             Token::LeftParen,
+            Token::Identifier("id".to_string()),
+            Token::Comma,
+            Token::Identifier("name".to_string()),
+            Token::RightParen,
+            Token::KeywordNatural,
+            Token::KeywordLeftJoin,
+            Token::Identifier("baz".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (select, _) = p.select_stmt(input, true)?;
+        let join = match select {
+            SelectStmt::Select(select) => match select.from_clause {
+                FromClause::Join(join) => join,
+                from_clause => panic!("expected a join, got {:?}", from_clause),
+            },
+            stmt => panic!("expected a join, got {:?}", stmt),
+        };
+        assert_eq!(join.join_type, JoinType::Left);
+        assert_eq!(join.constraint, JoinConstraint::Natural);
+        match join.left.from_clause {
+            FromClause::Join(join) => assert_eq!(
+                join.constraint,
+                JoinConstraint::Using(vec!["id".to_owned(), "name".to_owned()])
+            ),
+            from_clause => panic!("expected a join, got {:?}", from_clause),
+        }
+
+        // select * from foo natural cross join bar;
+        let input = Input::new(vec![
             Token::Select,
             Token::Star,
             Token::From,
+            Token::Identifier("foo".to_string()),
+            Token::KeywordNatural,
+            Token::KeywordCrossJoin,
             Token::Identifier("bar".to_string()),
-            Token::Where,
-            Token::False,
-            Token::RightParen,
-            Token::KeywordAs,
-            Token::Identifier("employee".to_string()),
-            Token::KeywordOn,
-            Token::True,
             Token::Semicolon,
             Token::EOF,
         ]);
+        assert!(p.select_stmt(input, true).is_err());
 
-        let (select, _) = p.select_stmt(input, true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn parse_set_operations() -> Result<()> {
+        let mut p = Parser::new();
+        // select * from foo union select * from bar intersect all select * from baz
+        //  except select * from qux order by id limit 1;
+        let select_from = |table: &str| {
+            vec![
+                Token::Select,
+                Token::Star,
+                Token::From,
+                Token::Identifier(table.to_owned()),
+            ]
+        };
+        let input = Input::new(
+            [
+                select_from("foo"),
+                vec![Token::KeywordUnion],
+                select_from("bar"),
+                vec![Token::KeywordIntersect, Token::KeywordAll],
+                select_from("baz"),
+                vec![Token::KeywordExcept],
+                select_from("qux"),
+                vec![
+                    Token::KeywordOrderBy,
+                    Token::Identifier("id".to_owned()),
+                    Token::KeywordLimit,
+                    Token::Integer(1),
+                    Token::Semicolon,
+                    Token::EOF,
+                ],
+            ]
+            .concat(),
+        );
+
+        let select = |table: &str| {
+            SelectStmt::Select(Box::new(SingleSelectStmt {
+                properties: SelectProperties::Star,
+                from_clause: FromClause::Table(table.to_owned()),
+                where_clause: WhereClause::None,
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: None,
+            }))
+        };
+        let set_operation = |op, all, left, right| {
+            SelectStmt::SetOperation(SetOperationStmt {
+                op,
+                all,
+                left: Box::new(left),
+                right: Box::new(right),
+                order_by: vec![],
+                limit: None,
+            })
+        };
+        let (stmt, _) = p.select_stmt(input, true)?;
         assert_eq!(
-            select,
-            SelectStmt::Join(JoinStmt {
-                join_type: JoinType::InnerJoin,
-                properties: SelectProperties::Identifiers(vec![
-                    "person.age".to_owned(),
-                    "employee.id".to_owned()
-                ]),
-                left: SingleSelectStmt {
-                    properties: SelectProperties::Star,
-                    from_clause: FromClause::Table("foo".to_owned()),
-                    where_clause: WhereClause::None,
-                    alias: Some("person".to_owned())
-                },
-                right: SingleSelectStmt {
-                    properties: SelectProperties::Star,
-                    from_clause: FromClause::Select(Box::new(SelectStmt::Select(
-                        SingleSelectStmt {
-                            properties: SelectProperties::Star,
-                            from_clause: FromClause::Table("bar".to_owned()),
-                            where_clause: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(
-                                false
-                            ))),
-                            alias: None
-                        }
-                    ))),
-                    where_clause: WhereClause::None,
-                    alias: Some("employee".to_owned())
-                },
-                predicate: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(true)))
+            stmt,
+            SelectStmt::SetOperation(SetOperationStmt {
+                op: SetOperator::Except,
+                all: false,
+                left: Box::new(set_operation(
+                    SetOperator::Union,
+                    false,
+                    select("foo"),
+                    set_operation(SetOperator::Intersect, true, select("bar"), select("baz"))
+                )),
+                right: Box::new(select("qux")),
+                order_by: vec![OrderByExpr {
+                    expr: Expr::Literal(LiteralExpr::Identifier("id".to_owned())),
+                    order: SortOrder::Ascending,
+                    nulls: None,
+                }],
+                limit: Some(LimitClause {
+                    count: Some(1),
+                    offset: 0
+                }),
             })
         );
 
         Ok(())
     }
+
+    #[test]
+    fn parse_table_alias_without_as() -> Result<()> {
+        let mut p = Parser::new();
+        // select * from foo f cross join (select * from bar) b;
+        let input = Input::new(vec![
+            Token::Select,
+            Token::Star,
+            Token::From,
+            Token::Identifier("foo".to_string()),
+            Token::Identifier("f".to_string()),
+            Token::KeywordCrossJoin,
+            Token::LeftParen,
+            Token::Select,
+            Token::Star,
+            Token::From,
+            Token::Identifier("bar".to_string()),
+            Token::RightParen,
+            Token::Identifier("b".to_string()),
+            Token::Semicolon,
+            Token::EOF,
+        ]);
+
+        let (select, _) = p.select_stmt(input, true)?;
+        let join = match select {
+            SelectStmt::Select(select) => match select.from_clause {
+                FromClause::Join(join) => join,
+                from_clause => panic!("expected a join, got {:?}", from_clause),
+            },
+            stmt => panic!("expected a join, got {:?}", stmt),
+        };
+        assert_eq!(join.left.alias, Some("f".to_owned()));
+        assert_eq!(join.left.from_clause, FromClause::Table("foo".to_owned()));
+        assert_eq!(join.right.alias, Some("b".to_owned()));
+
+        Ok(())
+    }
 }
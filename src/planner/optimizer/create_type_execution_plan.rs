@@ -0,0 +1,6 @@
+use crate::storage::types::EnumType;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CreateTypeExecutionPlan {
+    pub enum_type: EnumType,
+}
@@ -1,8 +1,14 @@
-use crate::storage::storage_manager::TableName;
+use crate::planner::plan::insert_plan::OnConflict;
+use crate::planner::plan::query_plan::{QueryResultSchema, ReturningClause};
+use crate::storage::storage_manager::{AttributeName, TableName};
 use crate::storage::tuple::TupleRecord;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct InsertTupleExecutionPlan {
     pub table_name: TableName,
-    pub tuple: TupleRecord,
+    pub schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub tuples: Vec<TupleRecord>,
+    pub on_conflict: OnConflict,
+    pub returning: Option<ReturningClause>,
 }
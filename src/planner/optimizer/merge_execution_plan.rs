@@ -0,0 +1,14 @@
+use crate::parser::ast::Expr;
+use crate::planner::plan::merge_plan::MergeWhenClause;
+use crate::planner::plan::query_plan::{QueryPlan, QueryResultSchema};
+use crate::storage::storage_manager::{AttributeName, TableName};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct MergeExecutionPlan {
+    pub table_name: TableName,
+    pub target_schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub source: QueryPlan,
+    pub predicate: Expr,
+    pub when_clauses: Vec<MergeWhenClause>,
+}
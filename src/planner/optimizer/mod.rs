@@ -1,20 +1,39 @@
 mod create_table_execution_plan;
+mod create_type_execution_plan;
+mod delete_execution_plan;
 mod insert_tuple_execution_plan;
+mod merge_execution_plan;
 mod query_execution_plan;
+mod truncate_execution_plan;
+mod update_execution_plan;
 
 pub(crate) use crate::planner::optimizer::create_table_execution_plan::CreateTableExecutionPlan;
+pub(crate) use crate::planner::optimizer::create_type_execution_plan::CreateTypeExecutionPlan;
+pub(crate) use crate::planner::optimizer::delete_execution_plan::DeleteExecutionPlan;
 pub(crate) use crate::planner::optimizer::insert_tuple_execution_plan::InsertTupleExecutionPlan;
+pub(crate) use crate::planner::optimizer::merge_execution_plan::MergeExecutionPlan;
 pub(crate) use crate::planner::optimizer::query_execution_plan::QueryExecutionPlan;
-pub(crate) use crate::planner::plan::create_plan::CreateTablePlan;
+pub(crate) use crate::planner::optimizer::truncate_execution_plan::TruncateExecutionPlan;
+pub(crate) use crate::planner::optimizer::update_execution_plan::UpdateExecutionPlan;
+pub(crate) use crate::planner::plan::create_plan::{CreateTablePlan, CreateTypePlan};
+pub(crate) use crate::planner::plan::delete_plan::DeletePlan;
 pub(crate) use crate::planner::plan::insert_plan::InsertTuplePlan;
+pub(crate) use crate::planner::plan::merge_plan::MergePlan;
 pub(crate) use crate::planner::plan::query_plan::QueryPlan;
 use crate::planner::plan::query_plan::{QueryPlanNode, QueryResultSchema};
+pub(crate) use crate::planner::plan::truncate_plan::TruncatePlan;
+pub(crate) use crate::planner::plan::update_plan::UpdatePlan;
 pub(crate) use crate::planner::plan::Plan;
 use crate::storage::storage_manager::Schema;
 
 pub(crate) enum ExecutionPlan {
     CreateTable(create_table_execution_plan::CreateTableExecutionPlan),
+    CreateType(create_type_execution_plan::CreateTypeExecutionPlan),
     InsertTuple(insert_tuple_execution_plan::InsertTupleExecutionPlan),
+    Update(update_execution_plan::UpdateExecutionPlan),
+    Delete(delete_execution_plan::DeleteExecutionPlan),
+    Truncate(truncate_execution_plan::TruncateExecutionPlan),
+    Merge(merge_execution_plan::MergeExecutionPlan),
     Query(query_execution_plan::QueryExecutionPlan),
 }
 
@@ -30,11 +49,37 @@ impl ExecutionPlan {
             Self::Query(QueryExecutionPlan {
                 plan: QueryPlanNode::Project(node),
             }) => Some(node.schema.clone()),
+            Self::Query(QueryExecutionPlan {
+                plan: QueryPlanNode::Sort(node),
+            }) => Some(node.schema.clone()),
+            Self::Query(QueryExecutionPlan {
+                plan: QueryPlanNode::Distinct(node),
+            }) => Some(node.schema.clone()),
+            Self::Query(QueryExecutionPlan {
+                plan: QueryPlanNode::Aggregate(node),
+            }) => Some(node.schema.clone()),
+            Self::Query(QueryExecutionPlan {
+                plan: QueryPlanNode::Limit(node),
+            }) => Some(node.schema.clone()),
             Self::Query(QueryExecutionPlan {
                 plan: QueryPlanNode::Join(node),
             }) => Some(node.schema.clone()),
+            Self::Query(QueryExecutionPlan {
+                plan: QueryPlanNode::SetOperation(node),
+            }) => Some(node.schema.clone()),
+            Self::Query(QueryExecutionPlan {
+                plan: QueryPlanNode::Subquery(node),
+            }) => Some(node.schema.clone()),
+            Self::Query(QueryExecutionPlan {
+                plan: QueryPlanNode::OuterRecord(node),
+            }) => Some(node.schema.clone()),
             Self::CreateTable(plan) => None,
-            Self::InsertTuple(plan) => None,
+            Self::CreateType(_) => None,
+            Self::InsertTuple(plan) => plan.returning.as_ref().map(|r| r.schema.clone()),
+            Self::Update(plan) => plan.returning.as_ref().map(|r| r.schema.clone()),
+            Self::Delete(plan) => plan.returning.as_ref().map(|r| r.schema.clone()),
+            Self::Truncate(_) => None,
+            Self::Merge(_) => None,
         }
     }
 }
@@ -53,9 +98,68 @@ impl Optimizer {
                 primary_key,
                 schema_attributes,
             }),
-            Plan::InsertTuple(InsertTuplePlan { table_name, tuple }) => {
-                ExecutionPlan::InsertTuple(InsertTupleExecutionPlan { table_name, tuple })
+            Plan::CreateType(CreateTypePlan { enum_type }) => {
+                ExecutionPlan::CreateType(CreateTypeExecutionPlan { enum_type })
             }
+            Plan::InsertTuple(InsertTuplePlan {
+                table_name,
+                schema,
+                primary_key,
+                tuples,
+                on_conflict,
+                returning,
+            }) => ExecutionPlan::InsertTuple(InsertTupleExecutionPlan {
+                table_name,
+                schema,
+                primary_key,
+                tuples,
+                on_conflict,
+                returning,
+            }),
+            Plan::Update(UpdatePlan {
+                table_name,
+                schema,
+                primary_key,
+                assignments,
+                predicate,
+                returning,
+            }) => ExecutionPlan::Update(UpdateExecutionPlan {
+                table_name,
+                schema,
+                primary_key,
+                assignments,
+                predicate,
+                returning,
+            }),
+            Plan::Delete(DeletePlan {
+                table_name,
+                schema,
+                predicate,
+                returning,
+            }) => ExecutionPlan::Delete(DeleteExecutionPlan {
+                table_name,
+                schema,
+                predicate,
+                returning,
+            }),
+            Plan::Truncate(TruncatePlan { table_names }) => {
+                ExecutionPlan::Truncate(TruncateExecutionPlan { table_names })
+            }
+            Plan::Merge(MergePlan {
+                table_name,
+                target_schema,
+                primary_key,
+                source,
+                predicate,
+                when_clauses,
+            }) => ExecutionPlan::Merge(MergeExecutionPlan {
+                table_name,
+                target_schema,
+                primary_key,
+                source,
+                predicate,
+                when_clauses,
+            }),
             Plan::Query(QueryPlan { plan, .. }) => {
                 ExecutionPlan::Query(QueryExecutionPlan { plan })
             }
@@ -1,5 +1,5 @@
 use crate::storage::storage_manager::{AttributeName, CreateTableRequest, TableName};
-use crate::storage::types::AttributeType;
+use crate::storage::types::{AttributeType, EnumType};
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct CreateTablePlan {
@@ -7,3 +7,8 @@ pub struct CreateTablePlan {
     pub primary_key: AttributeName,
     pub schema_attributes: Vec<(AttributeName, AttributeType)>,
 }
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CreateTypePlan {
+    pub enum_type: EnumType,
+}
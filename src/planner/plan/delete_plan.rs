@@ -0,0 +1,11 @@
+use crate::parser::ast::Expr;
+use crate::planner::plan::query_plan::{QueryResultSchema, ReturningClause};
+use crate::storage::storage_manager::TableName;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct DeletePlan {
+    pub table_name: TableName,
+    pub schema: QueryResultSchema,
+    pub predicate: Option<Expr>,
+    pub returning: Option<ReturningClause>,
+}
@@ -1,8 +1,23 @@
-use crate::storage::storage_manager::TableName;
+use crate::parser::ast::Expr;
+use crate::planner::plan::query_plan::{QueryResultSchema, ReturningClause};
+use crate::storage::storage_manager::{AttributeName, TableName};
 use crate::storage::tuple::TupleRecord;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct InsertTuplePlan {
     pub table_name: TableName,
-    pub tuple: TupleRecord,
+    pub schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub tuples: Vec<TupleRecord>,
+    pub on_conflict: OnConflict,
+    pub returning: Option<ReturningClause>,
+}
+
+// How a row whose primary key is already taken is handled.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum OnConflict {
+    Fail,
+    DoNothing,
+    // The assignments see the existing row's attributes and the proposed row as `excluded.*`.
+    DoUpdate(Vec<(AttributeName, Expr)>),
 }
@@ -0,0 +1,30 @@
+use crate::parser::ast::Expr;
+use crate::planner::plan::query_plan::{QueryPlan, QueryResultSchema};
+use crate::storage::storage_manager::{AttributeName, TableName};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct MergePlan {
+    pub table_name: TableName,
+    // Attributes of the target table, qualified by the table name or its alias.
+    pub target_schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub source: QueryPlan,
+    pub predicate: Expr,
+    pub when_clauses: Vec<MergeWhenClause>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct MergeWhenClause {
+    pub matched: bool,
+    pub condition: Option<Expr>,
+    pub action: MergeAction,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum MergeAction {
+    Update(Vec<(AttributeName, Expr)>),
+    Delete,
+    // One value per attribute of the target, in schema order.
+    Insert(Vec<Expr>),
+    DoNothing,
+}
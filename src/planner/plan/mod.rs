@@ -1,15 +1,28 @@
 pub(crate) mod create_plan;
+pub(crate) mod delete_plan;
 pub(crate) mod insert_plan;
+pub(crate) mod merge_plan;
 pub(crate) mod query_plan;
+pub(crate) mod truncate_plan;
+pub(crate) mod update_plan;
 
-use crate::planner::plan::create_plan::CreateTablePlan;
+use crate::planner::plan::create_plan::{CreateTablePlan, CreateTypePlan};
+use crate::planner::plan::delete_plan::DeletePlan;
 use crate::planner::plan::insert_plan::InsertTuplePlan;
+use crate::planner::plan::merge_plan::MergePlan;
 use crate::planner::plan::query_plan::QueryPlan;
+use crate::planner::plan::truncate_plan::TruncatePlan;
+use crate::planner::plan::update_plan::UpdatePlan;
 use crate::storage::storage_manager::StorageManager;
 
 #[derive(Debug, Eq, PartialEq)]
 pub(crate) enum Plan {
     CreateTable(CreateTablePlan),
+    CreateType(CreateTypePlan),
     InsertTuple(InsertTuplePlan),
+    Update(UpdatePlan),
+    Delete(DeletePlan),
+    Truncate(TruncatePlan),
+    Merge(MergePlan),
     Query(QueryPlan),
 }
@@ -1,7 +1,5 @@
-use crate::parser::ast::{Expr, JoinType};
-use crate::storage::storage_manager::{
-    AttributeName, Attributes, Schema, StorageManager, TableName,
-};
+use crate::parser::ast::{AggregateCall, Expr, JoinType, SetOperator, SubqueryKind};
+use crate::storage::storage_manager::{AttributeName, Attributes, Schema, TableName};
 use crate::storage::types::AttributeType;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -28,35 +26,90 @@ impl QueryResultSchema {
         }
     }
 
-    pub fn aliased(self, alias: Option<&String>) -> Self {
-        match alias {
-            Some(alias) => self.with_alias(alias),
-            None => self.clone(),
-        }
+    pub fn attr_names(&self) -> impl Iterator<Item = AttributeName> + '_ {
+        self.attributes
+            .attributes_iter()
+            .map(|(attr_name, _)| attr_name.clone())
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub(crate) struct ScanNode {
     pub schema: QueryResultSchema,
     pub table_name: TableName,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+// The rows returned by a statement that modifies a table, computed from the affected records.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct ReturningClause {
+    pub schema: QueryResultSchema,
+    pub exprs: Vec<Expr>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub(crate) struct FilterNode {
     pub predicate: Expr,
     pub schema: QueryResultSchema,
     pub child: Box<QueryPlan>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub(crate) struct ProjectNode {
     pub schema: QueryResultSchema,
     pub record_schema: QueryResultSchema,
+    pub exprs: Vec<Expr>,
+    pub child: Box<QueryPlan>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct SortNode {
+    pub schema: QueryResultSchema,
+    // The names the sort keys refer to the attributes of the sorted records by.
+    pub record_schema: QueryResultSchema,
+    pub sort_keys: Vec<SortKey>,
+    pub child: Box<QueryPlan>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct SortKey {
+    pub expr: Expr,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+// Drops records equal to a previous one, or with `on` set, records for which the expressions
+// evaluate to the same values as for a previous one.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct DistinctNode {
+    pub schema: QueryResultSchema,
+    // The names the `on` expressions refer to the attributes of the records by.
+    pub record_schema: QueryResultSchema,
+    pub on: Option<Vec<Expr>>,
+    pub child: Box<QueryPlan>,
+}
+
+// Computes the aggregates over each group of records for which the `group_by` expressions
+// evaluate to the same values. Its records hold the group values followed by the aggregates.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct AggregateNode {
+    pub schema: QueryResultSchema,
+    // The names the expressions refer to the attributes of the grouped records by.
+    pub record_schema: QueryResultSchema,
+    pub group_by: Vec<Expr>,
+    pub aggregates: Vec<AggregateCall>,
     pub child: Box<QueryPlan>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+// Skips `offset` records, then returns at most `limit` records.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct LimitNode {
+    pub schema: QueryResultSchema,
+    pub limit: Option<usize>,
+    pub offset: usize,
+    pub child: Box<QueryPlan>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub(crate) struct JoinNode {
     pub join_type: JoinType,
     pub predicate: Expr,
@@ -65,16 +118,70 @@ pub(crate) struct JoinNode {
     pub right: Box<QueryPlan>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+// Combines the records of both children, which have the same types of attributes. Without `all`
+// duplicate records are dropped.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct SetOperationNode {
+    pub op: SetOperator,
+    pub all: bool,
+    pub schema: QueryResultSchema,
+    pub left: Box<QueryPlan>,
+    pub right: Box<QueryPlan>,
+}
+
+// Appends the value of a subquery to each record of the child. The subquery is evaluated for
+// each record, which its `OuterRecord` node returns, unless it is not correlated i.e. it does not
+// refer to the attributes of the record, then it is evaluated once.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct SubqueryNode {
+    pub schema: QueryResultSchema,
+    pub kind: SubqueryKind,
+    pub correlated: bool,
+    pub query: Box<QueryPlan>,
+    pub child: Box<QueryPlan>,
+}
+
+// The record of the query a subquery is evaluated for.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct OuterRecordNode {
+    pub schema: QueryResultSchema,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub(crate) struct QueryPlan {
     pub result_schema: QueryResultSchema,
     pub plan: QueryPlanNode,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub(crate) enum QueryPlanNode {
     Scan(ScanNode),
     Filter(FilterNode),
     Project(ProjectNode),
+    Sort(SortNode),
+    Distinct(DistinctNode),
+    Aggregate(AggregateNode),
+    Limit(LimitNode),
     Join(JoinNode),
+    SetOperation(SetOperationNode),
+    Subquery(SubqueryNode),
+    OuterRecord(OuterRecordNode),
+}
+
+impl QueryPlanNode {
+    // The plans the records of the node are computed from.
+    pub fn children(&self) -> Vec<&QueryPlan> {
+        match self {
+            Self::Scan(_) | Self::OuterRecord(_) => vec![],
+            Self::Filter(node) => vec![&node.child],
+            Self::Project(node) => vec![&node.child],
+            Self::Sort(node) => vec![&node.child],
+            Self::Distinct(node) => vec![&node.child],
+            Self::Aggregate(node) => vec![&node.child],
+            Self::Limit(node) => vec![&node.child],
+            Self::Join(node) => vec![&node.left, &node.right],
+            Self::SetOperation(node) => vec![&node.left, &node.right],
+            Self::Subquery(node) => vec![&node.child, &node.query],
+        }
+    }
 }
@@ -0,0 +1,6 @@
+use crate::storage::storage_manager::TableName;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct TruncatePlan {
+    pub table_names: Vec<TableName>,
+}
@@ -0,0 +1,13 @@
+use crate::parser::ast::Expr;
+use crate::planner::plan::query_plan::{QueryResultSchema, ReturningClause};
+use crate::storage::storage_manager::{AttributeName, TableName};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct UpdatePlan {
+    pub table_name: TableName,
+    pub schema: QueryResultSchema,
+    pub primary_key: AttributeName,
+    pub assignments: Vec<(AttributeName, Expr)>,
+    pub predicate: Option<Expr>,
+    pub returning: Option<ReturningClause>,
+}
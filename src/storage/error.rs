@@ -7,6 +7,8 @@ pub enum StorageError {
     NoSuchTuple(TupleId),
     AlreadyExists(String),
     TupleSerdeError(String),
+    InvalidValue(String),
+    CardinalityViolation(String),
 }
 
 impl Error for StorageError {
@@ -15,6 +17,10 @@ impl Error for StorageError {
             Self::NoSuchTuple(_) => "The requested tuple does not exist",
             Self::AlreadyExists(_) => "The resource already exists",
             Self::TupleSerdeError(_) => "Error (de)serializing a tuple",
+            Self::InvalidValue(_) => "A value can not be stored in its attribute",
+            Self::CardinalityViolation(_) => {
+                "A row would be modified more than once, or a subquery has too many rows"
+            }
         }
     }
 }
@@ -25,6 +31,8 @@ impl std::fmt::Display for StorageError {
             Self::NoSuchTuple(tid) => write!(f, "no such tuple {:?}", tid),
             Self::AlreadyExists(resource) => write!(f, "resource [{:?}] already exists", resource),
             Self::TupleSerdeError(msg) => write!(f, "{}", msg),
+            Self::InvalidValue(reason) => write!(f, "{}", reason),
+            Self::CardinalityViolation(reason) => write!(f, "{}", reason),
         }
     }
 }
@@ -0,0 +1,595 @@
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct JsonError {
+    pub details: String,
+}
+
+impl JsonError {
+    fn new(details: String) -> Self {
+        JsonError { details }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl Error for JsonError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+type Result<T> = std::result::Result<T, JsonError>;
+
+// A JSON number, ordered and compared by its total order so that it can live inside
+// a StorageTupleValue.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonNumber(pub f64);
+
+impl PartialEq for JsonNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for JsonNumber {}
+
+impl PartialOrd for JsonNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JsonNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+// A parsed JSON document. Object keys are kept sorted so that equal documents have
+// the same binary form regardless of how they were written.
+#[derive(Debug, Eq, PartialEq, Clone, Ord, PartialOrd)]
+pub enum JsonValue {
+    Null,
+    Boolean(bool),
+    Number(JsonNumber),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>),
+}
+
+// Tags of the binary form.
+const NULL: u8 = 0x0;
+const FALSE: u8 = 0x1;
+const TRUE: u8 = 0x2;
+const NUMBER: u8 = 0x3;
+const STRING: u8 = 0x4;
+const ARRAY: u8 = 0x5;
+const OBJECT: u8 = 0x6;
+
+impl JsonValue {
+    pub fn parse(text: &str) -> Result<JsonValue> {
+        let mut parser = JsonParser {
+            input: text,
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != text.len() {
+            return Err(JsonError::new(format!(
+                "unexpected trailing characters at position {}",
+                parser.pos
+            )));
+        }
+        Ok(value)
+    }
+
+    // The member with the given key if this is an object.
+    pub fn get_key(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            Self::Object(members) => members.get(key),
+            _ => None,
+        }
+    }
+
+    // The element at the given index if this is an array.
+    pub fn get_index(&self, index: i32) -> Option<&JsonValue> {
+        match self {
+            Self::Array(elements) if index >= 0 => elements.get(index as usize),
+            _ => None,
+        }
+    }
+
+    // Looks up a path such as `$.a.b[0]` where `$` denotes this document.
+    pub fn get_path(&self, path: &str) -> Result<Option<&JsonValue>> {
+        let invalid_path = || JsonError::new(format!("invalid JSON path {:?}", path));
+
+        let mut rest = path.strip_prefix('$').ok_or_else(invalid_path)?;
+        let mut current = Some(self);
+        while !rest.is_empty() {
+            if let Some(suffix) = rest.strip_prefix('.') {
+                let end = suffix.find(['.', '[']).unwrap_or(suffix.len());
+                if end == 0 {
+                    return Err(invalid_path());
+                }
+                current = current.and_then(|value| value.get_key(&suffix[..end]));
+                rest = &suffix[end..];
+            } else if let Some(suffix) = rest.strip_prefix('[') {
+                let end = suffix.find(']').ok_or_else(invalid_path)?;
+                let index = suffix[..end].parse().map_err(|_| invalid_path())?;
+                current = current.and_then(|value| value.get_index(index));
+                rest = &suffix[end + 1..];
+            } else {
+                return Err(invalid_path());
+            }
+        }
+        Ok(current)
+    }
+
+    // The text form of a value as returned by `->>`: strings are unquoted while every
+    // other value is rendered as JSON.
+    pub fn to_unquoted_string(&self) -> String {
+        match self {
+            Self::String(text) => text.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        1 + match self {
+            Self::Null | Self::Boolean(_) => 0,
+            Self::Number(_) => 8,
+            Self::String(text) => 4 + text.len(),
+            Self::Array(elements) => 4 + elements.iter().map(Self::encoded_len).sum::<usize>(),
+            Self::Object(members) => {
+                4 + members
+                    .iter()
+                    .map(|(key, value)| 4 + key.len() + value.encoded_len())
+                    .sum::<usize>()
+            }
+        }
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Self::Null => buf.push(NULL),
+            Self::Boolean(false) => buf.push(FALSE),
+            Self::Boolean(true) => buf.push(TRUE),
+            Self::Number(number) => {
+                buf.push(NUMBER);
+                buf.write_f64::<BigEndian>(number.0).unwrap();
+            }
+            Self::String(text) => {
+                buf.push(STRING);
+                Self::encode_text(text, buf);
+            }
+            Self::Array(elements) => {
+                buf.push(ARRAY);
+                buf.write_u32::<BigEndian>(elements.len() as u32).unwrap();
+                for element in elements {
+                    element.encode(buf);
+                }
+            }
+            Self::Object(members) => {
+                buf.push(OBJECT);
+                buf.write_u32::<BigEndian>(members.len() as u32).unwrap();
+                for (key, value) in members {
+                    Self::encode_text(key, buf);
+                    value.encode(buf);
+                }
+            }
+        }
+    }
+
+    fn encode_text(text: &str, buf: &mut Vec<u8>) {
+        buf.write_u32::<BigEndian>(text.len() as u32).unwrap();
+        buf.extend_from_slice(text.as_bytes());
+    }
+
+    // Decodes a value from its binary form, returning the value and the number of bytes read.
+    pub fn decode(bytes: &[u8]) -> Result<(usize, JsonValue)> {
+        let invalid = || JsonError::new("invalid binary JSON".to_owned());
+
+        let tag = *bytes.first().ok_or_else(invalid)?;
+        let mut rest = &bytes[1..];
+        let value = match tag {
+            NULL => Self::Null,
+            FALSE => Self::Boolean(false),
+            TRUE => Self::Boolean(true),
+            NUMBER => Self::Number(JsonNumber(
+                rest.read_f64::<BigEndian>().map_err(|_| invalid())?,
+            )),
+            STRING => Self::String(Self::decode_text(&mut rest)?),
+            ARRAY => {
+                let len = rest.read_u32::<BigEndian>().map_err(|_| invalid())?;
+                let mut elements = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    let (read_bytes, element) = Self::decode(rest)?;
+                    rest = &rest[read_bytes..];
+                    elements.push(element);
+                }
+                Self::Array(elements)
+            }
+            OBJECT => {
+                let len = rest.read_u32::<BigEndian>().map_err(|_| invalid())?;
+                let mut members = BTreeMap::new();
+                for _ in 0..len {
+                    let key = Self::decode_text(&mut rest)?;
+                    let (read_bytes, value) = Self::decode(rest)?;
+                    rest = &rest[read_bytes..];
+                    members.insert(key, value);
+                }
+                Self::Object(members)
+            }
+            _ => return Err(invalid()),
+        };
+
+        Ok((bytes.len() - rest.len(), value))
+    }
+
+    fn decode_text(bytes: &mut &[u8]) -> Result<String> {
+        let invalid = || JsonError::new("invalid binary JSON string".to_owned());
+
+        let len = bytes.read_u32::<BigEndian>().map_err(|_| invalid())? as usize;
+        if bytes.len() < len {
+            return Err(invalid());
+        }
+        let text = String::from_utf8(bytes[..len].to_vec()).map_err(|_| invalid())?;
+        *bytes = &bytes[len..];
+        Ok(text)
+    }
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_string(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+            write!(f, "\"")?;
+            for ch in text.chars() {
+                match ch {
+                    '"' => write!(f, "\\\"")?,
+                    '\\' => write!(f, "\\\\")?,
+                    '\n' => write!(f, "\\n")?,
+                    '\r' => write!(f, "\\r")?,
+                    '\t' => write!(f, "\\t")?,
+                    ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+                    ch => write!(f, "{}", ch)?,
+                }
+            }
+            write!(f, "\"")
+        }
+
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Boolean(value) => write!(f, "{}", value),
+            Self::Number(number) => write!(f, "{}", number.0),
+            Self::String(text) => write_string(f, text),
+            Self::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Arrays and objects are parsed recursively, deeper nesting is rejected before it can overflow
+// the stack.
+const MAX_DEPTH: usize = 512;
+
+struct JsonParser<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn value(&mut self) -> Result<JsonValue> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.keyword("null", JsonValue::Null),
+            Some('t') => self.keyword("true", JsonValue::Boolean(true)),
+            Some('f') => self.keyword("false", JsonValue::Boolean(false)),
+            Some('"') => Ok(JsonValue::String(self.string()?)),
+            Some('[') => self.nested(Self::array),
+            Some('{') => self.nested(Self::object),
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => self.number(),
+            Some(ch) => Err(self.error(&format!("unexpected character {:?}", ch))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: JsonValue) -> Result<JsonValue> {
+        if self.input[self.pos..].starts_with(keyword) {
+            self.pos += keyword.len();
+            Ok(value)
+        } else {
+            Err(self.error(&format!("expected {:?}", keyword)))
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<JsonValue>) -> Result<JsonValue> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!("nesting deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    // A number is an optional minus sign, an integer part without leading zeros, and optional
+    // fraction and exponent parts that each have at least one digit.
+    fn number(&mut self) -> Result<JsonValue> {
+        let start = self.pos;
+        self.skip('-');
+        match self.peek() {
+            Some('0') => self.pos += 1,
+            Some('1'..='9') => self.digits(),
+            _ => return Err(self.error("invalid number")),
+        }
+        if self.skip('.') {
+            self.required_digits()?;
+        }
+        if self.skip('e') || self.skip('E') {
+            let _ = self.skip('+') || self.skip('-');
+            self.required_digits()?;
+        }
+        match self.input[start..self.pos].parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(JsonValue::Number(JsonNumber(number))),
+            _ => Err(self.error("invalid number")),
+        }
+    }
+
+    fn digits(&mut self) {
+        while let Some('0'..='9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn required_digits(&mut self) -> Result<()> {
+        match self.peek() {
+            Some('0'..='9') => {
+                self.digits();
+                Ok(())
+            }
+            _ => Err(self.error("invalid number")),
+        }
+    }
+
+    // Consumes the given character if it is next.
+    fn skip(&mut self, want: char) -> bool {
+        let found = self.peek() == Some(want);
+        if found {
+            self.pos += want.len_utf8();
+        }
+        found
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.next() {
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    Some('/') => text.push('/'),
+                    Some('b') => text.push('\u{8}'),
+                    Some('f') => text.push('\u{c}'),
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some('u') => text.push(self.unicode_escape()?),
+                    _ => return Err(self.error("invalid escape sequence")),
+                },
+                Some(ch) if (ch as u32) < 0x20 => {
+                    return Err(self.error("control character in string"))
+                }
+                Some(ch) => text.push(ch),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self) -> Result<char> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            // A surrogate pair e.g \ud83d\ude00
+            if !self.input[self.pos..].starts_with("\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("invalid surrogate pair"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        std::char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let digits = self.input.get(self.pos..self.pos + 4);
+        match digits.and_then(|digits| u32::from_str_radix(digits, 16).ok()) {
+            Some(code) => {
+                self.pos += 4;
+                Ok(code)
+            }
+            None => Err(self.error("invalid unicode escape")),
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue> {
+        self.expect('[')?;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(elements));
+        }
+        loop {
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(JsonValue::Array(elements)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue> {
+        self.expect('{')?;
+        let mut members = BTreeMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            // As with most databases, the last of any duplicate keys wins.
+            members.insert(key, self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(members)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn expect(&mut self, want: char) -> Result<()> {
+        match self.next() {
+            Some(got) if got == want => Ok(()),
+            _ => Err(self.error(&format!("expected {:?}", want))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.peek() {
+            if !ch.is_whitespace() {
+                break;
+            }
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn error(&self, reason: &str) -> JsonError {
+        JsonError::new(format!("invalid JSON at position {}: {}", self.pos, reason))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() -> Result<()> {
+        let value = JsonValue::parse(
+            r#" {"b": [1, 2.5, -3e2, true, null], "a": {"c": "x\"y\u00e9\ud83d\ude00"}} "#,
+        )?;
+        assert_eq!(
+            value.to_string(),
+            r#"{"a":{"c":"x\"yé😀"},"b":[1,2.5,-300,true,null]}"#
+        );
+
+        assert!(JsonValue::parse("").is_err());
+        assert!(JsonValue::parse("{\"a\": }").is_err());
+        assert!(JsonValue::parse("[1, 2").is_err());
+        assert!(JsonValue::parse("1 2").is_err());
+        assert!(JsonValue::parse("\"\\ud83d\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn numbers() -> Result<()> {
+        assert_eq!(JsonValue::parse("0")?.to_string(), "0");
+        assert_eq!(JsonValue::parse("-0.5e1")?.to_string(), "-5");
+        assert_eq!(JsonValue::parse("12E+2")?.to_string(), "1200");
+
+        for invalid in &[
+            "1.", ".5", "01", "-", "-01", "1e", "1e+", "+1", "1e999", "-1e999",
+        ] {
+            assert!(JsonValue::parse(invalid).is_err(), "{:?}", invalid);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nesting_depth() -> Result<()> {
+        let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(JsonValue::parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(JsonValue::parse(&nested(MAX_DEPTH + 1)).is_err());
+        assert!(JsonValue::parse(&"{\"a\":".repeat(100_000)).is_err());
+        assert!(JsonValue::parse(&"[".repeat(100_000)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn encode_and_decode() -> Result<()> {
+        let value = JsonValue::parse(r#"{"a": [1, "two", {"three": false}], "b": null}"#)?;
+        let mut buf = Vec::new();
+        value.encode(&mut buf);
+        assert_eq!(buf.len(), value.encoded_len());
+        assert_eq!(JsonValue::decode(&buf)?, (buf.len(), value));
+        Ok(())
+    }
+
+    #[test]
+    fn paths() -> Result<()> {
+        let value = JsonValue::parse(r#"{"a": {"b": [10, {"c": "d"}]}}"#)?;
+        assert_eq!(
+            value.get_path("$.a.b[1].c")?,
+            Some(&JsonValue::String("d".to_owned()))
+        );
+        assert_eq!(value.get_path("$")?, Some(&value));
+        assert_eq!(value.get_path("$.a.x")?, None);
+        assert_eq!(value.get_path("$.a.b[5]")?, None);
+        assert!(value.get_path("a.b").is_err());
+        assert!(value.get_path("$.a[x]").is_err());
+        assert!(value.get_path("$..a").is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 pub(crate) mod error;
+pub(crate) mod json;
 pub(crate) mod storage_manager;
 pub(crate) mod table_storage;
 pub(crate) mod tuple;
 pub(crate) mod tuple_serde;
 pub(crate) mod types;
+pub(crate) mod uuid;
@@ -1,7 +1,7 @@
 use crate::storage::error::{Result, StorageError};
 use crate::storage::table_storage::Storage;
 use crate::storage::tuple::{StoreId, TupleRecord};
-use crate::storage::types::AttributeType;
+use crate::storage::types::{AttributeType, EnumType};
 use std::cell::{RefCell, RefMut};
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
@@ -96,6 +96,7 @@ pub struct StorageManager {
     next_store_id: StoreId,
     table_storage_directory: HashMap<StoreId, RefCell<Storage>>,
     schemas: HashMap<TableName, Schema>,
+    types: HashMap<String, EnumType>,
 }
 
 impl StorageManager {
@@ -104,6 +105,7 @@ impl StorageManager {
             next_store_id: StoreId(0),
             table_storage_directory: HashMap::new(),
             schemas: HashMap::new(),
+            types: HashMap::new(),
         }
     }
 
@@ -139,6 +141,22 @@ impl StorageManager {
         Ok(())
     }
 
+    pub fn create_type(&mut self, enum_type: EnumType) -> Result<()> {
+        if self.types.contains_key(&enum_type.name) {
+            return Err(StorageError::AlreadyExists(format!(
+                "type {:?}",
+                enum_type.name
+            )));
+        }
+        self.types.insert(enum_type.name.clone(), enum_type);
+
+        Ok(())
+    }
+
+    pub fn get_type(&self, type_name: &str) -> Option<EnumType> {
+        self.types.get(type_name).cloned()
+    }
+
     pub fn get_table_store(&self, table_name: &TableName) -> Option<RefMut<Storage>> {
         self.schemas
             .get(table_name)
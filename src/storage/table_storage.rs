@@ -1,10 +1,16 @@
+use crate::storage::error::{Result, StorageError};
 use crate::storage::tuple::{StoreId, TupleId, TupleIndex, TupleRecord};
-use std::collections::HashMap;
+use crate::storage::tuple_serde::StorageTupleValue;
+use std::collections::{BTreeMap, HashMap};
 
 pub struct Storage {
     next_index: TupleIndex,
     store_id: StoreId,
-    tuple_store: HashMap<TupleId, TupleRecord>,
+    // Tuples along with their primary key.
+    tuple_store: HashMap<TupleId, (StorageTupleValue, TupleRecord)>,
+    primary_key_index: BTreeMap<StorageTupleValue, TupleId>,
+    // Slots of deleted tuples, handed out again before new slots are allocated.
+    free_slots: Vec<TupleIndex>,
 }
 
 impl Storage {
@@ -13,25 +19,161 @@ impl Storage {
             next_index: 0,
             store_id,
             tuple_store: HashMap::new(),
+            primary_key_index: BTreeMap::new(),
+            free_slots: Vec::new(),
         }
     }
 
-    pub fn insert_tuple(&mut self, tuple: TupleRecord) -> TupleId {
+    // Keys are checked to be unique by the callers, the tuple is indexed by the given key.
+    pub fn insert_tuple(&mut self, key: StorageTupleValue, tuple: TupleRecord) -> TupleId {
+        let slot_index = match self.free_slots.pop() {
+            Some(slot_index) => slot_index,
+            None => {
+                self.next_index += 1;
+                self.next_index - 1
+            }
+        };
         let id = TupleId {
             store_id: self.store_id.clone(),
-            slot_index: self.next_index,
+            slot_index,
         };
-        self.next_index += 1;
 
-        self.tuple_store.insert(id.clone(), tuple);
+        self.primary_key_index.insert(key.clone(), id.clone());
+        self.tuple_store.insert(id.clone(), (key, tuple));
         id
     }
 
     pub fn get_tuple(&self, id: &TupleId) -> Option<TupleRecord> {
-        self.tuple_store.get(id).map(|tuple| tuple.clone())
+        self.tuple_store.get(id).map(|(_, tuple)| tuple.clone())
+    }
+
+    pub fn get_tuple_id(&self, key: &StorageTupleValue) -> Option<TupleId> {
+        self.primary_key_index.get(key).cloned()
+    }
+
+    // A statement may move keys between its rows, the entry of the old key is only removed
+    // while no other row has taken it.
+    pub fn update_tuple(
+        &mut self,
+        id: &TupleId,
+        key: StorageTupleValue,
+        tuple: TupleRecord,
+    ) -> Result<()> {
+        match self.tuple_store.get_mut(id) {
+            Some((old_key, record)) => {
+                if self.primary_key_index.get(old_key) == Some(id) {
+                    self.primary_key_index.remove(old_key);
+                }
+                self.primary_key_index.insert(key.clone(), id.clone());
+                *old_key = key;
+                *record = tuple;
+                Ok(())
+            }
+            None => Err(StorageError::NoSuchTuple(id.clone())),
+        }
+    }
+
+    pub fn delete_tuple(&mut self, id: &TupleId) -> Result<TupleRecord> {
+        let (key, record) = self
+            .tuple_store
+            .remove(id)
+            .ok_or_else(|| StorageError::NoSuchTuple(id.clone()))?;
+        if self.primary_key_index.get(&key) == Some(id) {
+            self.primary_key_index.remove(&key);
+        }
+        self.free_slots.push(id.slot_index);
+
+        // Give memory back once most of the table has been deleted.
+        if self.tuple_store.len() < self.tuple_store.capacity() / 4 {
+            self.tuple_store.shrink_to_fit();
+        }
+
+        Ok(record)
+    }
+
+    // Removes every tuple and starts allocating slots from the beginning again.
+    pub fn truncate(&mut self) {
+        self.next_index = 0;
+        self.tuple_store = HashMap::new();
+        self.primary_key_index = BTreeMap::new();
+        self.free_slots = Vec::new();
     }
 
     pub fn scan(&self) -> impl Iterator<Item = (&TupleId, &TupleRecord)> {
-        self.tuple_store.iter()
+        self.tuple_store.iter().map(|(id, (_, tuple))| (id, tuple))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::storage::error::StorageError;
+    use crate::storage::table_storage::Storage;
+    use crate::storage::tuple::{StoreId, TupleRecord};
+    use crate::storage::tuple_serde::StorageTupleValue::Integer;
+
+    #[test]
+    fn delete_reuses_slots() {
+        let mut storage = Storage::new(StoreId(0));
+        let first = storage.insert_tuple(Integer(1), TupleRecord(vec![1]));
+        let second = storage.insert_tuple(Integer(2), TupleRecord(vec![2]));
+
+        assert_eq!(storage.delete_tuple(&first), Ok(TupleRecord(vec![1])));
+        assert_eq!(
+            storage.delete_tuple(&first),
+            Err(StorageError::NoSuchTuple(first.clone()))
+        );
+        assert_eq!(storage.get_tuple(&first), None);
+
+        let third = storage.insert_tuple(Integer(3), TupleRecord(vec![3]));
+        assert_eq!(third, first);
+        assert_eq!(storage.get_tuple(&third), Some(TupleRecord(vec![3])));
+        assert_eq!(storage.get_tuple(&second), Some(TupleRecord(vec![2])));
+
+        let fourth = storage.insert_tuple(Integer(4), TupleRecord(vec![4]));
+        assert_eq!(fourth.slot_index, 2);
+    }
+
+    #[test]
+    fn primary_key_index() {
+        let mut storage = Storage::new(StoreId(0));
+        let first = storage.insert_tuple(Integer(1), TupleRecord(vec![1]));
+        let second = storage.insert_tuple(Integer(2), TupleRecord(vec![2]));
+        assert_eq!(storage.get_tuple_id(&Integer(1)), Some(first.clone()));
+        assert_eq!(storage.get_tuple_id(&Integer(3)), None);
+
+        // Shifting the keys of both tuples keeps the key taken by the first one.
+        storage
+            .update_tuple(&first, Integer(2), TupleRecord(vec![2]))
+            .unwrap();
+        storage
+            .update_tuple(&second, Integer(3), TupleRecord(vec![3]))
+            .unwrap();
+        assert_eq!(storage.get_tuple_id(&Integer(1)), None);
+        assert_eq!(storage.get_tuple_id(&Integer(2)), Some(first.clone()));
+        assert_eq!(storage.get_tuple_id(&Integer(3)), Some(second.clone()));
+
+        storage.delete_tuple(&second).unwrap();
+        assert_eq!(storage.get_tuple_id(&Integer(3)), None);
+        assert_eq!(storage.get_tuple_id(&Integer(2)), Some(first));
+
+        storage.truncate();
+        assert_eq!(storage.get_tuple_id(&Integer(2)), None);
+    }
+
+    #[test]
+    fn truncate() {
+        let mut storage = Storage::new(StoreId(0));
+        let first = storage.insert_tuple(Integer(1), TupleRecord(vec![1]));
+        let second = storage.insert_tuple(Integer(2), TupleRecord(vec![2]));
+        storage.delete_tuple(&second).unwrap();
+
+        storage.truncate();
+        assert_eq!(storage.scan().count(), 0);
+        assert_eq!(storage.get_tuple(&first), None);
+
+        let third = storage.insert_tuple(Integer(3), TupleRecord(vec![3]));
+        assert_eq!(third.slot_index, 0);
+        let fourth = storage.insert_tuple(Integer(4), TupleRecord(vec![4]));
+        assert_eq!(fourth.slot_index, 1);
     }
 }
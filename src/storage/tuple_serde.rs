@@ -1,6 +1,8 @@
-use crate::storage::storage_manager::AttributeName;
+use crate::storage::json::JsonValue;
+use crate::storage::storage_manager::{AttributeName, Attributes};
 use crate::storage::tuple::TupleRecord;
-use crate::storage::types::AttributeType;
+use crate::storage::types::{AttributeType, EnumLabel, EnumType};
+use crate::storage::uuid::{Uuid, UUID_SIZE};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use std::collections::HashMap;
 use std::error::Error;
@@ -8,12 +10,14 @@ use std::error::Error;
 #[derive(Debug, Eq, PartialEq)]
 pub enum SerdeError {
     EOF(String),
+    InvalidData(String),
 }
 
 impl Error for SerdeError {
     fn description(&self) -> &str {
         match self {
             Self::EOF(_) => "Reached the end of file during deserialization",
+            Self::InvalidData(_) => "Found malformed data during deserialization",
         }
     }
 }
@@ -22,6 +26,7 @@ impl std::fmt::Display for SerdeError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::EOF(name) => write!(f, "unable to deserialize {:?}", name),
+            Self::InvalidData(reason) => write!(f, "{}", reason),
         }
     }
 }
@@ -31,6 +36,12 @@ pub enum StorageTupleValue {
     Integer(i32),
     Boolean(bool),
     String(String),
+    Binary(Vec<u8>),
+    Json(JsonValue),
+    Uuid(Uuid),
+    Enum(EnumLabel),
+    // Only stored in attributes of a nullable type.
+    Null,
 }
 
 pub fn serialize_tuple(values: Vec<StorageTupleValue>) -> TupleRecord {
@@ -39,6 +50,13 @@ pub fn serialize_tuple(values: Vec<StorageTupleValue>) -> TupleRecord {
             StorageTupleValue::Integer(_) => 4,
             StorageTupleValue::Boolean(_) => 1,
             StorageTupleValue::String(s) => 4 + s.bytes().len(),
+            StorageTupleValue::Binary(b) => 4 + b.len(),
+            StorageTupleValue::Json(json) => 4 + json.encoded_len(),
+            StorageTupleValue::Uuid(_) => UUID_SIZE,
+            StorageTupleValue::Enum(_) => 2,
+            StorageTupleValue::Null => unreachable!(
+                "[validation] NULL is only stored in nullable attributes, see serialize_record"
+            ),
         }
     });
     let mut tuple = Vec::with_capacity(tuple_size);
@@ -70,22 +88,85 @@ pub fn serialize_tuple(values: Vec<StorageTupleValue>) -> TupleRecord {
                     i += 1;
                 }
             }
+            StorageTupleValue::Binary(value) => {
+                (&mut tuple[i..i + 4])
+                    .write_u32::<BigEndian>(value.len() as u32)
+                    .unwrap();
+                i += 4;
+                for byte in value {
+                    tuple[i] = byte;
+                    i += 1;
+                }
+            }
+            StorageTupleValue::Json(value) => {
+                let mut encoded = Vec::with_capacity(value.encoded_len());
+                value.encode(&mut encoded);
+                (&mut tuple[i..i + 4])
+                    .write_u32::<BigEndian>(encoded.len() as u32)
+                    .unwrap();
+                i += 4;
+                for byte in encoded {
+                    tuple[i] = byte;
+                    i += 1;
+                }
+            }
+            StorageTupleValue::Uuid(value) => {
+                tuple[i..i + UUID_SIZE].copy_from_slice(&value.0);
+                i += UUID_SIZE;
+            }
+            StorageTupleValue::Enum(value) => {
+                (&mut tuple[i..i + 2])
+                    .write_u16::<BigEndian>(value.ordinal)
+                    .unwrap();
+                i += 2;
+            }
+            StorageTupleValue::Null => unreachable!("NULL was rejected when sizing the tuple"),
         }
     }
 
     TupleRecord(tuple)
 }
 
+// Serializes the values of a record with the given attributes. A value of a nullable attribute
+// is preceded by a byte telling whether it is NULL.
+pub fn serialize_record(values: Vec<StorageTupleValue>, attributes: &Attributes) -> TupleRecord {
+    let mut tuple = Vec::new();
+    for (value, (_, attr_type)) in values.into_iter().zip(attributes.attributes_iter()) {
+        match (attr_type, value) {
+            (AttributeType::Nullable(_), StorageTupleValue::Null) => tuple.push(0x0),
+            (AttributeType::Nullable(_), value) => {
+                tuple.push(0x1);
+                tuple.extend(serialize_tuple(vec![value]).0);
+            }
+            (_, value) => tuple.extend(serialize_tuple(vec![value]).0),
+        }
+    }
+    TupleRecord(tuple)
+}
+
+// Encodes values that may be NULL such that the same values at the same positions, and only
+// those, are encoded the same.
+pub fn serialize_key(values: Vec<StorageTupleValue>) -> Vec<u8> {
+    let mut key = Vec::new();
+    for value in values {
+        match value {
+            StorageTupleValue::Null => key.push(0x0),
+            value => {
+                key.push(0x1);
+                key.extend(serialize_tuple(vec![value]).0);
+            }
+        }
+    }
+    key
+}
+
 pub fn deserialize_tuple(tuple: TupleRecord, schema: Vec<AttributeType>) -> Vec<StorageTupleValue> {
     let tuple = tuple.0;
     let mut values = Vec::new();
     let mut i = 0;
     for attr_type in schema {
-        let (read_bytes, value) = match attr_type {
-            AttributeType::Integer => TupleRecord::read_integer(&tuple[i..]).expect("Invalid data"),
-            AttributeType::Boolean => TupleRecord::read_boolean(&tuple[i..]).expect("Invalid data"),
-            AttributeType::Text => TupleRecord::read_text(&tuple[i..]).expect("Invalid data"),
-        };
+        let (read_bytes, value) =
+            TupleRecord::read_value(&tuple[i..], &attr_type).expect("Invalid data");
         i += read_bytes;
         values.push(value);
     }
@@ -103,11 +184,7 @@ impl TupleRecord {
 
         let mut index = 0;
         for (attr_name, attr_type) in schema {
-            let (read_bytes, value) = match attr_type {
-                AttributeType::Integer => Self::read_integer(&self.0[index..])?,
-                AttributeType::Text => Self::read_text(&self.0[index..])?,
-                AttributeType::Boolean => Self::read_boolean(&self.0[index..])?,
-            };
+            let (read_bytes, value) = Self::read_value(&self.0[index..], attr_type)?;
             values.extend(vec![(attr_name.clone(), value)]);
             index += read_bytes;
         }
@@ -117,6 +194,28 @@ impl TupleRecord {
         Ok(values)
     }
 
+    fn read_value(
+        tuple: &[u8],
+        attr_type: &AttributeType,
+    ) -> Result<(usize, StorageTupleValue), SerdeError> {
+        match attr_type {
+            AttributeType::Integer => Self::read_integer(tuple),
+            AttributeType::Text | AttributeType::Varchar(_) => Self::read_text(tuple),
+            AttributeType::Char(_) => Self::read_char(tuple),
+            AttributeType::Boolean => Self::read_boolean(tuple),
+            AttributeType::Binary => Self::read_binary(tuple),
+            AttributeType::Json => Self::read_json(tuple),
+            AttributeType::Uuid => Self::read_uuid(tuple),
+            AttributeType::Enum(enum_type) => Self::read_enum(tuple, enum_type),
+            AttributeType::Nullable(attr_type) => match tuple.first() {
+                Some(0x0) => Ok((1, StorageTupleValue::Null)),
+                Some(_) => Self::read_value(&tuple[1..], attr_type)
+                    .map(|(read_bytes, value)| (read_bytes + 1, value)),
+                None => Err(SerdeError::EOF("null flag".to_owned())),
+            },
+        }
+    }
+
     fn read_integer(tuple: &[u8]) -> Result<(usize, StorageTupleValue), SerdeError> {
         let value = (&tuple[..4])
             .read_i32::<BigEndian>()
@@ -145,12 +244,75 @@ impl TupleRecord {
 
         Ok((end, StorageTupleValue::String(value)))
     }
+
+    // Trailing padding of a CHAR is insignificant so it is dropped when read back.
+    fn read_char(tuple: &[u8]) -> Result<(usize, StorageTupleValue), SerdeError> {
+        match Self::read_text(tuple)? {
+            (read_bytes, StorageTupleValue::String(value)) => Ok((
+                read_bytes,
+                StorageTupleValue::String(value.trim_end_matches(' ').to_owned()),
+            )),
+            _ => unreachable!("read_text always returns a string"),
+        }
+    }
+
+    fn read_binary(tuple: &[u8]) -> Result<(usize, StorageTupleValue), SerdeError> {
+        let size = (&tuple[..4])
+            .read_u32::<BigEndian>()
+            .expect("Invalid tuple - tried to read binary size");
+        let end = 4 + size as usize;
+        if end > tuple.len() {
+            return Err(SerdeError::EOF("binary".to_owned()));
+        }
+
+        Ok((end, StorageTupleValue::Binary(tuple[4..end].to_vec())))
+    }
+
+    fn read_json(tuple: &[u8]) -> Result<(usize, StorageTupleValue), SerdeError> {
+        match Self::read_binary(tuple)? {
+            (read_bytes, StorageTupleValue::Binary(encoded)) => {
+                let (_, value) = JsonValue::decode(&encoded)
+                    .map_err(|err| SerdeError::InvalidData(err.to_string()))?;
+                Ok((read_bytes, StorageTupleValue::Json(value)))
+            }
+            _ => unreachable!("read_binary always returns binary"),
+        }
+    }
+
+    fn read_uuid(tuple: &[u8]) -> Result<(usize, StorageTupleValue), SerdeError> {
+        if tuple.len() < UUID_SIZE {
+            return Err(SerdeError::EOF("uuid".to_owned()));
+        }
+        let mut bytes = [0; UUID_SIZE];
+        bytes.copy_from_slice(&tuple[..UUID_SIZE]);
+
+        Ok((UUID_SIZE, StorageTupleValue::Uuid(Uuid(bytes))))
+    }
+
+    fn read_enum(
+        tuple: &[u8],
+        enum_type: &EnumType,
+    ) -> Result<(usize, StorageTupleValue), SerdeError> {
+        let ordinal = (&tuple[..])
+            .read_u16::<BigEndian>()
+            .map_err(|_| SerdeError::EOF("enum".to_owned()))?;
+        let label = enum_type.label(ordinal).ok_or_else(|| {
+            SerdeError::InvalidData(format!(
+                "{} is not a valid ordinal for enum {:?}",
+                ordinal, enum_type.name
+            ))
+        })?;
+
+        Ok((2, StorageTupleValue::Enum(label)))
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::storage::json::JsonValue;
     use crate::storage::tuple_serde::{deserialize_tuple, serialize_tuple, StorageTupleValue};
     use crate::storage::types::AttributeType;
+    use crate::storage::uuid::Uuid;
 
     #[test]
     fn serde_tuple() {
@@ -160,6 +322,11 @@ mod test {
             StorageTupleValue::Integer(-4),
             StorageTupleValue::Boolean(true),
             StorageTupleValue::String("hello".to_owned()),
+            StorageTupleValue::Binary(vec![0xde, 0xad, 0xbe, 0xef]),
+            StorageTupleValue::Binary(vec![]),
+            StorageTupleValue::String("abc".to_owned()),
+            StorageTupleValue::Json(JsonValue::parse(r#"{"a": [1, "b"]}"#).unwrap()),
+            StorageTupleValue::Uuid(Uuid([7; 16])),
         ];
         let schema = vec![
             AttributeType::Integer,
@@ -167,6 +334,11 @@ mod test {
             AttributeType::Integer,
             AttributeType::Boolean,
             AttributeType::Text,
+            AttributeType::Binary,
+            AttributeType::Binary,
+            AttributeType::Varchar(3),
+            AttributeType::Json,
+            AttributeType::Uuid,
         ];
 
         assert_eq!(
@@ -174,4 +346,13 @@ mod test {
             deserialize_tuple(serialize_tuple(values), schema)
         )
     }
+
+    #[test]
+    fn serde_char_drops_padding() {
+        let values = vec![StorageTupleValue::String("ab   ".to_owned())];
+        assert_eq!(
+            vec![StorageTupleValue::String("ab".to_owned())],
+            deserialize_tuple(serialize_tuple(values), vec![AttributeType::Char(5)])
+        )
+    }
 }
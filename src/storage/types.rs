@@ -1,6 +1,167 @@
+use crate::storage::json::JsonValue;
+use crate::storage::tuple_serde::StorageTupleValue;
+use crate::storage::uuid::Uuid;
+use std::fmt;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum AttributeType {
     Integer,
     Boolean,
     Text,
+    // Text of at most n characters.
+    Varchar(u32),
+    // Text of exactly n characters, padded with trailing spaces.
+    Char(u32),
+    Binary,
+    Json,
+    Uuid,
+    Enum(EnumType),
+    // A type that also has NULL as a value, the attributes of the unmatched side of an outer
+    // join have one. Attributes of tables are never nullable.
+    Nullable(Box<AttributeType>),
+}
+
+// An enum created with CREATE TYPE, values are stored as the ordinal of their label.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EnumType {
+    pub name: String,
+    pub labels: Vec<String>,
+}
+
+impl EnumType {
+    pub fn label(&self, ordinal: u16) -> Option<EnumLabel> {
+        self.labels.get(ordinal as usize).map(|label| EnumLabel {
+            ordinal,
+            label: label.clone(),
+        })
+    }
+
+    pub fn find_label(&self, label: &str) -> Option<EnumLabel> {
+        self.labels
+            .iter()
+            .position(|candidate| candidate == label)
+            .map(|ordinal| EnumLabel {
+                ordinal: ordinal as u16,
+                label: label.to_owned(),
+            })
+    }
+}
+
+// A value of an enum, ordered by the position of its label in the declaration.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct EnumLabel {
+    pub ordinal: u16,
+    pub label: String,
+}
+
+impl AttributeType {
+    // The type with any length limit dropped, values of types with the same base type
+    // can be compared against each other.
+    pub fn base_type(&self) -> AttributeType {
+        match self {
+            Self::Varchar(_) | Self::Char(_) => Self::Text,
+            Self::Nullable(attr_type) => attr_type.base_type(),
+            other => other.clone(),
+        }
+    }
+
+    pub fn nullable(self) -> AttributeType {
+        match self {
+            Self::Nullable(_) => self,
+            other => Self::Nullable(Box::new(other)),
+        }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, Self::Nullable(_))
+    }
+
+    // The type of the values of this type that are not NULL.
+    pub fn without_null(&self) -> AttributeType {
+        match self {
+            Self::Nullable(attr_type) => (**attr_type).clone(),
+            other => other.clone(),
+        }
+    }
+
+    // Whether values of the given type can be stored in an attribute of this type. Types
+    // that are written as string literals also accept text.
+    pub fn accepts(&self, value_type: &AttributeType) -> bool {
+        match (self, value_type) {
+            (_, Self::Nullable(_)) => false,
+            (Self::Json, Self::Text) | (Self::Uuid, Self::Text) | (Self::Enum(_), Self::Text) => {
+                true
+            }
+            (expected, value_type) => expected.base_type() == value_type.base_type(),
+        }
+    }
+
+    // Converts an accepted value into its stored representation.
+    pub fn coerce(&self, value: StorageTupleValue) -> Result<StorageTupleValue, CoercionError> {
+        match (self, value) {
+            (Self::Json, StorageTupleValue::String(text)) => JsonValue::parse(&text)
+                .map(StorageTupleValue::Json)
+                .map_err(|err| CoercionError::InvalidValue(err.to_string())),
+            (Self::Uuid, StorageTupleValue::String(text)) => Uuid::parse(&text)
+                .map(StorageTupleValue::Uuid)
+                .map_err(|err| CoercionError::InvalidValue(err.to_string())),
+            (Self::Enum(enum_type), StorageTupleValue::String(label)) => enum_type
+                .find_label(&label)
+                .map(StorageTupleValue::Enum)
+                .ok_or_else(|| {
+                    CoercionError::InvalidValue(format!(
+                        "invalid label {:?} for enum {:?}",
+                        label, enum_type.name
+                    ))
+                }),
+            (Self::Varchar(limit), StorageTupleValue::String(text))
+            | (Self::Char(limit), StorageTupleValue::String(text)) => {
+                self.apply_length_limit(*limit as usize, text)
+            }
+            (_, value) => Ok(value),
+        }
+    }
+
+    // Enforces the length limit of VARCHAR(n) and CHAR(n) attributes and pads CHAR(n) values
+    // to their full length. As in standard SQL, excess trailing spaces are silently truncated.
+    fn apply_length_limit(
+        &self,
+        limit: usize,
+        mut text: String,
+    ) -> Result<StorageTupleValue, CoercionError> {
+        let length = text.chars().count();
+        if length > limit {
+            if text.chars().skip(limit).any(|ch| ch != ' ') {
+                return Err(CoercionError::TooLong(format!(
+                    "value of length {} is too long for type {:?}",
+                    length, self
+                )));
+            }
+            text = text.chars().take(limit).collect();
+        }
+
+        match self {
+            Self::Char(_) => Ok(StorageTupleValue::String(format!(
+                "{:<width$}",
+                text,
+                width = limit
+            ))),
+            _ => Ok(StorageTupleValue::String(text)),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum CoercionError {
+    InvalidValue(String),
+    // The value exceeds the length limit of a VARCHAR(n) or CHAR(n).
+    TooLong(String),
+}
+
+impl fmt::Display for CoercionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidValue(reason) | Self::TooLong(reason) => write!(f, "{}", reason),
+        }
+    }
 }
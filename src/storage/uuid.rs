@@ -0,0 +1,132 @@
+use std::collections::hash_map::RandomState;
+use std::error::Error;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct UuidError {
+    pub details: String,
+}
+
+impl fmt::Display for UuidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl Error for UuidError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+pub const UUID_SIZE: usize = 16;
+
+// A UUID, ordered by its bytes which matches the order of its canonical text form.
+#[derive(Eq, PartialEq, Clone, Copy, Ord, PartialOrd, Hash)]
+pub struct Uuid(pub [u8; UUID_SIZE]);
+
+impl Uuid {
+    // Accepts the canonical hyphenated form as well as 32 bare hex digits, in either case.
+    pub fn parse(input: &str) -> Result<Self, UuidError> {
+        let invalid = || UuidError {
+            details: format!("invalid UUID {:?}", input),
+        };
+
+        let digits = match input.len() {
+            36 => {
+                let hyphens_in_place = input
+                    .char_indices()
+                    .all(|(i, ch)| (ch == '-') == matches!(i, 8 | 13 | 18 | 23));
+                if !hyphens_in_place {
+                    return Err(invalid());
+                }
+                input.replace('-', "")
+            }
+            32 => input.to_owned(),
+            _ => return Err(invalid()),
+        };
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let mut bytes = [0; UUID_SIZE];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = digits
+                .get(2 * i..2 * i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(invalid)?;
+        }
+        Ok(Uuid(bytes))
+    }
+
+    // Generates a random (version 4) UUID. The randomness comes from the per-process keys
+    // of the standard hasher, mixed with the clock and a counter so that ids never repeat.
+    pub fn new_random() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or(0);
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        let mut bytes = [0; UUID_SIZE];
+        for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(nanos);
+            hasher.write_u64(count);
+            hasher.write_usize(i);
+            chunk.copy_from_slice(&hasher.finish().to_be_bytes());
+        }
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Uuid(bytes)
+    }
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if let 4 | 6 | 8 | 10 = i {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Uuid({})", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::storage::uuid::Uuid;
+
+    #[test]
+    fn parse_and_display() {
+        let uuid = Uuid::parse("A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A11").unwrap();
+        assert_eq!(uuid.to_string(), "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11");
+        assert_eq!(Uuid::parse("a0eebc999c0b4ef8bb6d6bb9bd380a11"), Ok(uuid));
+
+        assert!(Uuid::parse("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a1").is_err());
+        assert!(Uuid::parse("a0eebc99-9c0b4-ef8-bb6d-6bb9bd380a11").is_err());
+        assert!(Uuid::parse("g0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").is_err());
+        assert!(Uuid::parse("+0eebc999c0b4ef8bb6d6bb9bd380a11").is_err());
+    }
+
+    #[test]
+    fn random() {
+        let first = Uuid::new_random();
+        let second = Uuid::new_random();
+        assert_ne!(first, second);
+        assert_eq!(first.0[6] >> 4, 4);
+        assert_eq!(first.0[8] >> 6, 0b10);
+    }
+}
@@ -8,8 +8,11 @@ pub enum TranslateError {
     MultiplePrimaryKeys(Vec<String>),
     NoSuchTable(String),
     NoSuchAttribute(String),
+    AmbiguousAttributeName(String),
+    NoSuchType(String),
     InvalidArguments(String),
     TypeError(String),
+    ValueTooLong(String),
     StorageError(Box<dyn Error>),
 }
 
@@ -24,8 +27,11 @@ impl Error for TranslateError {
             Self::PrimaryKeyRequired => "No primary key was provided",
             Self::NoSuchTable(_) => "The table does not exist",
             Self::NoSuchAttribute(_) => "The attribute does not exist",
+            Self::AmbiguousAttributeName(_) => "The attribute name refers to several attributes",
+            Self::NoSuchType(_) => "The type does not exist",
             Self::InvalidArguments(_) => "Invalid arguments were provided to an operation",
             Self::TypeError(_) => "Invalid types were provided to an operation",
+            Self::ValueTooLong(_) => "A value exceeds the length limit of its attribute",
         }
     }
 }
@@ -38,8 +44,11 @@ impl std::fmt::Display for TranslateError {
             Self::PrimaryKeyRequired => write!(f, "Primary key required"),
             Self::NoSuchTable(name) => write!(f, "No such table [{:?}]", name),
             Self::NoSuchAttribute(name) => write!(f, "No such attribute [{:?}]", name),
+            Self::AmbiguousAttributeName(name) => write!(f, "Ambiguous attribute [{:?}]", name),
+            Self::NoSuchType(name) => write!(f, "No such type [{:?}]", name),
             Self::InvalidArguments(reason) => write!(f, "{:?}", reason),
             Self::TypeError(reason) => write!(f, "{:?}", reason),
+            Self::ValueTooLong(reason) => write!(f, "{:?}", reason),
         }
     }
 }
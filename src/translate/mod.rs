@@ -1,30 +1,48 @@
 mod error;
+mod scope;
 mod type_check;
 
+use crate::execution::expr_evaluation::evaluate_expr_with_ctx;
+use crate::parser::ast;
 use crate::parser::ast::{
-    AttributeDefinition, AttributeType as ParserAttributeType, AttributeType, AttributeValue,
-    BinaryExpr, BinaryOperation, CreateTableStmt, Expr, FromClause, InsertStmt, JoinStmt,
-    LiteralExpr, SelectProperties, SelectStmt, SingleSelectStmt, Stmt, WhereClause,
+    AggregateCall, Assignment, AttributeDefinition, AttributeType as ParserAttributeType,
+    AttributeType, AttributeValue, BinaryExpr, BinaryOperation, ConflictAction, CreateTableStmt,
+    CreateTypeStmt, DeleteStmt, Distinct, Expr, FromClause, FunctionCall, InsertStmt, JoinClause,
+    JoinConstraint, JoinType, LimitClause, LiteralExpr, MergeStmt, NullsOrder, OrderByExpr,
+    SelectItem, SelectProperties, SelectStmt, SetOperationStmt, SingleSelectStmt, SortOrder, Stmt,
+    SubqueryExpr, SubqueryKind, TableReference, TruncateStmt, UpdateStmt, WhereClause,
 };
-use crate::planner::plan::create_plan::CreateTablePlan;
-use crate::planner::plan::insert_plan::InsertTuplePlan;
+use crate::planner::plan::create_plan::{CreateTablePlan, CreateTypePlan};
+use crate::planner::plan::delete_plan::DeletePlan;
+use crate::planner::plan::insert_plan::{InsertTuplePlan, OnConflict};
+use crate::planner::plan::merge_plan::{MergeAction, MergePlan, MergeWhenClause};
 use crate::planner::plan::query_plan::{
-    FilterNode, JoinNode, ProjectNode, QueryPlan, QueryPlanNode, QueryResultSchema, ScanNode,
+    AggregateNode, DistinctNode, FilterNode, JoinNode, LimitNode, OuterRecordNode, ProjectNode,
+    QueryPlan, QueryPlanNode, QueryResultSchema, ReturningClause, ScanNode, SetOperationNode,
+    SortKey, SortNode, SubqueryNode,
 };
+use crate::planner::plan::truncate_plan::TruncatePlan;
+use crate::planner::plan::update_plan::UpdatePlan;
 use crate::planner::plan::Plan;
 use crate::storage::error::StorageError;
 use crate::storage::storage_manager::{
     AttributeName, Attributes, CreateTableRequest, Schema, StorageManager, TableName,
 };
+use crate::storage::tuple::TupleRecord;
 use crate::storage::tuple_serde::{serialize_tuple, StorageTupleValue};
-use crate::storage::types::AttributeType as StorageAttributeType;
+use crate::storage::types::{AttributeType as StorageAttributeType, CoercionError, EnumType};
 use crate::translate::error::TranslateError;
+use crate::translate::scope::Scope;
 use crate::translate::type_check::{
-    type_check_expr, type_check_join_predicate, type_check_projection,
+    coerce_comparisons, type_check_aggregate, type_check_expr, type_check_join_predicate,
+    type_check_projection, type_check_set_operation, type_check_subquery, type_check_where_clause,
 };
 use error::Result;
+use std::cell::Cell;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::rc::Rc;
 
 pub(crate) struct Translator<'storage> {
     pub storage_manager: &'storage StorageManager,
@@ -34,7 +52,12 @@ impl<'storage> Translator<'storage> {
     pub fn translate(&mut self, stmt: Stmt) -> Result<Plan> {
         match stmt {
             Stmt::CreateTable(stmt) => self.translate_create_table(stmt),
+            Stmt::CreateType(stmt) => self.translate_create_type(stmt),
             Stmt::Insert(stmt) => self.translate_insert(stmt),
+            Stmt::Update(stmt) => self.translate_update(stmt),
+            Stmt::Delete(stmt) => self.translate_delete(stmt),
+            Stmt::Truncate(stmt) => self.translate_truncate(stmt),
+            Stmt::Merge(stmt) => self.translate_merge(stmt),
             Stmt::Select(stmt) => self.translate_select(stmt),
         }
     }
@@ -81,15 +104,13 @@ impl<'storage> Translator<'storage> {
             }
         }
 
-        let schema_attributes = attribute_definitions
-            .into_iter()
-            .map(|attr| {
-                (
-                    AttributeName(attr.name),
-                    Self::translate_attribute_type(attr.attribute_type),
-                )
-            })
-            .collect();
+        let mut schema_attributes = Vec::new();
+        for attr in attribute_definitions {
+            schema_attributes.push((
+                AttributeName(attr.name),
+                self.translate_attribute_type(attr.attribute_type)?,
+            ));
+        }
 
         Ok(Plan::CreateTable(CreateTablePlan {
             table_name,
@@ -98,307 +119,1649 @@ impl<'storage> Translator<'storage> {
         }))
     }
 
+    fn translate_create_type(&mut self, stmt: CreateTypeStmt) -> Result<Plan> {
+        let CreateTypeStmt { type_name, labels } = stmt;
+
+        if self.storage_manager.get_type(&type_name).is_some() {
+            return Err(TranslateError::StorageError(Box::new(
+                StorageError::AlreadyExists(format!("type {:?}", type_name)),
+            )));
+        }
+
+        // Values are stored as a 2 byte ordinal.
+        if labels.is_empty() || labels.len() > u16::MAX as usize + 1 {
+            return Err(TranslateError::InvalidArguments(format!(
+                "enum {:?} must have between 1 and {} labels",
+                type_name,
+                u16::MAX as usize + 1
+            )));
+        }
+        {
+            let mut seen = HashSet::new();
+            for label in &labels {
+                if !seen.insert(label) {
+                    return Err(TranslateError::InvalidArguments(format!(
+                        "enum {:?} has duplicate label {:?}",
+                        type_name, label
+                    )));
+                }
+            }
+        }
+
+        Ok(Plan::CreateType(CreateTypePlan {
+            enum_type: EnumType {
+                name: type_name,
+                labels,
+            },
+        }))
+    }
+
+    // Every row is validated before the plan is built, so either all rows are inserted or none.
     fn translate_insert(&mut self, stmt: InsertStmt) -> Result<Plan> {
         let InsertStmt {
             table_name,
             attribute_names,
-            attribute_values,
+            rows,
+            on_conflict,
+            returning,
         } = stmt;
 
         let table_name = TableName(table_name);
         let schema = self.get_table_schema(&table_name, None)?;
+        let on_conflict = Self::translate_on_conflict(&table_name, &schema, on_conflict)?;
 
-        if attribute_names.len() != attribute_values.len() {
-            return Err(TranslateError::InvalidArguments(format!(
-                "attribute length mismatch: {:?} attributes specified, {} values provided, expected {}",
-                attribute_names.len(),
-                attribute_values.len(),
-                schema.num_attributes(),
-            )));
-        }
-        if attribute_values.is_empty() {
+        if rows.is_empty() || rows.iter().any(|row| row.is_empty()) {
             return Err(TranslateError::InvalidArguments(
                 "No attribute values provided".to_owned(),
             ));
         }
 
-        let mut resolved_attribute_values = Vec::new();
-        for attr in attribute_values {
-            resolved_attribute_values.push(Self::resolve_attribute_value(attr)?);
-        }
-
         let attribute_names = attribute_names
             .into_iter()
             .map(|name| AttributeName(name))
             .collect::<Vec<AttributeName>>();
+        Self::check_inserted_attributes(&table_name, &schema, &attribute_names)?;
+
+        let mut tuples = Vec::with_capacity(rows.len());
+        for attribute_values in rows {
+            tuples.push(Self::translate_insert_row(
+                &table_name,
+                &schema,
+                &attribute_names,
+                attribute_values,
+            )?);
+        }
 
-        fn resolved_value_to_type(value: &StorageTupleValue) -> StorageAttributeType {
-            match value {
-                StorageTupleValue::Integer(_) => StorageAttributeType::Integer,
-                StorageTupleValue::String(_) => StorageAttributeType::Text,
-                StorageTupleValue::Boolean(_) => unimplemented!("no boolean upstream"),
+        let primary_key = schema.primary_key.clone();
+        let schema = QueryResultSchema::from(schema);
+        let returning = Self::translate_returning(&schema, returning)?;
+
+        Ok(Plan::InsertTuple(InsertTuplePlan {
+            table_name,
+            schema,
+            primary_key,
+            tuples,
+            on_conflict,
+            returning,
+        }))
+    }
+
+    // Conflicts are only detected on the primary key, as it is the only unique constraint.
+    fn translate_on_conflict(
+        table_name: &TableName,
+        schema: &Schema,
+        on_conflict: Option<ast::OnConflict>,
+    ) -> Result<OnConflict> {
+        let ast::OnConflict { target, action } = match on_conflict {
+            Some(on_conflict) => on_conflict,
+            None => return Ok(OnConflict::Fail),
+        };
+        if let Some(target) = target {
+            if target != schema.primary_key.0 {
+                return Err(TranslateError::InvalidArguments(format!(
+                    "conflict target {:?} is not the primary key of table {:?}",
+                    target, table_name.0
+                )));
             }
         }
-        for (name, value) in attribute_names.iter().zip(resolved_attribute_values.iter()) {
-            match schema.attributes.get_attribute_type(name) {
-                Some(expected_type) if expected_type != resolved_value_to_type(value) => {
-                    return Err(TranslateError::InvalidArguments(format!(
-                        "type mismatch for attribute {:?} in table {:?}: expected {:?}, got {:?}",
-                        name.0,
-                        table_name.0,
-                        expected_type,
-                        resolved_value_to_type(value)
-                    )))
+
+        match action {
+            ConflictAction::DoNothing => Ok(OnConflict::DoNothing),
+            ConflictAction::DoUpdate(assignments) => {
+                let excluded_names = schema
+                    .attributes
+                    .attributes_iter()
+                    .map(|(name, _)| format!("excluded.{}", name.0))
+                    .collect::<Vec<_>>();
+                let mut ctx = schema.attributes.as_lookup_table();
+                for (name, (_, attr_type)) in excluded_names
+                    .iter()
+                    .zip(schema.attributes.attributes_iter())
+                {
+                    ctx.insert(name, attr_type);
                 }
-                None => {
+
+                let assignments =
+                    Self::translate_assignments(table_name, &schema.attributes, assignments, &ctx)?;
+                if assignments
+                    .iter()
+                    .any(|(name, _)| name == &schema.primary_key)
+                {
                     return Err(TranslateError::InvalidArguments(format!(
-                        "no such attribute {:?} in table {:?}",
-                        name.0, table_name.0,
-                    )))
+                        "the primary key {:?} cannot be updated on conflict",
+                        schema.primary_key.0
+                    )));
                 }
-                _ => (), // types match so nothing to do.
+                Ok(OnConflict::DoUpdate(assignments))
             }
         }
+    }
 
-        Ok(Plan::InsertTuple(InsertTuplePlan {
+    // Records are laid out in schema order, so every attribute needs exactly one value.
+    fn check_inserted_attributes(
+        table_name: &TableName,
+        schema: &Schema,
+        attribute_names: &[AttributeName],
+    ) -> Result<()> {
+        let mut seen = HashSet::new();
+        for name in attribute_names {
+            if schema.attributes.get_attribute_type(name).is_none() {
+                return Err(TranslateError::InvalidArguments(format!(
+                    "no such attribute {:?} in table {:?}",
+                    name.0, table_name.0,
+                )));
+            }
+            if !seen.insert(name) {
+                return Err(TranslateError::DuplicateAttributeName(name.0.clone()));
+            }
+        }
+        if let Some((missing, _)) = schema
+            .attributes
+            .attributes_iter()
+            .find(|(name, _)| !attribute_names.contains(name))
+        {
+            return Err(TranslateError::InvalidArguments(format!(
+                "no value provided for attribute {:?} in table {:?}",
+                missing.0, table_name.0,
+            )));
+        }
+        Ok(())
+    }
+
+    fn translate_insert_row(
+        table_name: &TableName,
+        schema: &Schema,
+        attribute_names: &[AttributeName],
+        attribute_values: Vec<AttributeValue>,
+    ) -> Result<TupleRecord> {
+        if attribute_names.len() != attribute_values.len() {
+            return Err(TranslateError::InvalidArguments(format!(
+                "attribute length mismatch: {:?} attributes specified, {} values provided, expected {}",
+                attribute_names.len(),
+                attribute_values.len(),
+                schema.num_attributes(),
+            )));
+        }
+
+        let mut values_by_name = HashMap::new();
+        for (name, attr) in attribute_names.iter().zip(attribute_values) {
+            values_by_name.insert(name, Self::resolve_attribute_value(attr)?);
+        }
+
+        let mut tuple_values = Vec::new();
+        for (name, expected_type) in schema.attributes.attributes_iter() {
+            let (value, value_type) = values_by_name
+                .remove(name)
+                .expect("every attribute was checked to have a value");
+            tuple_values.push(Self::coerce_value(
+                table_name,
+                name,
+                expected_type,
+                value,
+                value_type,
+            )?);
+        }
+
+        Ok(serialize_tuple(tuple_values))
+    }
+
+    fn translate_update(&mut self, stmt: UpdateStmt) -> Result<Plan> {
+        let UpdateStmt {
+            table_name,
+            assignments,
+            where_clause,
+            returning,
+        } = stmt;
+
+        Self::check_no_subqueries(
+            "UPDATE",
+            assignments
+                .iter()
+                .map(|assignment| &assignment.value)
+                .chain(Self::where_clause_expr(&where_clause))
+                .chain(Self::returning_exprs(&returning)),
+        )?;
+
+        let table_name = TableName(table_name);
+        let schema = self.get_table_schema(&table_name, None)?;
+        let primary_key = schema.primary_key.clone();
+        let schema = QueryResultSchema::from(schema);
+        let ctx = schema.attributes.as_lookup_table();
+
+        let assignments =
+            Self::translate_assignments(&table_name, &schema.attributes, assignments, &ctx)?;
+        let predicate = type_check_where_clause(where_clause, &ctx)?;
+        let returning = Self::translate_returning(&schema, returning)?;
+
+        Ok(Plan::Update(UpdatePlan {
+            table_name,
+            schema,
+            primary_key,
+            assignments,
+            predicate,
+            returning,
+        }))
+    }
+
+    // Resolves `SET attribute = expr` pairs against the attributes of the table.
+    fn translate_assignments(
+        table_name: &TableName,
+        attributes: &Attributes,
+        assignments: Vec<Assignment>,
+        ctx: &HashMap<&String, &StorageAttributeType>,
+    ) -> Result<Vec<(AttributeName, Expr)>> {
+        let mut resolved_assignments: Vec<(AttributeName, Expr)> = Vec::new();
+        for Assignment {
+            attribute_name,
+            value,
+        } in assignments
+        {
+            let attribute_name = AttributeName(attribute_name);
+            let attribute_type = match attributes.get_attribute_type(&attribute_name) {
+                Some(attribute_type) => attribute_type,
+                None => return Err(TranslateError::NoSuchAttribute(attribute_name.0)),
+            };
+            if resolved_assignments
+                .iter()
+                .any(|(name, _)| name == &attribute_name)
+            {
+                return Err(TranslateError::InvalidArguments(format!(
+                    "attribute {:?} is assigned more than once",
+                    attribute_name.0
+                )));
+            }
+
+            let value_type = type_check_expr(&value, ctx)?;
+            if !attribute_type.accepts(&value_type) {
+                return Err(TranslateError::TypeError(format!(
+                    "cannot assign a value of type {:?} to attribute {:?} of type {:?}",
+                    value_type, attribute_name.0, attribute_type
+                )));
+            }
+            // Constants are checked upfront, other values are checked as rows are updated.
+            if let Expr::Literal(LiteralExpr::String(text)) = &value {
+                Self::coerce_value(
+                    table_name,
+                    &attribute_name,
+                    &attribute_type,
+                    StorageTupleValue::String(text.clone()),
+                    value_type,
+                )?;
+            }
+
+            resolved_assignments.push((attribute_name, coerce_comparisons(value, ctx)));
+        }
+
+        Ok(resolved_assignments)
+    }
+
+    fn translate_delete(&mut self, stmt: DeleteStmt) -> Result<Plan> {
+        let DeleteStmt {
+            table_name,
+            where_clause,
+            returning,
+        } = stmt;
+
+        Self::check_no_subqueries(
+            "DELETE",
+            Self::where_clause_expr(&where_clause).chain(Self::returning_exprs(&returning)),
+        )?;
+
+        let table_name = TableName(table_name);
+        let schema = QueryResultSchema::from(self.get_table_schema(&table_name, None)?);
+        let predicate =
+            type_check_where_clause(where_clause, &schema.attributes.as_lookup_table())?;
+        let returning = Self::translate_returning(&schema, returning)?;
+
+        Ok(Plan::Delete(DeletePlan {
+            table_name,
+            schema,
+            predicate,
+            returning,
+        }))
+    }
+
+    // Tables have no sequences yet, so there is nothing for `RESTART IDENTITY` to reset.
+    fn translate_truncate(&mut self, stmt: TruncateStmt) -> Result<Plan> {
+        let TruncateStmt {
+            table_names,
+            restart_identity: _,
+        } = stmt;
+
+        let mut truncated = Vec::with_capacity(table_names.len());
+        for table_name in table_names {
+            let table_name = TableName(table_name);
+            self.get_table_schema(&table_name, None)?;
+            if !truncated.contains(&table_name) {
+                truncated.push(table_name);
+            }
+        }
+
+        Ok(Plan::Truncate(TruncatePlan {
+            table_names: truncated,
+        }))
+    }
+
+    // Attributes of the target and the source are referred to by qualified names such as
+    // `t.balance` and `s.balance`, while assigned and inserted attributes are target names.
+    fn translate_merge(&mut self, stmt: MergeStmt) -> Result<Plan> {
+        let MergeStmt {
+            target_table,
+            target_alias,
+            source,
+            source_alias,
+            predicate,
+            when_clauses,
+        } = stmt;
+
+        let table_name = TableName(target_table);
+        let schema = self.get_table_schema(&table_name, None)?;
+        let target_alias = target_alias.unwrap_or_else(|| table_name.0.clone());
+        let target_schema = QueryResultSchema::from(schema.clone().with_alias(&target_alias));
+        let primary_key = AttributeName(format!("{}.{}", target_alias, schema.primary_key.0));
+
+        let source_alias = match (&source, source_alias) {
+            (_, Some(alias)) => alias,
+            (FromClause::Table(source_table), None) => source_table.clone(),
+            (FromClause::Select(_), None) => {
+                return Err(TranslateError::InvalidArguments(
+                    "a subquery in USING must have an alias".to_owned(),
+                ))
+            }
+            (FromClause::Join(_), None) => {
+                return Err(TranslateError::InvalidArguments(
+                    "a join in USING must have an alias".to_owned(),
+                ))
+            }
+        };
+        if source_alias == target_alias {
+            return Err(TranslateError::InvalidArguments(format!(
+                "the target and source of MERGE are both named {:?}",
+                source_alias
+            )));
+        }
+        let source = self.translate_single_select(
+            SingleSelectStmt {
+                properties: SelectProperties::Star,
+                from_clause: source,
+                where_clause: WhereClause::None,
+                order_by: vec![],
+                limit: None,
+                distinct: None,
+                group_by: vec![],
+                having: None,
+                alias: Some(source_alias),
+            },
+            None,
+        )?;
+
+        let source_ctx = source.result_schema.attributes.as_lookup_table();
+        let mut joined_ctx = target_schema.attributes.as_lookup_table();
+        joined_ctx.extend(source_ctx.iter());
+        let predicate = type_check_join_predicate(predicate, &joined_ctx)?;
+
+        let mut resolved_when_clauses = Vec::with_capacity(when_clauses.len());
+        for ast::MergeWhenClause {
+            matched,
+            condition,
+            action,
+        } in when_clauses
+        {
+            let ctx = if matched { &joined_ctx } else { &source_ctx };
+            let condition = match condition {
+                Some(condition) => type_check_where_clause(WhereClause::Expr(condition), ctx)?,
+                None => None,
+            };
+            let action = match action {
+                ast::MergeAction::Update(assignments) => {
+                    let assignments = Self::translate_assignments(
+                        &table_name,
+                        &schema.attributes,
+                        assignments,
+                        ctx,
+                    )?;
+                    if assignments
+                        .iter()
+                        .any(|(name, _)| name == &schema.primary_key)
+                    {
+                        return Err(TranslateError::InvalidArguments(format!(
+                            "the primary key {:?} cannot be updated by MERGE",
+                            schema.primary_key.0
+                        )));
+                    }
+                    MergeAction::Update(
+                        assignments
+                            .into_iter()
+                            .map(|(name, expr)| {
+                                (AttributeName(format!("{}.{}", target_alias, name.0)), expr)
+                            })
+                            .collect(),
+                    )
+                }
+                ast::MergeAction::Delete => MergeAction::Delete,
+                ast::MergeAction::Insert {
+                    attribute_names,
+                    values,
+                } => MergeAction::Insert(Self::translate_merge_insert(
+                    &table_name,
+                    &schema,
+                    attribute_names,
+                    values,
+                    ctx,
+                )?),
+                ast::MergeAction::DoNothing => MergeAction::DoNothing,
+            };
+            resolved_when_clauses.push(MergeWhenClause {
+                matched,
+                condition,
+                action,
+            });
+        }
+
+        Ok(Plan::Merge(MergePlan {
             table_name,
-            tuple: serialize_tuple(resolved_attribute_values),
+            target_schema,
+            primary_key,
+            source,
+            predicate,
+            when_clauses: resolved_when_clauses,
         }))
     }
 
-    fn translate_attribute_type(attribute_type: ParserAttributeType) -> StorageAttributeType {
-        match attribute_type {
+    // Orders the inserted values by the schema of the target.
+    fn translate_merge_insert(
+        table_name: &TableName,
+        schema: &Schema,
+        attribute_names: Option<Vec<String>>,
+        values: Vec<Expr>,
+        ctx: &HashMap<&String, &StorageAttributeType>,
+    ) -> Result<Vec<Expr>> {
+        let attribute_names = match attribute_names {
+            Some(attribute_names) => attribute_names.into_iter().map(AttributeName).collect(),
+            None => schema
+                .attributes
+                .attributes_iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>(),
+        };
+        if attribute_names.len() != values.len() {
+            return Err(TranslateError::InvalidArguments(format!(
+                "attribute length mismatch: {} attributes specified, {} values provided",
+                attribute_names.len(),
+                values.len(),
+            )));
+        }
+        Self::check_inserted_attributes(table_name, schema, &attribute_names)?;
+
+        let mut values_by_name = HashMap::new();
+        for (name, value) in attribute_names.iter().zip(values) {
+            let expected_type = schema
+                .attributes
+                .get_attribute_type(name)
+                .expect("inserted attributes were checked to exist");
+            let value_type = type_check_expr(&value, ctx)?;
+            if !expected_type.accepts(&value_type) {
+                return Err(TranslateError::TypeError(format!(
+                    "cannot insert a value of type {:?} into attribute {:?} of type {:?}",
+                    value_type, name.0, expected_type
+                )));
+            }
+            values_by_name.insert(name, coerce_comparisons(value, ctx));
+        }
+
+        Ok(schema
+            .attributes
+            .attributes_iter()
+            .map(|(name, _)| {
+                values_by_name
+                    .remove(name)
+                    .expect("every attribute was checked to have a value")
+            })
+            .collect())
+    }
+
+    // Subqueries are planned as part of a query, the statements that modify a table cannot
+    // evaluate them.
+    fn check_no_subqueries<'e>(
+        statement: &str,
+        mut exprs: impl Iterator<Item = &'e Expr>,
+    ) -> Result<()> {
+        match exprs.find(|expr| expr.contains_subquery()) {
+            Some(expr) => Err(TranslateError::InvalidArguments(format!(
+                "subqueries are not supported in {} statements, got {}",
+                statement, expr
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn where_clause_expr(where_clause: &WhereClause) -> impl Iterator<Item = &Expr> {
+        match where_clause {
+            WhereClause::Expr(expr) => Some(expr),
+            WhereClause::None => None,
+        }
+        .into_iter()
+    }
+
+    fn returning_exprs(returning: &Option<SelectProperties>) -> impl Iterator<Item = &Expr> {
+        match returning {
+            Some(SelectProperties::Exprs(items)) => items.as_slice(),
+            _ => &[],
+        }
+        .iter()
+        .map(|item| &item.expr)
+    }
+
+    // `RETURNING` is a projection over the records affected by a statement.
+    fn translate_returning(
+        schema: &QueryResultSchema,
+        returning: Option<SelectProperties>,
+    ) -> Result<Option<ReturningClause>> {
+        let items = match returning {
+            None => return Ok(None),
+            Some(SelectProperties::Star) => Self::identifier_items(
+                schema
+                    .attributes
+                    .attributes_iter()
+                    .map(|(attr_name, _)| attr_name.0.clone())
+                    .collect(),
+            ),
+            Some(SelectProperties::Identifiers(attr_names)) => Self::identifier_items(attr_names),
+            Some(SelectProperties::Exprs(items)) => items,
+        };
+        let ctx = schema.attributes.as_lookup_table();
+        let schema = QueryResultSchema::new(type_check_projection(&items, &ctx)?);
+        let exprs = items
+            .into_iter()
+            .map(|item| coerce_comparisons(item.expr, &ctx))
+            .collect();
+
+        Ok(Some(ReturningClause { schema, exprs }))
+    }
+
+    // Converts a value to the representation of the attribute it is stored in.
+    fn coerce_value(
+        table_name: &TableName,
+        name: &AttributeName,
+        expected_type: &StorageAttributeType,
+        value: StorageTupleValue,
+        value_type: StorageAttributeType,
+    ) -> Result<StorageTupleValue> {
+        if !expected_type.accepts(&value_type) {
+            return Err(TranslateError::InvalidArguments(format!(
+                "type mismatch for attribute {:?} in table {:?}: expected {:?}, got {:?}",
+                name.0, table_name.0, expected_type, value_type
+            )));
+        }
+
+        expected_type.coerce(value).map_err(|err| {
+            let reason = format!(
+                "{} for attribute {:?} in table {:?}",
+                err, name.0, table_name.0
+            );
+            match err {
+                CoercionError::InvalidValue(_) => TranslateError::InvalidArguments(reason),
+                CoercionError::TooLong(_) => TranslateError::ValueTooLong(reason),
+            }
+        })
+    }
+
+    fn translate_attribute_type(
+        &self,
+        attribute_type: ParserAttributeType,
+    ) -> Result<StorageAttributeType> {
+        Ok(match attribute_type {
             ParserAttributeType::Integer => StorageAttributeType::Integer,
             ParserAttributeType::Text => StorageAttributeType::Text,
+            ParserAttributeType::Varchar(length) => StorageAttributeType::Varchar(length),
+            ParserAttributeType::Char(length) => StorageAttributeType::Char(length),
+            ParserAttributeType::Binary => StorageAttributeType::Binary,
+            ParserAttributeType::Json => StorageAttributeType::Json,
+            ParserAttributeType::Uuid => StorageAttributeType::Uuid,
+            ParserAttributeType::Named(type_name) => {
+                match self.storage_manager.get_type(&type_name) {
+                    Some(enum_type) => StorageAttributeType::Enum(enum_type),
+                    None => return Err(TranslateError::NoSuchType(type_name)),
+                }
+            }
+        })
+    }
+
+    // Evaluates an inserted value, it may not refer to any attributes.
+    fn resolve_attribute_value(
+        attribute_value: AttributeValue,
+    ) -> Result<(StorageTupleValue, StorageAttributeType)> {
+        match attribute_value {
+            AttributeValue::String(s) => {
+                Ok((StorageTupleValue::String(s), StorageAttributeType::Text))
+            }
+            AttributeValue::Expr(expr) => {
+                let ctx = HashMap::new();
+                let value_type = type_check_expr(&expr, &ctx)?;
+                Ok((
+                    evaluate_expr_with_ctx(&expr, &HashMap::new(), &HashMap::new())?,
+                    value_type,
+                ))
+            }
+        }
+    }
+
+    fn translate_select(&mut self, stmt: SelectStmt) -> Result<Plan> {
+        Ok(Plan::Query(self.translate_query(stmt, None)?))
+    }
+
+    // A subquery is translated with the query it is nested in, whose attributes it can refer to.
+    fn translate_query(
+        &mut self,
+        stmt: SelectStmt,
+        outer: Option<&OuterQuery>,
+    ) -> Result<QueryPlan> {
+        match stmt {
+            SelectStmt::Select(stmt) => self.translate_single_select(*stmt, outer),
+            SelectStmt::SetOperation(stmt) => self.translate_set_operation(stmt, outer),
+        }
+    }
+
+    fn translate_set_operation(
+        &mut self,
+        stmt: SetOperationStmt,
+        outer: Option<&OuterQuery>,
+    ) -> Result<QueryPlan> {
+        let SetOperationStmt {
+            op,
+            all,
+            left,
+            right,
+            order_by,
+            limit,
+        } = stmt;
+
+        let left = self.translate_query(*left, outer)?;
+        let right = self.translate_query(*right, outer)?;
+        let schema = QueryResultSchema::new(type_check_set_operation(
+            &op,
+            &left.result_schema.attributes,
+            &right.result_schema.attributes,
+        )?);
+
+        let plan = QueryPlan {
+            result_schema: schema.clone(),
+            plan: QueryPlanNode::SetOperation(SetOperationNode {
+                op,
+                all,
+                schema: schema.clone(),
+                left: Box::new(left),
+                right: Box::new(right),
+            }),
+        };
+        // ORDER BY refers to the attributes of the combined records by their names.
+        let plan = Self::translate_sort(plan, schema, order_by)?;
+        Ok(Self::translate_limit(plan, limit))
+    }
+
+    fn translate_join(&mut self, join: JoinClause) -> Result<(QueryPlan, Scope)> {
+        let JoinClause {
+            join_type,
+            left,
+            right,
+            constraint,
+        } = join;
+
+        let (left_plan, left_scope) = self.translate_table_reference(left)?;
+        let (right_plan, right_scope) = self.translate_table_reference(right)?;
+
+        // USING and NATURAL match the attributes of the same name on both sides.
+        let using_names = match &constraint {
+            JoinConstraint::Using(names) => names.clone(),
+            JoinConstraint::Natural => {
+                let right_names = right_scope.names();
+                left_scope
+                    .names()
+                    .into_iter()
+                    .filter(|name| right_names.contains(name))
+                    .map(|name| name.to_owned())
+                    .collect()
+            }
+            _ => vec![],
+        };
+        let mut using = Vec::new();
+        for name in using_names {
+            if name.contains('.') || using.iter().any(|(using_name, _, _)| using_name == &name) {
+                return Err(TranslateError::InvalidArguments(format!(
+                    "invalid attribute name {:?} in USING",
+                    name
+                )));
+            }
+            let left_attr_name = left_scope.resolve_identifier(&name)?;
+            let right_attr_name = right_scope.resolve_identifier(&name)?;
+            using.push((name, left_attr_name, right_attr_name));
+        }
+
+        let scope = left_scope.join(right_scope);
+
+        let left_attributes = left_plan.result_schema.attributes.clone();
+        let right_attributes = right_plan.result_schema.attributes.clone();
+
+        // Check that the result schema will have unique column names.
+        {
+            let left_table = left_attributes.as_lookup_table();
+            let left_keys = left_table.keys().collect::<HashSet<_>>();
+            let right_table = right_attributes.as_lookup_table();
+            match right_table.keys().find(move |k| left_keys.contains(k)) {
+                Some(dup) => return Err(TranslateError::DuplicateAttributeName((*dup).to_owned())),
+                _ => (),
+            }
+        }
+
+        let joined_record_attributes = left_attributes
+            .attributes_iter()
+            .chain(right_attributes.attributes_iter())
+            .cloned()
+            .collect();
+
+        let joined_records_schema =
+            QueryResultSchema::new(Attributes::new(joined_record_attributes));
+        let ctx = joined_records_schema.attributes.as_lookup_table();
+        let identifier =
+            |attr_name: &AttributeName| Expr::Literal(LiteralExpr::Identifier(attr_name.0.clone()));
+        let predicate_expr = match constraint {
+            JoinConstraint::On(predicate) => {
+                type_check_join_predicate(WhereClause::Expr(scope.resolve(predicate)?), &ctx)?
+            }
+            JoinConstraint::None if join_type != JoinType::Cross => {
+                type_check_join_predicate(WhereClause::None, &ctx)?
+            }
+            _ => {
+                let predicate = using
+                    .iter()
+                    .map(|(_, left_attr_name, right_attr_name)| {
+                        Expr::Binary(BinaryExpr {
+                            left: Box::new(identifier(left_attr_name)),
+                            op: BinaryOperation::Equal,
+                            right: Box::new(identifier(right_attr_name)),
+                        })
+                    })
+                    .reduce(|left, right| {
+                        Expr::Binary(BinaryExpr {
+                            left: Box::new(left),
+                            op: BinaryOperation::And,
+                            right: Box::new(right),
+                        })
+                    })
+                    .unwrap_or(Expr::Literal(LiteralExpr::Boolean(true)));
+                type_check_join_predicate(WhereClause::Expr(predicate), &ctx)?
+            }
+        };
+
+        // A merged attribute is the attribute of the side whose records are all kept, of either
+        // side in a full join.
+        let merged_exprs = using
+            .iter()
+            .map(|(name, left_attr_name, right_attr_name)| SelectItem {
+                expr: match join_type {
+                    JoinType::Right => identifier(right_attr_name),
+                    JoinType::Full => Expr::Function(FunctionCall {
+                        name: "coalesce".to_owned(),
+                        args: vec![identifier(left_attr_name), identifier(right_attr_name)],
+                    }),
+                    _ => identifier(left_attr_name),
+                },
+                alias: Some(name.clone()),
+            })
+            .collect::<Vec<_>>();
+
+        // The attributes of the side an outer join extends with NULL become nullable.
+        let (left_nullable, right_nullable) = match join_type {
+            JoinType::Inner | JoinType::Cross => (false, false),
+            JoinType::Left => (false, true),
+            JoinType::Right => (true, false),
+            JoinType::Full => (true, true),
+        };
+        let nullable_attributes = |attributes: &Attributes, nullable: bool| {
+            attributes
+                .attributes_iter()
+                .map(|(attr_name, attr_type)| {
+                    if nullable {
+                        (attr_name.clone(), attr_type.clone().nullable())
+                    } else {
+                        (attr_name.clone(), attr_type.clone())
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let joined_schema = QueryResultSchema::new(Attributes::new(
+            nullable_attributes(&left_attributes, left_nullable)
+                .into_iter()
+                .chain(nullable_attributes(&right_attributes, right_nullable))
+                .collect(),
+        ));
+
+        let plan = QueryPlan {
+            result_schema: joined_schema.clone(),
+            plan: QueryPlanNode::Join(JoinNode {
+                join_type,
+                predicate: predicate_expr,
+                schema: joined_schema.clone(),
+                left: Box::new(left_plan),
+                right: Box::new(right_plan),
+            }),
+        };
+        if using.is_empty() {
+            return Ok((plan, scope));
+        }
+
+        // The merged attributes are added to the joined records. The attributes they are merged
+        // from are kept for their qualified references, except for the merged attributes of a
+        // nested join which are replaced.
+        let items = merged_exprs
+            .into_iter()
+            .chain(
+                joined_schema
+                    .attributes
+                    .attributes_iter()
+                    .filter(|(attr_name, _)| !using.iter().any(|(name, _, _)| name == &attr_name.0))
+                    .map(|(attr_name, _)| SelectItem {
+                        expr: identifier(attr_name),
+                        alias: None,
+                    }),
+            )
+            .collect::<Vec<_>>();
+        let schema = QueryResultSchema::new(type_check_projection(
+            &items,
+            &joined_schema.attributes.as_lookup_table(),
+        )?);
+        let scope = using.iter().enumerate().fold(
+            scope,
+            |scope, (position, (name, left_attr_name, right_attr_name))| {
+                scope.merge(position, name, &[left_attr_name, right_attr_name])
+            },
+        );
+
+        let plan = QueryPlan {
+            result_schema: schema.clone(),
+            plan: QueryPlanNode::Project(ProjectNode {
+                schema,
+                record_schema: joined_schema,
+                exprs: items.into_iter().map(|item| item.expr).collect(),
+                child: Box::new(plan),
+            }),
+        };
+        Ok((plan, scope))
+    }
+
+    // Within a join the attributes of a table are named by the table, unless it has an alias.
+    fn translate_table_reference(
+        &mut self,
+        reference: TableReference,
+    ) -> Result<(QueryPlan, Scope)> {
+        let TableReference { from_clause, alias } = reference;
+        let alias = match (&from_clause, alias) {
+            (FromClause::Table(table_name), None) => Some(table_name.clone()),
+            (_, alias) => alias,
+        };
+        self.translate_from_clause(from_clause, alias.as_ref())
+    }
+
+    // The plan of the records a query selects from, and the scope its expressions are resolved
+    // in. An alias renames the attributes of the records e.g `p.name`.
+    fn translate_from_clause(
+        &mut self,
+        from_clause: FromClause,
+        alias: Option<&String>,
+    ) -> Result<(QueryPlan, Scope)> {
+        let (plan, scope) = match from_clause {
+            FromClause::Table(table_name) => {
+                let table_name = TableName(table_name);
+                let schema = QueryResultSchema::from(self.get_table_schema(&table_name, None)?);
+                let scope = Scope::new(Some(&table_name.0), schema.attr_names());
+                let plan = QueryPlan {
+                    result_schema: schema.clone(),
+                    plan: QueryPlanNode::Scan(ScanNode { schema, table_name }),
+                };
+                (plan, scope)
+            }
+            FromClause::Select(nested_select) => {
+                let plan = self.translate_query(*nested_select, None)?;
+                let scope = Scope::new(None, plan.result_schema.attr_names());
+                (plan, scope)
+            }
+            FromClause::Join(join) => self.translate_join(*join)?,
+        };
+
+        Ok(match alias {
+            Some(alias) => (
+                QueryPlan {
+                    result_schema: plan.result_schema.with_alias(alias),
+                    plan: plan.plan,
+                },
+                scope.with_alias(alias),
+            ),
+            None => (plan, scope),
+        })
+    }
+
+    fn translate_single_select(
+        &mut self,
+        stmt: SingleSelectStmt,
+        outer: Option<&OuterQuery>,
+    ) -> Result<QueryPlan> {
+        let SingleSelectStmt {
+            properties,
+            from_clause,
+            where_clause,
+            order_by,
+            limit,
+            distinct,
+            group_by,
+            having,
+            alias,
+        } = stmt;
+
+        let (child_plan, scope) = self.translate_from_clause(from_clause, alias.as_ref())?;
+        let (child_plan, scope) = match outer {
+            Some(outer) => Self::translate_outer_record(child_plan, scope, outer),
+            None => (child_plan, scope),
+        };
+
+        let plan = match where_clause {
+            WhereClause::Expr(predicate) => {
+                let predicate = scope.resolve(predicate)?;
+                let record_schema = child_plan.result_schema.clone();
+                let (child_plan, predicate) =
+                    self.translate_subqueries(child_plan, &scope, predicate)?;
+                let ctx = child_plan.result_schema.attributes.as_lookup_table();
+                let _ = type_check_expr(&predicate, &ctx)?;
+                let predicate = coerce_comparisons(predicate, &ctx);
+                let plan = QueryPlan {
+                    result_schema: child_plan.result_schema.clone(),
+                    plan: QueryPlanNode::Filter(FilterNode {
+                        schema: child_plan.result_schema.clone(),
+                        predicate,
+                        child: Box::new(child_plan),
+                    }),
+                };
+                // The attributes of the subqueries are dropped once the records are filtered.
+                if plan.result_schema == record_schema {
+                    plan
+                } else {
+                    QueryPlan {
+                        result_schema: record_schema.clone(),
+                        plan: QueryPlanNode::Project(ProjectNode {
+                            schema: record_schema.clone(),
+                            record_schema: plan.result_schema.clone(),
+                            exprs: record_schema
+                                .attr_names()
+                                .map(|attr_name| {
+                                    Expr::Literal(LiteralExpr::Identifier(attr_name.0))
+                                })
+                                .collect(),
+                            child: Box::new(plan),
+                        }),
+                    }
+                }
+            }
+            WhereClause::None => child_plan,
+        };
+
+        let clauses = SelectClauses {
+            properties,
+            distinct,
+            group_by,
+            having,
+            order_by,
+            limit,
+        };
+        self.translate_select_clauses(plan, &scope, clauses)
+    }
+
+    // A subquery is evaluated for a record of the outer query, which is joined with each of the
+    // records the subquery selects from.
+    fn translate_outer_record(
+        child_plan: QueryPlan,
+        scope: Scope,
+        outer: &OuterQuery,
+    ) -> (QueryPlan, Scope) {
+        let outer_schema = outer.schema.clone().with_alias("$outer");
+        let schema = QueryResultSchema::new(Attributes::new(
+            outer_schema
+                .attributes
+                .attributes_iter()
+                .chain(child_plan.result_schema.attributes.attributes_iter())
+                .cloned()
+                .collect(),
+        ));
+        let plan = QueryPlan {
+            result_schema: schema.clone(),
+            plan: QueryPlanNode::Join(JoinNode {
+                join_type: JoinType::Cross,
+                predicate: Expr::Literal(LiteralExpr::Boolean(true)),
+                schema,
+                left: Box::new(QueryPlan {
+                    result_schema: outer_schema.clone(),
+                    plan: QueryPlanNode::OuterRecord(OuterRecordNode {
+                        schema: outer_schema,
+                    }),
+                }),
+                right: Box::new(child_plan),
+            }),
+        };
+        let scope = scope.with_outer(outer.scope.clone(), outer.correlated.clone());
+        (plan, scope)
+    }
+
+    // Replaces the subqueries of an expression by the attributes their values are appended to
+    // the records of the plan as.
+    fn translate_subqueries(
+        &mut self,
+        plan: QueryPlan,
+        scope: &Scope,
+        expr: Expr,
+    ) -> Result<(QueryPlan, Expr)> {
+        match expr {
+            Expr::Binary(BinaryExpr { left, op, right }) => {
+                let (plan, left) = self.translate_subqueries(plan, scope, *left)?;
+                let (plan, right) = self.translate_subqueries(plan, scope, *right)?;
+                Ok((
+                    plan,
+                    Expr::Binary(BinaryExpr {
+                        left: Box::new(left),
+                        op,
+                        right: Box::new(right),
+                    }),
+                ))
+            }
+            Expr::Unary(ast::UnaryExpr { op, operand }) => {
+                let (plan, operand) = self.translate_subqueries(plan, scope, *operand)?;
+                Ok((
+                    plan,
+                    Expr::Unary(ast::UnaryExpr {
+                        op,
+                        operand: Box::new(operand),
+                    }),
+                ))
+            }
+            Expr::Function(FunctionCall { name, args }) => {
+                let mut plan = plan;
+                let mut translated_args = Vec::new();
+                for arg in args {
+                    let (arg_plan, arg) = self.translate_subqueries(plan, scope, arg)?;
+                    plan = arg_plan;
+                    translated_args.push(arg);
+                }
+                Ok((
+                    plan,
+                    Expr::Function(FunctionCall {
+                        name,
+                        args: translated_args,
+                    }),
+                ))
+            }
+            Expr::Subquery(SubqueryExpr { kind, query }) => {
+                let (plan, kind) = match kind {
+                    SubqueryKind::In(expr) => {
+                        let (plan, expr) = self.translate_subqueries(plan, scope, *expr)?;
+                        (plan, SubqueryKind::In(Box::new(expr)))
+                    }
+                    kind => (plan, kind),
+                };
+                self.translate_subquery(plan, scope, kind, *query)
+            }
+            expr => Ok((plan, expr)),
+        }
+    }
+
+    // The value of a subquery is appended to the records of the plan, as an attribute named by its
+    // position. Names of attributes can't start with `$`.
+    fn translate_subquery(
+        &mut self,
+        child_plan: QueryPlan,
+        scope: &Scope,
+        kind: SubqueryKind,
+        query: SelectStmt,
+    ) -> Result<(QueryPlan, Expr)> {
+        let correlated = Rc::new(Cell::new(false));
+        let query = self.translate_query(
+            query,
+            Some(&OuterQuery {
+                scope,
+                schema: &child_plan.result_schema,
+                correlated: correlated.clone(),
+            }),
+        )?;
+
+        let mut attributes = child_plan
+            .result_schema
+            .attributes
+            .attributes_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let value_type = type_check_subquery(
+            &kind,
+            &query.result_schema.attributes,
+            &child_plan.result_schema.attributes.as_lookup_table(),
+        )?;
+        let attr_name = format!("$subquery{}", attributes.len());
+        attributes.push((AttributeName(attr_name.clone()), value_type));
+        let schema = QueryResultSchema::new(Attributes::new(attributes));
+
+        let plan = QueryPlan {
+            result_schema: schema.clone(),
+            plan: QueryPlanNode::Subquery(SubqueryNode {
+                schema,
+                kind,
+                correlated: correlated.get(),
+                query: Box::new(query),
+                child: Box::new(child_plan),
+            }),
+        };
+        Ok((plan, Expr::Literal(LiteralExpr::Identifier(attr_name))))
+    }
+
+    fn translate_select_clauses(
+        &mut self,
+        plan: QueryPlan,
+        scope: &Scope,
+        clauses: SelectClauses,
+    ) -> Result<QueryPlan> {
+        let SelectClauses {
+            properties,
+            distinct,
+            group_by,
+            having,
+            order_by,
+            limit,
+        } = clauses;
+
+        // The attributes of the select list are named as they are written, before the references
+        // to attributes are resolved.
+        let items = match properties {
+            SelectProperties::Star => None,
+            SelectProperties::Identifiers(attr_names) => Some(Self::identifier_items(attr_names)),
+            SelectProperties::Exprs(items) => Some(items),
+        };
+        let items = match items {
+            Some(items) => Some(
+                items
+                    .into_iter()
+                    .map(|item| {
+                        Ok(SelectItem {
+                            alias: Some(item.name()),
+                            expr: scope.resolve(item.expr)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            None => None,
+        };
+        // `*` leaves out the attributes USING merges.
+        let items = match (items, scope.star()) {
+            (None, Some(attr_names)) => Some(Self::identifier_items(attr_names)),
+            (items, _) => items,
+        };
+        let select_list = match &items {
+            Some(items) => items
+                .iter()
+                .map(|item| (item.name(), item.expr.clone()))
+                .collect(),
+            None => plan
+                .result_schema
+                .attributes
+                .attributes_iter()
+                .map(|(attr_name, _)| {
+                    let expr = Expr::Literal(LiteralExpr::Identifier(attr_name.0.clone()));
+                    (attr_name.0.clone(), expr)
+                })
+                .collect(),
+        };
+        let order_by = Self::resolve_order_by(scope, select_list, distinct.as_ref(), order_by)?;
+        let distinct = match distinct {
+            Some(Distinct::On(exprs)) => Some(Distinct::On(
+                exprs
+                    .into_iter()
+                    .map(|expr| scope.resolve(expr))
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+            distinct => distinct,
+        };
+        let group_by = group_by
+            .into_iter()
+            .map(|expr| scope.resolve(expr))
+            .collect::<Result<Vec<_>>>()?;
+        let having = match having {
+            Some(having) => Some(scope.resolve(having)?),
+            None => None,
+        };
+
+        let is_grouped = !group_by.is_empty()
+            || having.is_some()
+            || order_by
+                .iter()
+                .any(|order_by| order_by.expr.contains_aggregate())
+            || match &items {
+                Some(items) => items.iter().any(|item| item.expr.contains_aggregate()),
+                None => false,
+            };
+        if is_grouped {
+            let record_schema = plan.result_schema.clone();
+            let items = match items {
+                Some(items) => items,
+                None => Self::identifier_items(
+                    record_schema
+                        .attributes
+                        .attributes_iter()
+                        .map(|(attr_name, _)| attr_name.0.clone())
+                        .collect(),
+                ),
+            };
+
+            let (plan, grouping) = Self::translate_grouping(
+                plan,
+                record_schema,
+                group_by,
+                having,
+                items
+                    .iter()
+                    .map(|item| &item.expr)
+                    .chain(order_by.iter().map(|order_by| &order_by.expr)),
+            )?;
+            let items = items
+                .into_iter()
+                .map(|SelectItem { expr, alias }| {
+                    Ok(SelectItem {
+                        expr: grouping.rewrite(expr)?,
+                        alias,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let distinct = match distinct {
+                Some(Distinct::On(exprs)) => Some(Distinct::On(
+                    exprs
+                        .into_iter()
+                        .map(|expr| grouping.rewrite(expr))
+                        .collect::<Result<Vec<_>>>()?,
+                )),
+                distinct => distinct,
+            };
+            let order_by = order_by
+                .into_iter()
+                .map(|OrderByExpr { expr, order, nulls }| {
+                    Ok(OrderByExpr {
+                        expr: grouping.rewrite(expr)?,
+                        order,
+                        nulls,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let plan = self.translate_projection(plan, items, distinct, order_by)?;
+            return Ok(Self::translate_limit(plan, limit));
+        }
+
+        let plan = match items {
+            // The values of the subqueries of the select list are appended to the records before
+            // they are projected.
+            Some(items) => {
+                let mut plan = plan;
+                let mut translated_items = Vec::new();
+                for SelectItem { expr, alias } in items {
+                    let (item_plan, expr) = self.translate_subqueries(plan, scope, expr)?;
+                    plan = item_plan;
+                    translated_items.push(SelectItem { expr, alias });
+                }
+                Self::translate_limit(
+                    self.translate_projection(plan, translated_items, distinct, order_by)?,
+                    limit,
+                )
+            }
+            None => {
+                let record_schema = plan.result_schema.clone();
+                let plan =
+                    Self::translate_distinct_and_sort(plan, record_schema, distinct, order_by)?;
+                Self::translate_limit(plan, limit)
+            }
+        };
+
+        Ok(plan)
+    }
+
+    // ORDER BY refers to the attributes of the select list by name when it can, otherwise to
+    // the attributes of the records. The records are sorted before DISTINCT ON picks from them,
+    // so then it always refers to the attributes of the records.
+    fn resolve_order_by(
+        scope: &Scope,
+        select_list: Vec<(String, Expr)>,
+        distinct: Option<&Distinct>,
+        order_by: Vec<OrderByExpr>,
+    ) -> Result<Vec<OrderByExpr>> {
+        // An integer literal is the position of an expression of the select list, from 1.
+        let positions = order_by
+            .iter()
+            .map(|order_by| match &order_by.expr {
+                Expr::Literal(LiteralExpr::Integer(position)) => match usize::try_from(*position) {
+                    Ok(position) if (1..=select_list.len()).contains(&position) => {
+                        Ok(Some(position - 1))
+                    }
+                    _ => Err(TranslateError::InvalidArguments(format!(
+                        "ORDER BY position {} is not in select list",
+                        position
+                    ))),
+                },
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let projection_scope = Scope::new(
+            None,
+            select_list
+                .iter()
+                .map(|(attr_name, _)| AttributeName(attr_name.clone())),
+        );
+        let sorts_projection = !matches!(distinct, Some(Distinct::On(_)))
+            && order_by.iter().zip(&positions).all(|(order_by, position)| {
+                position.is_some() || projection_scope.resolve(order_by.expr.clone()).is_ok()
+            });
+        // Sorting by anything else would pick one of the rows that DISTINCT merges.
+        if !sorts_projection && distinct == Some(&Distinct::Rows) {
+            return Err(TranslateError::InvalidArguments(
+                "for SELECT DISTINCT, ORDER BY expressions must appear in select list".to_owned(),
+            ));
         }
+        order_by
+            .into_iter()
+            .zip(positions)
+            .map(|(OrderByExpr { expr, order, nulls }, position)| {
+                let expr = match (position, sorts_projection) {
+                    (Some(position), true) => {
+                        Expr::Literal(LiteralExpr::Identifier(select_list[position].0.clone()))
+                    }
+                    (Some(position), false) => select_list[position].1.clone(),
+                    (None, true) => expr,
+                    (None, false) => scope.resolve(expr)?,
+                };
+                Ok(OrderByExpr { expr, order, nulls })
+            })
+            .collect()
     }
 
-    fn resolve_attribute_value(attribute_value: AttributeValue) -> Result<StorageTupleValue> {
-        fn resolve_literal_expr(expr: LiteralExpr) -> Result<StorageTupleValue> {
-            match expr {
-                LiteralExpr::String(s) => Ok(StorageTupleValue::String(s)),
-                LiteralExpr::Boolean(b) => Ok(StorageTupleValue::Boolean(b)),
-                LiteralExpr::Integer(i) => Ok(StorageTupleValue::Integer(i)),
-                LiteralExpr::Identifier(s) => Err(TranslateError::InvalidArguments(format!(
-                    "Identifiers cannot appear here: Found {:?}",
-                    s
-                ))),
-            }
-        }
-        fn resolve_binary_expr(expr: BinaryExpr) -> Result<StorageTupleValue> {
-            let left_type = resolve_expr(*expr.left)?;
-            let right_type = resolve_expr(*expr.right)?;
-
-            match left_type {
-                StorageTupleValue::String(_) => Err(TranslateError::InvalidArguments(
-                    "left operand of binary operations cannot be strings".to_owned(),
-                )),
-                StorageTupleValue::Integer(left) => match right_type {
-                    StorageTupleValue::Integer(right) => match &expr.op {
-                        BinaryOperation::Addition => Ok(StorageTupleValue::Integer(left + right)),
-                        BinaryOperation::Subtraction => {
-                            Ok(StorageTupleValue::Integer(left - right))
-                        }
-                        BinaryOperation::Multiplication => {
-                            Ok(StorageTupleValue::Integer(left * right))
-                        }
-                        BinaryOperation::Division => Ok(StorageTupleValue::Integer(left / right)),
-                        BinaryOperation::Equal => Ok(StorageTupleValue::Boolean(left == right)),
-                        BinaryOperation::NotEqual => Ok(StorageTupleValue::Boolean(left != right)),
-                        BinaryOperation::LessThan => Ok(StorageTupleValue::Boolean(left < right)),
-                        BinaryOperation::LessThanOrEqual => {
-                            Ok(StorageTupleValue::Boolean(left <= right))
-                        }
-                        BinaryOperation::GreaterThan => {
-                            Ok(StorageTupleValue::Boolean(left > right))
-                        }
-                        BinaryOperation::GreaterThanOrEqual => {
-                            Ok(StorageTupleValue::Boolean(left >= right))
-                        }
-                    },
-                    invalid => Err(TranslateError::InvalidArguments(format!(
-                        "Invalid right operand for arithmetic operation: {:?}",
-                        invalid
-                    ))),
-                },
-                StorageTupleValue::Boolean(left) => match right_type {
-                    StorageTupleValue::Boolean(right) => match &expr.op {
-                        BinaryOperation::Equal => Ok(StorageTupleValue::Boolean(left == right)),
-                        BinaryOperation::NotEqual => Ok(StorageTupleValue::Boolean(left != right)),
-                        op => Err(TranslateError::InvalidArguments(format!(
-                            "Invalid operation {:?} with boolean operands",
-                            op
-                        ))),
-                    },
-                    invalid => Err(TranslateError::InvalidArguments(format!(
-                        "Invalid right operand for arithmetic operation: {:?}",
-                        invalid
-                    ))),
-                },
+    // Groups the records of a plan and filters the groups by the HAVING clause. The aggregates are
+    // collected from `exprs`, the expressions that are evaluated over the groups.
+    fn translate_grouping<'e>(
+        child_plan: QueryPlan,
+        record_schema: QueryResultSchema,
+        group_by: Vec<Expr>,
+        having: Option<Expr>,
+        exprs: impl Iterator<Item = &'e Expr>,
+    ) -> Result<(QueryPlan, Grouping)> {
+        let ctx = record_schema.attributes.as_lookup_table();
+        let mut attributes = Vec::new();
+        let mut unique_group_by = Vec::new();
+        for expr in group_by {
+            if expr.contains_aggregate() {
+                return Err(TranslateError::InvalidArguments(format!(
+                    "aggregate functions are not allowed in GROUP BY, got {}",
+                    expr
+                )));
             }
-        }
-        fn resolve_expr(expr: Expr) -> Result<StorageTupleValue> {
-            match expr {
-                Expr::Binary(expr) => resolve_binary_expr(expr),
-                Expr::Literal(expr) => resolve_literal_expr(expr),
+            if !unique_group_by.contains(&expr) {
+                attributes.push((
+                    AttributeName(expr.to_string()),
+                    type_check_expr(&expr, &ctx)?,
+                ));
+                unique_group_by.push(expr);
             }
         }
 
-        match attribute_value {
-            AttributeValue::String(s) => Ok(StorageTupleValue::String(s)),
-            AttributeValue::Expr(expr) => resolve_expr(expr),
+        let mut aggregates = Vec::new();
+        for expr in exprs {
+            collect_aggregates(expr, &mut aggregates);
         }
-    }
-
-    fn translate_select(&mut self, stmt: SelectStmt) -> Result<Plan> {
-        match stmt {
-            SelectStmt::Select(stmt) => Ok(Plan::Query(self.translate_single_select(stmt)?)),
-            SelectStmt::Join(stmt) => self.translate_join(stmt),
+        if let Some(having) = &having {
+            collect_aggregates(having, &mut aggregates);
         }
-    }
-
-    fn translate_join(&mut self, stmt: JoinStmt) -> Result<Plan> {
-        let JoinStmt {
-            join_type,
-            properties,
-            left,
-            right,
-            predicate,
-        } = stmt;
-
-        let left_plan = self.translate_single_select(left)?;
-        let right_plan = self.translate_single_select(right)?;
-
-        let left_attributes = left_plan.result_schema.attributes.clone();
-        let right_attributes = right_plan.result_schema.attributes.clone();
-
-        // Check that the result schema will have unique column names.
-        {
-            let left_table = left_attributes.as_lookup_table();
-            let left_keys = left_table.keys().collect::<HashSet<_>>();
-            let right_table = right_attributes.as_lookup_table();
-            match right_table.keys().find(move |k| left_keys.contains(k)) {
-                Some(dup) => return Err(TranslateError::DuplicateAttributeName((*dup).to_owned())),
-                _ => (),
-            }
+        for call in &aggregates {
+            let name = Expr::Aggregate(call.clone()).to_string();
+            attributes.push((AttributeName(name), type_check_aggregate(call, &ctx)?));
         }
 
-        let joined_record_attributes = left_attributes
-            .attributes_iter()
-            .chain(right_attributes.attributes_iter())
-            .cloned()
+        let schema = QueryResultSchema::new(Attributes::new(attributes));
+        // The expressions are matched against the grouped ones before they are rewritten.
+        let group_by = unique_group_by
+            .iter()
+            .map(|expr| coerce_comparisons(expr.clone(), &ctx))
             .collect();
-
-        let joined_records_schema =
-            QueryResultSchema::new(Attributes::new(joined_record_attributes));
-        let ctx = joined_records_schema.attributes.as_lookup_table();
-        let predicate_expr = type_check_join_predicate(predicate, &ctx)?;
-
-        let join_plan = QueryPlan {
-            result_schema: joined_records_schema.clone(),
-            plan: QueryPlanNode::Join(JoinNode {
-                join_type,
-                predicate: predicate_expr,
-                schema: joined_records_schema.clone(),
-                left: Box::new(left_plan),
-                right: Box::new(right_plan),
-            }),
+        let aggregates = aggregates
+            .into_iter()
+            .map(|AggregateCall { function, arg }| AggregateCall {
+                function,
+                arg: arg.map(|arg| Box::new(coerce_comparisons(*arg, &ctx))),
+            })
+            .collect();
+        let grouping = Grouping {
+            group_by: unique_group_by,
+            record_schema: record_schema.clone(),
         };
-
-        let plan = match properties {
-            SelectProperties::Star => join_plan,
-            SelectProperties::Identifiers(attr_names) => {
-                self.translate_projection(join_plan, attr_names, None)?
-            }
+        let plan = QueryPlan {
+            result_schema: schema.clone(),
+            plan: QueryPlanNode::Aggregate(AggregateNode {
+                schema: schema.clone(),
+                record_schema,
+                group_by,
+                aggregates,
+                child: Box::new(child_plan),
+            }),
         };
 
-        Ok(Plan::Query(plan))
-    }
-
-    fn translate_single_select(&mut self, stmt: SingleSelectStmt) -> Result<QueryPlan> {
-        let SingleSelectStmt {
-            properties,
-            from_clause,
-            where_clause,
-            alias,
-        } = stmt;
-
-        let child_plan = match from_clause {
-            FromClause::Table(table_name) => {
-                let table_name = TableName(table_name);
-                let schema = QueryResultSchema::from(self.get_table_schema(&table_name, None)?);
+        let plan = match having {
+            Some(having) => {
+                let predicate = grouping.rewrite(having)?;
+                let ctx = schema.attributes.as_lookup_table();
+                let _ = type_check_where_clause(WhereClause::Expr(predicate.clone()), &ctx)?;
+                let predicate = coerce_comparisons(predicate, &ctx);
                 QueryPlan {
                     result_schema: schema.clone(),
-                    plan: QueryPlanNode::Scan(ScanNode { schema, table_name }),
-                }
-            }
-            FromClause::Select(nested_select) => {
-                let nested_table_plan = self.translate_select(*nested_select)?;
-                match nested_table_plan {
-                    Plan::Query(plan @ QueryPlan { .. }) => plan,
-                    _ => unreachable!(), // TODO: Use traits for Plan instead to encode these invariants?
-                }
-            }
-        };
-
-        let plan = match where_clause {
-            WhereClause::Expr(predicate) => {
-                let aliased_result_schema =
-                    child_plan.result_schema.clone().aliased(alias.as_ref());
-                let ctx = aliased_result_schema.attributes.as_lookup_table();
-                let _ = type_check_expr(&predicate, &ctx)?;
-                QueryPlan {
-                    result_schema: child_plan.result_schema.clone(),
                     plan: QueryPlanNode::Filter(FilterNode {
-                        schema: child_plan.result_schema.clone(),
                         predicate,
-                        child: Box::new(child_plan),
+                        schema,
+                        child: Box::new(plan),
                     }),
                 }
             }
-            WhereClause::None => child_plan,
-        };
-
-        let plan = match properties {
-            SelectProperties::Identifiers(attr_names) => {
-                self.translate_projection(plan, attr_names, alias.as_ref())?
-            }
-            SelectProperties::Star => QueryPlan {
-                result_schema: plan.result_schema.aliased(alias.as_ref()),
-                plan: plan.plan,
-            },
+            None => plan,
         };
-
-        Ok(plan)
+        Ok((plan, grouping))
     }
 
     fn translate_projection(
         &mut self,
         child_plan: QueryPlan,
-        projected_attr_names: Vec<String>,
-        alias: Option<&String>,
+        items: Vec<SelectItem>,
+        distinct: Option<Distinct>,
+        order_by: Vec<OrderByExpr>,
     ) -> Result<QueryPlan> {
-        let aliased_result_schema = child_plan.result_schema.clone().aliased(alias);
+        let record_schema = child_plan.result_schema.clone();
+        let record_lookup = record_schema.attributes.as_lookup_table();
 
-        let aliased_schema_lookup = aliased_result_schema.attributes.as_lookup_table();
+        let projection_result_schema =
+            QueryResultSchema::new(type_check_projection(&items, &record_lookup)?);
+        let exprs = items
+            .into_iter()
+            .map(|item| coerce_comparisons(item.expr, &record_lookup))
+            .collect();
 
-        let projection_result_schema = QueryResultSchema::new(type_check_projection(
-            &projected_attr_names,
-            &aliased_schema_lookup,
-        )?);
+        // ORDER BY refers to the projected attributes when it can, otherwise the records are
+        // sorted by the attributes they are projected from before projecting them. DISTINCT ON
+        // picks the records to project, so it's applied before the projection too.
+        let projection_lookup = projection_result_schema.attributes.as_lookup_table();
+        let sorts_projection = order_by
+            .iter()
+            .all(|order_by| type_check_expr(&order_by.expr, &projection_lookup).is_ok());
+        let (child_plan, distinct, order_by) = match (distinct, sorts_projection) {
+            (distinct @ Some(Distinct::On(_)), _) => (
+                Self::translate_distinct_and_sort(child_plan, record_schema, distinct, order_by)?,
+                None,
+                vec![],
+            ),
+            (distinct, true) => (child_plan, distinct, order_by),
+            (distinct, false) => (
+                Self::translate_sort(child_plan, record_schema, order_by)?,
+                distinct,
+                vec![],
+            ),
+        };
 
         let child_record_schema = child_plan.result_schema.clone();
 
-        Ok(QueryPlan {
+        let plan = QueryPlan {
             result_schema: projection_result_schema.clone(),
             plan: QueryPlanNode::Project(ProjectNode {
                 schema: projection_result_schema.clone(),
                 record_schema: child_record_schema,
+                exprs,
+                child: Box::new(child_plan),
+            }),
+        };
+        Self::translate_distinct_and_sort(plan, projection_result_schema, distinct, order_by)
+    }
+
+    // DISTINCT ON keeps the first record of each group in the requested order, so the records
+    // are sorted first. Otherwise duplicates are dropped before sorting the remaining records.
+    fn translate_distinct_and_sort(
+        child_plan: QueryPlan,
+        record_schema: QueryResultSchema,
+        distinct: Option<Distinct>,
+        order_by: Vec<OrderByExpr>,
+    ) -> Result<QueryPlan> {
+        match distinct {
+            Some(Distinct::On(exprs)) => {
+                let mut leading_sort_keys = order_by.iter().take(exprs.len());
+                if let Some(sort_key) = leading_sort_keys.find(|key| !exprs.contains(&key.expr)) {
+                    return Err(TranslateError::InvalidArguments(format!(
+                        "DISTINCT ON expressions must match the leading ORDER BY expressions, \
+                         got {}",
+                        sort_key.expr
+                    )));
+                }
+                let plan = Self::translate_sort(child_plan, record_schema.clone(), order_by)?;
+                Self::translate_distinct(plan, record_schema, Some(exprs))
+            }
+            Some(Distinct::Rows) => {
+                let plan = Self::translate_distinct(child_plan, record_schema.clone(), None)?;
+                Self::translate_sort(plan, record_schema, order_by)
+            }
+            None => Self::translate_sort(child_plan, record_schema, order_by),
+        }
+    }
+
+    fn translate_distinct(
+        child_plan: QueryPlan,
+        record_schema: QueryResultSchema,
+        on: Option<Vec<Expr>>,
+    ) -> Result<QueryPlan> {
+        let ctx = record_schema.attributes.as_lookup_table();
+        for expr in on.iter().flatten() {
+            let _ = type_check_expr(expr, &ctx)?;
+        }
+        let on = on.map(|exprs| {
+            exprs
+                .into_iter()
+                .map(|expr| coerce_comparisons(expr, &ctx))
+                .collect()
+        });
+
+        Ok(QueryPlan {
+            result_schema: child_plan.result_schema.clone(),
+            plan: QueryPlanNode::Distinct(DistinctNode {
+                schema: child_plan.result_schema.clone(),
+                record_schema,
+                on,
+                child: Box::new(child_plan),
+            }),
+        })
+    }
+
+    // Sorts the records of a plan, the sort keys are resolved in the given record schema.
+    fn translate_sort(
+        child_plan: QueryPlan,
+        record_schema: QueryResultSchema,
+        order_by: Vec<OrderByExpr>,
+    ) -> Result<QueryPlan> {
+        if order_by.is_empty() {
+            return Ok(child_plan);
+        }
+
+        let ctx = record_schema.attributes.as_lookup_table();
+        let sort_keys = order_by
+            .into_iter()
+            .map(|OrderByExpr { expr, order, nulls }| {
+                let _ = type_check_expr(&expr, &ctx)?;
+                let descending = order == SortOrder::Descending;
+                Ok(SortKey {
+                    expr: coerce_comparisons(expr, &ctx),
+                    descending,
+                    nulls_first: match nulls {
+                        Some(nulls) => nulls == NullsOrder::First,
+                        None => descending,
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(QueryPlan {
+            result_schema: child_plan.result_schema.clone(),
+            plan: QueryPlanNode::Sort(SortNode {
+                schema: child_plan.result_schema.clone(),
+                record_schema,
+                sort_keys,
                 child: Box::new(child_plan),
             }),
         })
     }
 
+    fn translate_limit(child_plan: QueryPlan, limit: Option<LimitClause>) -> QueryPlan {
+        match limit {
+            Some(LimitClause { count, offset }) => QueryPlan {
+                result_schema: child_plan.result_schema.clone(),
+                plan: QueryPlanNode::Limit(LimitNode {
+                    schema: child_plan.result_schema.clone(),
+                    limit: count.map(|count| count as usize),
+                    offset: offset as usize,
+                    child: Box::new(child_plan),
+                }),
+            },
+            None => child_plan,
+        }
+    }
+
+    fn identifier_items(attr_names: Vec<String>) -> Vec<SelectItem> {
+        attr_names
+            .into_iter()
+            .map(|attr_name| SelectItem {
+                expr: Expr::Literal(LiteralExpr::Identifier(attr_name)),
+                alias: None,
+            })
+            .collect()
+    }
+
     fn get_table_schema(&self, table_name: &TableName, alias: Option<&String>) -> Result<Schema> {
         match self.storage_manager.get_schema(table_name, alias) {
             Some(schema) => Ok(schema),
@@ -407,17 +1770,116 @@ impl<'storage> Translator<'storage> {
     }
 }
 
+// The query a subquery is nested in, the subquery is evaluated for each of its records.
+struct OuterQuery<'a> {
+    scope: &'a Scope,
+    schema: &'a QueryResultSchema,
+    // Set when the subquery refers to the attributes of the records.
+    correlated: Rc<Cell<bool>>,
+}
+
+// The clauses of a select statement that are applied to the records it selects from.
+struct SelectClauses {
+    properties: SelectProperties,
+    distinct: Option<Distinct>,
+    group_by: Vec<Expr>,
+    having: Option<Expr>,
+    order_by: Vec<OrderByExpr>,
+    limit: Option<LimitClause>,
+}
+
+// The GROUP BY expressions of a grouped query, expressions evaluated over the groups refer to
+// them and to the aggregates by the names of the attributes of the aggregated records.
+struct Grouping {
+    group_by: Vec<Expr>,
+    record_schema: QueryResultSchema,
+}
+
+impl Grouping {
+    fn rewrite(&self, expr: Expr) -> Result<Expr> {
+        if let Expr::Aggregate(_) = expr {
+            return Ok(Expr::Literal(LiteralExpr::Identifier(expr.to_string())));
+        }
+        if self.group_by.contains(&expr) {
+            return Ok(Expr::Literal(LiteralExpr::Identifier(expr.to_string())));
+        }
+
+        match expr {
+            Expr::Binary(BinaryExpr { left, op, right }) => Ok(Expr::Binary(BinaryExpr {
+                left: Box::new(self.rewrite(*left)?),
+                op,
+                right: Box::new(self.rewrite(*right)?),
+            })),
+            Expr::Unary(ast::UnaryExpr { op, operand }) => Ok(Expr::Unary(ast::UnaryExpr {
+                op,
+                operand: Box::new(self.rewrite(*operand)?),
+            })),
+            Expr::Function(ast::FunctionCall { name, args }) => {
+                Ok(Expr::Function(ast::FunctionCall {
+                    name,
+                    args: args
+                        .into_iter()
+                        .map(|arg| self.rewrite(arg))
+                        .collect::<Result<Vec<_>>>()?,
+                }))
+            }
+            Expr::Literal(LiteralExpr::Identifier(id))
+                if self
+                    .record_schema
+                    .attributes
+                    .attributes_iter()
+                    .any(|(attr_name, _)| attr_name.0 == id) =>
+            {
+                Err(TranslateError::InvalidArguments(format!(
+                    "attribute {} must appear in the GROUP BY clause or be used in an aggregate \
+                     function",
+                    id
+                )))
+            }
+            expr => Ok(expr),
+        }
+    }
+}
+
+fn collect_aggregates(expr: &Expr, aggregates: &mut Vec<AggregateCall>) {
+    match expr {
+        Expr::Aggregate(call) => {
+            if !aggregates.contains(call) {
+                aggregates.push(call.clone());
+            }
+        }
+        Expr::Binary(expr) => {
+            collect_aggregates(&expr.left, aggregates);
+            collect_aggregates(&expr.right, aggregates);
+        }
+        Expr::Unary(expr) => collect_aggregates(&expr.operand, aggregates),
+        Expr::Function(call) => {
+            for arg in &call.args {
+                collect_aggregates(arg, aggregates);
+            }
+        }
+        // The aggregates of a subquery are computed over the records of the subquery.
+        Expr::Subquery(subquery) => {
+            if let SubqueryKind::In(expr) = &subquery.kind {
+                collect_aggregates(expr, aggregates);
+            }
+        }
+        Expr::Literal(_) => {}
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::Result;
     use crate::parser::ast::Expr::{self, Literal};
     use crate::parser::ast::{
         AttributeDefinition, AttributeType as ParserAttributeType, AttributeValue, BinaryExpr,
-        BinaryOperation, CreateTableStmt, FromClause, InsertStmt, JoinStmt, JoinType, LiteralExpr,
-        SelectProperties, SelectStmt, SingleSelectStmt, WhereClause,
+        BinaryOperation, CreateTableStmt, FromClause, InsertStmt, JoinClause, JoinConstraint,
+        JoinType, LiteralExpr, SelectProperties, SelectStmt, SingleSelectStmt, TableReference,
+        WhereClause,
     };
     use crate::planner::plan::create_plan::CreateTablePlan;
-    use crate::planner::plan::insert_plan::InsertTuplePlan;
+    use crate::planner::plan::insert_plan::{InsertTuplePlan, OnConflict};
     use crate::planner::plan::query_plan::{
         FilterNode, JoinNode, ProjectNode, QueryPlan, QueryPlanNode, QueryResultSchema, ScanNode,
     };
@@ -475,10 +1937,12 @@ mod test {
         let stmt = InsertStmt {
             table_name: "person".to_owned(),
             attribute_names: vec!["name".to_owned(), "age".to_owned()],
-            attribute_values: vec![
+            rows: vec![vec![
                 AttributeValue::String("bob".to_owned()),
                 AttributeValue::Expr(Literal(LiteralExpr::Integer(20))),
-            ],
+            ]],
+            on_conflict: None,
+            returning: None,
         };
 
         let mut storage_manager = StorageManager::new();
@@ -499,10 +1963,70 @@ mod test {
             plan,
             Plan::InsertTuple(InsertTuplePlan {
                 table_name: TableName("person".to_owned()),
-                tuple: TupleRecord(vec![0, 0, 0, 3, 98, 111, 98, 0, 0, 0, 20])
+                schema: QueryResultSchema::new(Attributes::new(vec![
+                    (AttributeName("name".to_owned()), AttributeType::Text),
+                    (AttributeName("age".to_owned()), AttributeType::Integer),
+                ])),
+                primary_key: AttributeName("name".to_owned()),
+                tuples: vec![TupleRecord(vec![0, 0, 0, 3, 98, 111, 98, 0, 0, 0, 20])],
+                on_conflict: OnConflict::Fail,
+                returning: None,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_insert_length_limits() -> Result<()> {
+        let mut storage_manager = StorageManager::new();
+        storage_manager.create_table(CreateTableRequest {
+            table_name: TableName("person".to_owned()),
+            primary_key: AttributeName("name".to_owned()),
+            schema_attributes: vec![
+                (AttributeName("name".to_owned()), AttributeType::Varchar(3)),
+                (AttributeName("code".to_owned()), AttributeType::Char(2)),
+            ],
+        })?;
+        let mut t = Translator {
+            storage_manager: &storage_manager,
+        };
+        let insert = |name: &str, code: &str| InsertStmt {
+            table_name: "person".to_owned(),
+            attribute_names: vec!["name".to_owned(), "code".to_owned()],
+            rows: vec![vec![
+                AttributeValue::String(name.to_owned()),
+                AttributeValue::String(code.to_owned()),
+            ]],
+            on_conflict: None,
+            returning: None,
+        };
+
+        // CHAR values are padded and excess trailing spaces are truncated.
+        let plan = t.translate_insert(insert("bob  ", "a"))?;
+        assert_eq!(
+            plan,
+            Plan::InsertTuple(InsertTuplePlan {
+                table_name: TableName("person".to_owned()),
+                schema: QueryResultSchema::new(Attributes::new(vec![
+                    (AttributeName("name".to_owned()), AttributeType::Varchar(3)),
+                    (AttributeName("code".to_owned()), AttributeType::Char(2)),
+                ])),
+                primary_key: AttributeName("name".to_owned()),
+                tuples: vec![TupleRecord(vec![
+                    0, 0, 0, 3, 98, 111, 98, 0, 0, 0, 2, 97, 32
+                ])],
+                on_conflict: OnConflict::Fail,
+                returning: None,
             })
         );
 
+        let plan = t.translate_insert(insert("bobby", "a"));
+        assert_matches!(plan, Err(TranslateError::ValueTooLong(_)));
+
+        let plan = t.translate_insert(insert("bob", "abc"));
+        assert_matches!(plan, Err(TranslateError::ValueTooLong(_)));
+
         Ok(())
     }
 
@@ -517,12 +2041,17 @@ mod test {
                 right: Box::new(Expr::Literal(LiteralExpr::Integer(2))),
             })),
         });
-        let stmt = SelectStmt::Select(SingleSelectStmt {
+        let stmt = SelectStmt::Select(Box::new(SingleSelectStmt {
             properties: SelectProperties::Star,
             from_clause: FromClause::Table("person".to_owned()),
             where_clause: WhereClause::Expr(predicate.clone()),
+            order_by: vec![],
+            limit: None,
+            distinct: None,
+            group_by: vec![],
+            having: None,
             alias: None,
-        });
+        }));
 
         let schema_attributes = vec![
             (AttributeName("name".to_owned()), AttributeType::Text),
@@ -575,15 +2104,20 @@ mod test {
                 right: Box::new(Expr::Literal(LiteralExpr::Integer(2))),
             })),
         });
-        let stmt = SelectStmt::Select(SingleSelectStmt {
+        let stmt = SelectStmt::Select(Box::new(SingleSelectStmt {
             properties: SelectProperties::Identifiers(vec![
                 "is_member".to_owned(),
                 "age".to_owned(),
             ]),
             from_clause: FromClause::Table("person".to_owned()),
             where_clause: WhereClause::Expr(predicate.clone()),
+            order_by: vec![],
+            limit: None,
+            distinct: None,
+            group_by: vec![],
+            having: None,
             alias: None,
-        });
+        }));
 
         let schema_attributes = vec![
             (AttributeName("name".to_owned()), AttributeType::Text),
@@ -623,6 +2157,10 @@ mod test {
                 plan: QueryPlanNode::Project(ProjectNode {
                     schema: result_schema.clone(),
                     record_schema: schema.clone(),
+                    exprs: vec![
+                        Literal(LiteralExpr::Identifier("is_member".to_owned())),
+                        Literal(LiteralExpr::Identifier("age".to_owned())),
+                    ],
                     child: Box::new(QueryPlan {
                         result_schema: schema.clone(),
                         plan: QueryPlanNode::Filter(FilterNode {
@@ -646,15 +2184,20 @@ mod test {
 
     #[test]
     fn translate_projection_with_alias() -> Result<()> {
-        let stmt = SelectStmt::Select(SingleSelectStmt {
+        let stmt = SelectStmt::Select(Box::new(SingleSelectStmt {
             properties: SelectProperties::Identifiers(vec![
                 "employee.is_member".to_owned(),
                 "employee.age".to_owned(),
             ]),
             from_clause: FromClause::Table("person".to_owned()),
             where_clause: WhereClause::None,
+            order_by: vec![],
+            limit: None,
+            distinct: None,
+            group_by: vec![],
+            having: None,
             alias: Some("employee".to_owned()),
-        });
+        }));
 
         let schema_attributes = vec![
             (AttributeName("name".to_owned()), AttributeType::Text),
@@ -696,9 +2239,13 @@ mod test {
                 result_schema: result_schema.clone(),
                 plan: QueryPlanNode::Project(ProjectNode {
                     schema: result_schema.clone(),
-                    record_schema: schema.clone(),
+                    record_schema: schema.clone().with_alias("employee"),
+                    exprs: vec![
+                        Literal(LiteralExpr::Identifier("employee.is_member".to_owned())),
+                        Literal(LiteralExpr::Identifier("employee.age".to_owned())),
+                    ],
                     child: Box::new(QueryPlan {
-                        result_schema: schema.clone(),
+                        result_schema: schema.clone().with_alias("employee"),
                         plan: QueryPlanNode::Scan(ScanNode {
                             schema: schema.clone(),
                             table_name: TableName("person".to_owned())
@@ -713,15 +2260,21 @@ mod test {
 
     #[test]
     fn translate_projection_with_wrong_alias() -> Result<()> {
-        let stmt = SelectStmt::Select(SingleSelectStmt {
+        let stmt = SelectStmt::Select(Box::new(SingleSelectStmt {
+            // The alias hides the name of the table.
             properties: SelectProperties::Identifiers(vec![
-                "is_member".to_owned(),
-                "age".to_owned(),
+                "employee.is_member".to_owned(),
+                "person.age".to_owned(),
             ]),
             from_clause: FromClause::Table("person".to_owned()),
             where_clause: WhereClause::None,
+            order_by: vec![],
+            limit: None,
+            distinct: None,
+            group_by: vec![],
+            having: None,
             alias: Some("employee".to_owned()),
-        });
+        }));
 
         let schema_attributes = vec![
             (AttributeName("name".to_owned()), AttributeType::Text),
@@ -754,31 +2307,45 @@ mod test {
     fn translate_inner_join() -> Result<()> {
         // select person.age, employee.name from foo as person
         //  inner join (select * from foo where false) as employee on true;
-        let stmt = SelectStmt::Join(JoinStmt {
-            join_type: JoinType::InnerJoin,
+        let stmt = SelectStmt::Select(Box::new(SingleSelectStmt {
             properties: SelectProperties::Identifiers(vec![
                 "person.age".to_owned(),
                 "employee.name".to_owned(),
             ]),
-            left: SingleSelectStmt {
-                properties: SelectProperties::Star,
-                from_clause: FromClause::Table("foo".to_owned()),
-                where_clause: WhereClause::None,
-                alias: Some("person".to_owned()),
-            },
-            right: SingleSelectStmt {
-                properties: SelectProperties::Star,
-                from_clause: FromClause::Select(Box::new(SelectStmt::Select(SingleSelectStmt {
-                    properties: SelectProperties::Star,
+            from_clause: FromClause::Join(Box::new(JoinClause {
+                join_type: JoinType::Inner,
+                left: TableReference {
                     from_clause: FromClause::Table("foo".to_owned()),
-                    where_clause: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(false))),
-                    alias: None,
-                }))),
-                where_clause: WhereClause::None,
-                alias: Some("employee".to_owned()),
-            },
-            predicate: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(true))),
-        });
+                    alias: Some("person".to_owned()),
+                },
+                right: TableReference {
+                    from_clause: FromClause::Select(Box::new(SelectStmt::Select(Box::new(
+                        SingleSelectStmt {
+                            properties: SelectProperties::Star,
+                            from_clause: FromClause::Table("foo".to_owned()),
+                            where_clause: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(
+                                false,
+                            ))),
+                            order_by: vec![],
+                            limit: None,
+                            distinct: None,
+                            group_by: vec![],
+                            having: None,
+                            alias: None,
+                        },
+                    )))),
+                    alias: Some("employee".to_owned()),
+                },
+                constraint: JoinConstraint::On(Expr::Literal(LiteralExpr::Boolean(true))),
+            })),
+            where_clause: WhereClause::None,
+            order_by: vec![],
+            limit: None,
+            distinct: None,
+            group_by: vec![],
+            having: None,
+            alias: None,
+        }));
 
         let schema_attributes = vec![
             (AttributeName("name".to_owned()), AttributeType::Text),
@@ -830,10 +2397,14 @@ mod test {
                 plan: QueryPlanNode::Project(ProjectNode {
                     schema: projection_schema.clone(),
                     record_schema: join_schema.clone(),
+                    exprs: vec![
+                        Literal(LiteralExpr::Identifier("person.age".to_owned())),
+                        Literal(LiteralExpr::Identifier("employee.name".to_owned())),
+                    ],
                     child: Box::new(QueryPlan {
                         result_schema: join_schema.clone(),
                         plan: QueryPlanNode::Join(JoinNode {
-                            join_type: JoinType::InnerJoin,
+                            join_type: JoinType::Inner,
                             predicate: Expr::Literal(LiteralExpr::Boolean(true)),
                             schema: join_schema.clone(),
                             left: Box::new(QueryPlan {
@@ -870,23 +2441,28 @@ mod test {
     #[test]
     fn translate_inner_join_wrong_aliases() -> Result<()> {
         // select * from foo as person inner join foo as person on true;
-        let stmt = SelectStmt::Join(JoinStmt {
-            join_type: JoinType::InnerJoin,
+        let stmt = SelectStmt::Select(Box::new(SingleSelectStmt {
             properties: SelectProperties::Star,
-            left: SingleSelectStmt {
-                properties: SelectProperties::Star,
-                from_clause: FromClause::Table("foo".to_owned()),
-                where_clause: WhereClause::None,
-                alias: Some("person".to_owned()),
-            },
-            right: SingleSelectStmt {
-                properties: SelectProperties::Star,
-                from_clause: FromClause::Table("foo".to_owned()),
-                where_clause: WhereClause::None,
-                alias: Some("person".to_owned()),
-            },
-            predicate: WhereClause::Expr(Expr::Literal(LiteralExpr::Boolean(true))),
-        });
+            from_clause: FromClause::Join(Box::new(JoinClause {
+                join_type: JoinType::Inner,
+                left: TableReference {
+                    from_clause: FromClause::Table("foo".to_owned()),
+                    alias: Some("person".to_owned()),
+                },
+                right: TableReference {
+                    from_clause: FromClause::Table("foo".to_owned()),
+                    alias: Some("person".to_owned()),
+                },
+                constraint: JoinConstraint::On(Expr::Literal(LiteralExpr::Boolean(true))),
+            })),
+            where_clause: WhereClause::None,
+            order_by: vec![],
+            limit: None,
+            distinct: None,
+            group_by: vec![],
+            having: None,
+            alias: None,
+        }));
 
         let schema_attributes = vec![
             (AttributeName("name".to_owned()), AttributeType::Text),
@@ -914,23 +2490,28 @@ mod test {
     #[test]
     fn translate_inner_join_wrong_predicate_type() -> Result<()> {
         // select * from foo as p1 inner join foo as p2 on 3;
-        let stmt = SelectStmt::Join(JoinStmt {
-            join_type: JoinType::InnerJoin,
+        let stmt = SelectStmt::Select(Box::new(SingleSelectStmt {
             properties: SelectProperties::Star,
-            left: SingleSelectStmt {
-                properties: SelectProperties::Star,
-                from_clause: FromClause::Table("foo".to_owned()),
-                where_clause: WhereClause::None,
-                alias: Some("p1".to_owned()),
-            },
-            right: SingleSelectStmt {
-                properties: SelectProperties::Star,
-                from_clause: FromClause::Table("foo".to_owned()),
-                where_clause: WhereClause::None,
-                alias: Some("p2".to_owned()),
-            },
-            predicate: WhereClause::Expr(Expr::Literal(LiteralExpr::Integer(3))),
-        });
+            from_clause: FromClause::Join(Box::new(JoinClause {
+                join_type: JoinType::Inner,
+                left: TableReference {
+                    from_clause: FromClause::Table("foo".to_owned()),
+                    alias: Some("p1".to_owned()),
+                },
+                right: TableReference {
+                    from_clause: FromClause::Table("foo".to_owned()),
+                    alias: Some("p2".to_owned()),
+                },
+                constraint: JoinConstraint::On(Expr::Literal(LiteralExpr::Integer(3))),
+            })),
+            where_clause: WhereClause::None,
+            order_by: vec![],
+            limit: None,
+            distinct: None,
+            group_by: vec![],
+            having: None,
+            alias: None,
+        }));
 
         let schema_attributes = vec![
             (AttributeName("name".to_owned()), AttributeType::Text),
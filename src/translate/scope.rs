@@ -0,0 +1,356 @@
+use crate::parser::ast::{
+    AggregateCall, BinaryExpr, Expr, FunctionCall, LiteralExpr, SubqueryExpr, SubqueryKind,
+    UnaryExpr,
+};
+use crate::storage::storage_manager::AttributeName;
+use crate::translate::error::{Result, TranslateError};
+use std::cell::Cell;
+use std::rc::Rc;
+
+// The attributes the expressions of a query can refer to. An attribute is referred to by its
+// name, or by its name qualified by the table reference it comes from e.g `p.name`. References
+// are resolved to the names of the attributes of the records the query selects from, which are
+// unique even when the names of the attributes of several table references are the same.
+#[derive(Debug, Clone)]
+pub(crate) struct Scope {
+    attributes: Vec<ScopeAttribute>,
+    outer: Option<OuterScope>,
+}
+
+// The scope of the query a subquery is nested in. The records of the subquery hold the attributes
+// of the record of the outer query it is evaluated for, prefixed by `$outer.`, which is not a
+// name the attributes of a table can have.
+#[derive(Debug, Clone)]
+struct OuterScope {
+    scope: Box<Scope>,
+    // Set when an attribute of the outer query is referred to.
+    correlated: Rc<Cell<bool>>,
+}
+
+#[derive(Debug, Clone)]
+struct ScopeAttribute {
+    qualifier: Option<String>,
+    name: String,
+    attr_name: AttributeName,
+    // The attributes USING merges are only referred to by their qualified names, and are left
+    // out of `*`.
+    hidden: bool,
+}
+
+impl Scope {
+    // The attributes of a relation that are named as they are in its records, the attributes of
+    // a table are qualified by the name of the table.
+    pub fn new(qualifier: Option<&str>, attr_names: impl Iterator<Item = AttributeName>) -> Self {
+        Scope {
+            attributes: attr_names
+                .map(|attr_name| ScopeAttribute {
+                    qualifier: qualifier.map(|qualifier| qualifier.to_owned()),
+                    name: attr_name.0.clone(),
+                    attr_name,
+                    hidden: false,
+                })
+                .collect(),
+            outer: None,
+        }
+    }
+
+    // References to attributes that are not in this scope are resolved in the outer scope.
+    pub fn with_outer(self, outer: Scope, correlated: Rc<Cell<bool>>) -> Self {
+        Scope {
+            attributes: self.attributes,
+            outer: Some(OuterScope {
+                scope: Box::new(outer),
+                correlated,
+            }),
+        }
+    }
+
+    // An alias replaces the qualifier of the attributes, the attributes of the records are
+    // renamed like `Attributes::with_alias` renames them.
+    pub fn with_alias(self, alias: &str) -> Self {
+        Scope {
+            attributes: self
+                .attributes
+                .into_iter()
+                .map(|attribute| ScopeAttribute {
+                    // The alias hides the qualifiers of the merged attributes.
+                    qualifier: match attribute.hidden {
+                        true => None,
+                        false => Some(alias.to_owned()),
+                    },
+                    name: attribute.name,
+                    attr_name: AttributeName(format!("{}.{}", alias, attribute.attr_name.0)),
+                    hidden: attribute.hidden,
+                })
+                .collect(),
+            outer: self.outer,
+        }
+    }
+
+    // The attributes of both sides of a join.
+    pub fn join(mut self, other: Scope) -> Self {
+        self.attributes.extend(other.attributes);
+        self
+    }
+
+    // USING merges an attribute of each side of a join into a single attribute, which is the
+    // `position`th attribute of `*` and is referred to by its unqualified name. The merged
+    // attributes are still referred to by their qualified names.
+    pub fn merge(mut self, position: usize, name: &str, merged: &[&AttributeName]) -> Self {
+        for attribute in &mut self.attributes {
+            if merged.contains(&&attribute.attr_name) {
+                attribute.hidden = true;
+            }
+        }
+        self.attributes.insert(
+            position,
+            ScopeAttribute {
+                qualifier: None,
+                name: name.to_owned(),
+                attr_name: AttributeName(name.to_owned()),
+                hidden: false,
+            },
+        );
+        self
+    }
+
+    // The names of the attributes that are referred to without a qualifier, each name once.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for attribute in self.attributes.iter().filter(|attribute| !attribute.hidden) {
+            if !names.contains(&attribute.name.as_str()) {
+                names.push(attribute.name.as_str());
+            }
+        }
+        names
+    }
+
+    // The names of the attributes `*` selects, when they are not all the attributes of the
+    // records.
+    pub fn star(&self) -> Option<Vec<String>> {
+        if self.outer.is_none() && !self.attributes.iter().any(|attribute| attribute.hidden) {
+            return None;
+        }
+        let mut attr_names = Vec::new();
+        for attribute in self.attributes.iter().filter(|attribute| !attribute.hidden) {
+            if !attr_names.contains(&attribute.attr_name.0) {
+                attr_names.push(attribute.attr_name.0.clone());
+            }
+        }
+        Some(attr_names)
+    }
+
+    // Replaces the attribute references of an expression by the names of the attributes.
+    pub fn resolve(&self, expr: Expr) -> Result<Expr> {
+        match expr {
+            Expr::Binary(BinaryExpr { left, op, right }) => Ok(Expr::Binary(BinaryExpr {
+                left: Box::new(self.resolve(*left)?),
+                op,
+                right: Box::new(self.resolve(*right)?),
+            })),
+            Expr::Unary(UnaryExpr { op, operand }) => Ok(Expr::Unary(UnaryExpr {
+                op,
+                operand: Box::new(self.resolve(*operand)?),
+            })),
+            Expr::Function(FunctionCall { name, args }) => Ok(Expr::Function(FunctionCall {
+                name,
+                args: args
+                    .into_iter()
+                    .map(|arg| self.resolve(arg))
+                    .collect::<Result<Vec<_>>>()?,
+            })),
+            Expr::Aggregate(AggregateCall { function, arg }) => {
+                Ok(Expr::Aggregate(AggregateCall {
+                    function,
+                    arg: match arg {
+                        Some(arg) => Some(Box::new(self.resolve(*arg)?)),
+                        None => None,
+                    },
+                }))
+            }
+            Expr::Literal(LiteralExpr::Identifier(id)) => Ok(Expr::Literal(
+                LiteralExpr::Identifier(self.resolve_identifier(&id)?.0),
+            )),
+            // The query of a subquery is resolved when it is translated.
+            Expr::Subquery(SubqueryExpr {
+                kind: SubqueryKind::In(expr),
+                query,
+            }) => Ok(Expr::Subquery(SubqueryExpr {
+                kind: SubqueryKind::In(Box::new(self.resolve(*expr)?)),
+                query,
+            })),
+            expr => Ok(expr),
+        }
+    }
+
+    pub fn resolve_identifier(&self, id: &str) -> Result<AttributeName> {
+        let mut matches = self
+            .attributes
+            .iter()
+            .filter(|attribute| match id.find('.') {
+                Some(index) => {
+                    attribute.qualifier.as_deref() == Some(&id[..index])
+                        && attribute.name == id[index + 1..]
+                }
+                None => !attribute.hidden && attribute.name == id,
+            });
+        match (matches.next(), matches.next()) {
+            (Some(attribute), None) => Ok(attribute.attr_name.clone()),
+            (Some(_), Some(_)) => Err(TranslateError::AmbiguousAttributeName(id.to_owned())),
+            (None, _) => match &self.outer {
+                Some(outer) => {
+                    let attr_name = outer.scope.resolve_identifier(id)?;
+                    outer.correlated.set(true);
+                    Ok(AttributeName(format!("$outer.{}", attr_name.0)))
+                }
+                None => Err(TranslateError::NoSuchAttribute(id.to_owned())),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::ast::{BinaryExpr, BinaryOperation, Expr, LiteralExpr};
+    use crate::storage::storage_manager::AttributeName;
+    use crate::translate::error::TranslateError;
+    use crate::translate::scope::Scope;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn resolve() {
+        let person = || {
+            vec!["name", "age"]
+                .into_iter()
+                .map(|attr_name| AttributeName(attr_name.to_owned()))
+        };
+        let employee = || {
+            vec!["name", "salary"]
+                .into_iter()
+                .map(|attr_name| AttributeName(attr_name.to_owned()))
+        };
+        let id = |id: &str| Expr::Literal(LiteralExpr::Identifier(id.to_owned()));
+        let resolve = |scope: &Scope, name: &str| match scope.resolve(id(name)) {
+            Ok(Expr::Literal(LiteralExpr::Identifier(attr_name))) => Ok(attr_name),
+            Ok(expr) => panic!("expected an identifier, got {:?}", expr),
+            Err(err) => Err(err),
+        };
+
+        let scope = Scope::new(Some("person"), person());
+        assert_eq!(resolve(&scope, "name").unwrap(), "name");
+        assert_eq!(resolve(&scope, "person.name").unwrap(), "name");
+        assert_matches!(
+            resolve(&scope, "p.name"),
+            Err(TranslateError::NoSuchAttribute(_))
+        );
+
+        let scope = Scope::new(Some("person"), person())
+            .with_alias("p")
+            .join(Scope::new(Some("employee"), employee()).with_alias("employee"));
+        assert_eq!(resolve(&scope, "p.name").unwrap(), "p.name");
+        assert_eq!(resolve(&scope, "employee.name").unwrap(), "employee.name");
+        assert_eq!(resolve(&scope, "age").unwrap(), "p.age");
+        assert_eq!(resolve(&scope, "salary").unwrap(), "employee.salary");
+        assert_matches!(
+            resolve(&scope, "person.name"),
+            Err(TranslateError::NoSuchAttribute(_))
+        );
+        assert_matches!(
+            resolve(&scope, "name"),
+            Err(TranslateError::AmbiguousAttributeName(_))
+        );
+
+        assert_eq!(
+            scope
+                .resolve(Expr::Binary(BinaryExpr {
+                    left: Box::new(id("age")),
+                    op: BinaryOperation::Equal,
+                    right: Box::new(id("employee.salary")),
+                }))
+                .unwrap(),
+            Expr::Binary(BinaryExpr {
+                left: Box::new(id("p.age")),
+                op: BinaryOperation::Equal,
+                right: Box::new(id("employee.salary")),
+            })
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let attr_names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| AttributeName((*name).to_owned()))
+                .collect::<Vec<_>>()
+        };
+        let resolve = |scope: &Scope, id: &str| scope.resolve_identifier(id).map(|name| name.0);
+
+        let scope = Scope::new(Some("person"), attr_names(&["id", "name"]).into_iter())
+            .with_alias("person")
+            .join(
+                Scope::new(Some("employee"), attr_names(&["id", "salary"]).into_iter())
+                    .with_alias("employee"),
+            );
+        assert_eq!(scope.names(), vec!["id", "name", "salary"]);
+        assert_eq!(scope.star(), None);
+
+        let scope = scope.merge(
+            0,
+            "id",
+            &[
+                &AttributeName("person.id".to_owned()),
+                &AttributeName("employee.id".to_owned()),
+            ],
+        );
+        assert_eq!(resolve(&scope, "id").unwrap(), "id");
+        assert_eq!(resolve(&scope, "person.id").unwrap(), "person.id");
+        assert_eq!(resolve(&scope, "employee.id").unwrap(), "employee.id");
+        assert_eq!(resolve(&scope, "name").unwrap(), "person.name");
+        assert_eq!(
+            scope.star().unwrap(),
+            vec!["id", "person.name", "employee.salary"]
+        );
+
+        let scope = scope.with_alias("j");
+        assert_eq!(resolve(&scope, "j.id").unwrap(), "j.id");
+        assert_matches!(
+            resolve(&scope, "person.id"),
+            Err(TranslateError::NoSuchAttribute(_))
+        );
+        assert_eq!(
+            scope.star().unwrap(),
+            vec!["j.id", "j.person.name", "j.employee.salary"]
+        );
+    }
+
+    #[test]
+    fn outer() {
+        let attr_names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| AttributeName((*name).to_owned()))
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+        let resolve = |scope: &Scope, id: &str| scope.resolve_identifier(id).map(|name| name.0);
+
+        let person = Scope::new(Some("person"), attr_names(&["id", "name"]));
+        let correlated = Rc::new(Cell::new(false));
+        let scope = Scope::new(Some("employee"), attr_names(&["id", "salary"]))
+            .with_alias("e")
+            .with_outer(person, correlated.clone());
+        assert_eq!(resolve(&scope, "id").unwrap(), "e.id");
+        assert_eq!(resolve(&scope, "salary").unwrap(), "e.salary");
+        assert!(!correlated.get());
+        assert_eq!(resolve(&scope, "person.id").unwrap(), "$outer.id");
+        assert_eq!(resolve(&scope, "name").unwrap(), "$outer.name");
+        assert!(correlated.get());
+        assert_matches!(
+            resolve(&scope, "age"),
+            Err(TranslateError::NoSuchAttribute(_))
+        );
+        assert_eq!(scope.star().unwrap(), vec!["e.id", "e.salary"]);
+    }
+}
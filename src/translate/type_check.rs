@@ -1,6 +1,11 @@
-use crate::parser::ast::{BinaryOperation, Expr, LiteralExpr, WhereClause};
+use crate::parser::ast::{
+    AggregateCall, AggregateFunction, BinaryExpr, BinaryOperation, Expr, FunctionCall, LiteralExpr,
+    SelectItem, SetOperator, SubqueryKind, UnaryExpr, UnaryOperation, WhereClause,
+};
+use crate::storage::json::JsonValue;
 use crate::storage::storage_manager::{AttributeName, Attributes};
-use crate::storage::types::AttributeType;
+use crate::storage::types::{AttributeType, EnumType};
+use crate::storage::uuid::Uuid;
 use crate::translate::error::{Result, TranslateError};
 use std::collections::HashMap;
 
@@ -19,68 +24,440 @@ pub fn type_check_expr(
             Expr::Binary(expr) => {
                 let left = type_check(&expr.left, ctx)?;
                 let right = type_check(&expr.right, ctx)?;
-                if left != right {
-                    return Err(TranslateError::TypeError(format!(
-                        "For {:?} operation, left {:?} != right {:?}",
-                        expr.op, left, right
-                    )));
-                }
+                // A member missing from a JSON value is extracted as NULL.
+                let nullable = left.is_nullable()
+                    || right.is_nullable()
+                    || matches!(
+                        expr.op,
+                        BinaryOperation::JsonExtract | BinaryOperation::JsonExtractText
+                    );
+                type_check_binary(expr, left.base_type(), right.base_type())
+                    .map(|result| nullable_if(result, nullable))
+            }
 
-                match left {
-                    AttributeType::Text | AttributeType::Boolean => {
-                        return match expr.op {
-                            BinaryOperation::Equal | BinaryOperation::NotEqual => {
-                                Ok(AttributeType::Boolean)
-                            }
-                            _ => Err(TranslateError::TypeError(format!(
-                                "Arguments of type {:?} are not valid for operation {:?}",
-                                left, expr.op
-                            ))),
-                        }
+            Expr::Unary(expr) => {
+                let operand = type_check(&expr.operand, ctx)?;
+                let nullable = operand.is_nullable();
+                let operand = operand.base_type();
+                match (&expr.op, &operand) {
+                    (UnaryOperation::Not, AttributeType::Boolean) => Ok(AttributeType::Boolean),
+                    (UnaryOperation::Negation, AttributeType::Integer) => {
+                        Ok(AttributeType::Integer)
                     }
-                    AttributeType::Integer => match expr.op {
-                        BinaryOperation::Equal
-                        | BinaryOperation::NotEqual
-                        | BinaryOperation::LessThan
-                        | BinaryOperation::LessThanOrEqual
-                        | BinaryOperation::GreaterThan
-                        | BinaryOperation::GreaterThanOrEqual => Ok(AttributeType::Boolean),
-                        BinaryOperation::Addition
-                        | BinaryOperation::Subtraction
-                        | BinaryOperation::Multiplication
-                        | BinaryOperation::Division => Ok(AttributeType::Integer),
-                    },
+                    (op, _) => Err(TranslateError::TypeError(format!(
+                        "Argument of type {:?} is not valid for operation {:?}",
+                        operand, op
+                    ))),
                 }
+                .map(|result| nullable_if(result, nullable))
             }
 
             Expr::Literal(expr) => match expr {
                 LiteralExpr::Integer(_) => Ok(AttributeType::Integer),
                 LiteralExpr::Boolean(_) => Ok(AttributeType::Boolean),
                 LiteralExpr::String(_) => Ok(AttributeType::Text),
+                LiteralExpr::Binary(_) => Ok(AttributeType::Binary),
                 LiteralExpr::Identifier(attr) => eval(attr, ctx),
             },
+
+            Expr::Function(call) => {
+                let arg_types = call
+                    .args
+                    .iter()
+                    .map(|arg| type_check(arg, ctx))
+                    .collect::<Result<Vec<_>>>()?;
+                // COALESCE is NULL only when all of its arguments are.
+                let nullable = match call.name.as_str() {
+                    "coalesce" => arg_types.iter().all(AttributeType::is_nullable),
+                    _ => arg_types.iter().any(AttributeType::is_nullable),
+                };
+                let arg_types = arg_types.iter().map(AttributeType::base_type).collect();
+                type_check_function(call, arg_types).map(|result| nullable_if(result, nullable))
+            }
+            // Aggregates are computed before the expressions using them are evaluated, see
+            // `type_check_aggregate`.
+            Expr::Aggregate(call) => Err(TranslateError::InvalidArguments(format!(
+                "aggregate function {} is not allowed here",
+                call.function
+            ))),
+            // Subqueries are evaluated before the expressions using them, see
+            // `type_check_subquery`.
+            Expr::Subquery(_) => Err(TranslateError::InvalidArguments(format!(
+                "subquery {} is not allowed here",
+                expr
+            ))),
         }
     }
 
     type_check(expr, ctx)
 }
 
+// The type of a binary expression over values that are not NULL.
+fn type_check_binary(
+    expr: &BinaryExpr,
+    left: AttributeType,
+    right: AttributeType,
+) -> Result<AttributeType> {
+    if let BinaryOperation::JsonExtract | BinaryOperation::JsonExtractText = expr.op {
+        return match (&left, &right) {
+            (AttributeType::Json, AttributeType::Text)
+            | (AttributeType::Json, AttributeType::Integer) => match expr.op {
+                BinaryOperation::JsonExtract => Ok(AttributeType::Json),
+                _ => Ok(AttributeType::Text),
+            },
+            _ => Err(TranslateError::TypeError(format!(
+                "For {:?} operation, expected a JSON value and a key or index, got {:?} and {:?}",
+                expr.op, left, right
+            ))),
+        };
+    }
+    if let BinaryOperation::And | BinaryOperation::Or = expr.op {
+        return match (&left, &right) {
+            (AttributeType::Boolean, AttributeType::Boolean) => Ok(AttributeType::Boolean),
+            _ => Err(TranslateError::TypeError(format!(
+                "For {:?} operation, expected boolean operands, got {:?} and {:?}",
+                expr.op, left, right
+            ))),
+        };
+    }
+    if let Some(result) = type_check_uuid_literal(expr, &left, &right) {
+        return result;
+    }
+    if let Some(result) = type_check_enum_label(expr, &left, &right) {
+        return result;
+    }
+    if left != right {
+        return Err(TranslateError::TypeError(format!(
+            "For {:?} operation, left {:?} != right {:?}",
+            expr.op, left, right
+        )));
+    }
+
+    match left {
+        AttributeType::Text
+        | AttributeType::Varchar(_)
+        | AttributeType::Char(_)
+        | AttributeType::Boolean
+        | AttributeType::Binary
+        | AttributeType::Json => {
+            return match expr.op {
+                BinaryOperation::Equal | BinaryOperation::NotEqual => Ok(AttributeType::Boolean),
+                _ => Err(TranslateError::TypeError(format!(
+                    "Arguments of type {:?} are not valid for operation {:?}",
+                    left, expr.op
+                ))),
+            }
+        }
+        AttributeType::Uuid | AttributeType::Enum(_) => {
+            if expr.op.is_comparison() {
+                Ok(AttributeType::Boolean)
+            } else {
+                Err(TranslateError::TypeError(format!(
+                    "Arguments of type {:?} are not valid for operation {:?}",
+                    left, expr.op
+                )))
+            }
+        }
+        AttributeType::Integer => match expr.op {
+            BinaryOperation::Equal
+            | BinaryOperation::NotEqual
+            | BinaryOperation::LessThan
+            | BinaryOperation::LessThanOrEqual
+            | BinaryOperation::GreaterThan
+            | BinaryOperation::GreaterThanOrEqual => Ok(AttributeType::Boolean),
+            BinaryOperation::Addition
+            | BinaryOperation::Subtraction
+            | BinaryOperation::Multiplication
+            | BinaryOperation::Division => Ok(AttributeType::Integer),
+            BinaryOperation::JsonExtract
+            | BinaryOperation::JsonExtractText
+            | BinaryOperation::And
+            | BinaryOperation::Or => {
+                unreachable!("JSON and boolean operations are checked above")
+            }
+        },
+        AttributeType::Nullable(_) => unreachable!("base types are not nullable"),
+    }
+}
+
+// An expression is NULL when any of its operands is.
+fn nullable_if(attr_type: AttributeType, nullable: bool) -> AttributeType {
+    match nullable {
+        true => attr_type.nullable(),
+        false => attr_type,
+    }
+}
+
+// The type of an aggregate computed over records of the given context. There is no decimal type,
+// the average of integers is truncated to an integer. NULL arguments are skipped, only COUNT has a
+// value when no argument is left.
+pub fn type_check_aggregate(
+    call: &AggregateCall,
+    ctx: &HashMap<&String, &AttributeType>,
+) -> Result<AttributeType> {
+    let arg_type = match &call.arg {
+        Some(arg) => Some(type_check_expr(arg, ctx)?.without_null()),
+        None => None,
+    };
+    match (&call.function, arg_type) {
+        (AggregateFunction::Count, _) => Ok(AttributeType::Integer),
+        (AggregateFunction::Sum, Some(AttributeType::Integer))
+        | (AggregateFunction::Avg, Some(AttributeType::Integer)) => {
+            Ok(AttributeType::Integer.nullable())
+        }
+        (AggregateFunction::Min, Some(arg_type)) | (AggregateFunction::Max, Some(arg_type)) => {
+            Ok(arg_type.nullable())
+        }
+        (function, arg_type) => Err(TranslateError::TypeError(format!(
+            "Argument of type {:?} is not valid for aggregate function {}",
+            arg_type, function
+        ))),
+    }
+}
+
+// A UUID can be compared against a string literal holding a valid UUID.
+fn type_check_uuid_literal(
+    expr: &BinaryExpr,
+    left: &AttributeType,
+    right: &AttributeType,
+) -> Option<Result<AttributeType>> {
+    let literal = match (left, right, expr.left.as_ref(), expr.right.as_ref()) {
+        (AttributeType::Uuid, AttributeType::Text, _, Expr::Literal(LiteralExpr::String(s)))
+        | (AttributeType::Text, AttributeType::Uuid, Expr::Literal(LiteralExpr::String(s)), _) => s,
+        _ => return None,
+    };
+    let result = match Uuid::parse(literal) {
+        Ok(_) if expr.op.is_comparison() => Ok(AttributeType::Boolean),
+        Ok(_) => Err(TranslateError::TypeError(format!(
+            "Arguments of type {:?} are not valid for operation {:?}",
+            AttributeType::Uuid,
+            expr.op
+        ))),
+        Err(err) => Err(TranslateError::InvalidArguments(err.to_string())),
+    };
+    Some(result)
+}
+
+// An enum can be compared against a string literal holding one of its labels.
+fn type_check_enum_label(
+    expr: &BinaryExpr,
+    left: &AttributeType,
+    right: &AttributeType,
+) -> Option<Result<AttributeType>> {
+    let (enum_type, label) = match (left, right, expr.left.as_ref(), expr.right.as_ref()) {
+        (AttributeType::Enum(t), AttributeType::Text, _, Expr::Literal(LiteralExpr::String(s)))
+        | (AttributeType::Text, AttributeType::Enum(t), Expr::Literal(LiteralExpr::String(s)), _) => {
+            (t, s)
+        }
+        _ => return None,
+    };
+    let result = match enum_type.find_label(label) {
+        Some(_) if expr.op.is_comparison() => Ok(AttributeType::Boolean),
+        Some(_) => Err(TranslateError::TypeError(format!(
+            "Arguments of type {:?} are not valid for operation {:?}",
+            AttributeType::Enum(enum_type.clone()),
+            expr.op
+        ))),
+        None => Err(TranslateError::InvalidArguments(format!(
+            "invalid label {:?} for enum {:?}",
+            label, enum_type.name
+        ))),
+    };
+    Some(result)
+}
+
+fn type_check_function(
+    call: &FunctionCall,
+    arg_types: Vec<AttributeType>,
+) -> Result<AttributeType> {
+    match (call.name.as_str(), arg_types.as_slice()) {
+        ("json_extract", [AttributeType::Json, AttributeType::Text]) => {
+            // Reject malformed paths upfront when they are known.
+            if let Expr::Literal(LiteralExpr::String(path)) = &call.args[1] {
+                JsonValue::Null
+                    .get_path(path)
+                    .map_err(|err| TranslateError::InvalidArguments(err.to_string()))?;
+            }
+            Ok(AttributeType::Json)
+        }
+        ("gen_random_uuid", []) => Ok(AttributeType::Uuid),
+        ("coalesce", [first, rest @ ..]) if rest.iter().all(|arg_type| arg_type == first) => {
+            Ok(first.clone())
+        }
+        ("coalesce", _) => Err(TranslateError::TypeError(format!(
+            "coalesce expects arguments of the same type, got {:?}",
+            arg_types
+        ))),
+        ("gen_random_uuid", _) => Err(TranslateError::TypeError(format!(
+            "gen_random_uuid expects no arguments, got {:?}",
+            arg_types
+        ))),
+        ("json_extract", _) => Err(TranslateError::TypeError(format!(
+            "json_extract expects a JSON value and a path, got {:?}",
+            arg_types
+        ))),
+        (name, _) => Err(TranslateError::InvalidArguments(format!(
+            "no such function {:?}",
+            name
+        ))),
+    }
+}
+
+// Rewrites comparisons whose operands are compared as values of another type. A label compared
+// against an enum is compared as a value of the enum, which is ordered by the position of its
+// label.
+pub fn coerce_comparisons(expr: Expr, ctx: &HashMap<&String, &AttributeType>) -> Expr {
+    let type_of = |expr: &Expr| {
+        type_check_expr(expr, ctx)
+            .ok()
+            .map(|attr_type| attr_type.without_null())
+    };
+    match expr {
+        Expr::Binary(BinaryExpr { left, op, right }) => {
+            let operand_types = match op.is_comparison() {
+                true => (type_of(&left), type_of(&right)),
+                false => (None, None),
+            };
+            let left = coerce_comparisons(*left, ctx);
+            let right = coerce_comparisons(*right, ctx);
+            let (left, right) = match operand_types {
+                (Some(AttributeType::Enum(enum_type)), _) => {
+                    (left, enum_label_value(&enum_type, right))
+                }
+                (_, Some(AttributeType::Enum(enum_type))) => {
+                    (enum_label_value(&enum_type, left), right)
+                }
+                _ => (left, right),
+            };
+            Expr::Binary(BinaryExpr {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            })
+        }
+        Expr::Unary(UnaryExpr { op, operand }) => Expr::Unary(UnaryExpr {
+            op,
+            operand: Box::new(coerce_comparisons(*operand, ctx)),
+        }),
+        Expr::Function(FunctionCall { name, args }) => Expr::Function(FunctionCall {
+            name,
+            args: args
+                .into_iter()
+                .map(|arg| coerce_comparisons(arg, ctx))
+                .collect(),
+        }),
+        expr => expr,
+    }
+}
+
+// The value of the enum for a label literal, see `type_check_enum_label`. The internal
+// `$enum_label` function builds it from the position and the label.
+fn enum_label_value(enum_type: &EnumType, expr: Expr) -> Expr {
+    match expr {
+        Expr::Literal(LiteralExpr::String(label)) => match enum_type.find_label(&label) {
+            Some(value) => Expr::Function(FunctionCall {
+                name: "$enum_label".to_owned(),
+                args: vec![
+                    Expr::Literal(LiteralExpr::Integer(value.ordinal as i32)),
+                    Expr::Literal(LiteralExpr::String(value.label)),
+                ],
+            }),
+            None => Expr::Literal(LiteralExpr::String(label)),
+        },
+        expr => expr,
+    }
+}
+
+// Computed columns without an alias are named after the expression that produces them.
 pub fn type_check_projection(
-    attr_names: &Vec<String>,
+    items: &[SelectItem],
     ctx: &HashMap<&String, &AttributeType>,
 ) -> Result<Attributes> {
     let mut attributes = Vec::new();
-    for attr_name in attr_names {
-        match ctx.get(attr_name) {
-            Some(attr_type) => {
-                attributes.push((AttributeName(attr_name.clone()), (*attr_type).clone()))
-            }
-            None => return Err(TranslateError::NoSuchAttribute(attr_name.clone())),
+    for item in items {
+        let attr_type = match &item.expr {
+            Expr::Literal(LiteralExpr::Identifier(attr_name)) => match ctx.get(attr_name) {
+                Some(attr_type) => (*attr_type).clone(),
+                None => return Err(TranslateError::NoSuchAttribute(attr_name.clone())),
+            },
+            expr => type_check_expr(expr, ctx)?,
+        };
+        attributes.push((AttributeName(item.name()), attr_type));
+    }
+    Ok(Attributes::new(attributes))
+}
+
+// The records of the queries of a set operation are combined by position, the attributes are
+// named after those of the left query. An attribute is nullable when it is on either side.
+pub fn type_check_set_operation(
+    op: &SetOperator,
+    left: &Attributes,
+    right: &Attributes,
+) -> Result<Attributes> {
+    let left = left.attributes_iter().collect::<Vec<_>>();
+    let right = right.attributes_iter().collect::<Vec<_>>();
+    if left.len() != right.len() {
+        return Err(TranslateError::TypeError(format!(
+            "each {:?} query must have the same number of attributes, got {} and {}",
+            op,
+            left.len(),
+            right.len()
+        )));
+    }
+
+    let mut attributes = Vec::new();
+    for ((attr_name, left_type), (_, right_type)) in left.into_iter().zip(right) {
+        if left_type.base_type() != right_type.base_type() {
+            return Err(TranslateError::TypeError(format!(
+                "{:?} types {:?} and {:?} of attribute {:?} do not match",
+                op, left_type, right_type, attr_name.0
+            )));
         }
+        let nullable = left_type.is_nullable() || right_type.is_nullable();
+        attributes.push((
+            attr_name.clone(),
+            nullable_if(left_type.base_type(), nullable),
+        ));
     }
     Ok(Attributes::new(attributes))
 }
 
+// The type of a subquery whose records have the given attributes, the operand of IN is type
+// checked in the context of the records of the query the subquery is nested in.
+pub fn type_check_subquery(
+    kind: &SubqueryKind,
+    attributes: &Attributes,
+    ctx: &HashMap<&String, &AttributeType>,
+) -> Result<AttributeType> {
+    let attributes = attributes.attributes_iter().collect::<Vec<_>>();
+    let attr_type = match (kind, attributes.as_slice()) {
+        (SubqueryKind::Exists, _) => return Ok(AttributeType::Boolean),
+        (_, [(_, attr_type)]) => attr_type,
+        (_, attributes) => {
+            return Err(TranslateError::InvalidArguments(format!(
+                "subquery must return a single attribute, got {}",
+                attributes.len()
+            )))
+        }
+    };
+    match kind {
+        SubqueryKind::In(expr) => {
+            let operand = type_check_expr(expr, ctx)?;
+            if operand.base_type() != attr_type.base_type() {
+                return Err(TranslateError::TypeError(format!(
+                    "For IN operation, left {:?} != right {:?}",
+                    operand, attr_type
+                )));
+            }
+            // When no value matches, IN is NULL if the operand or a value is.
+            let nullable = operand.is_nullable() || attr_type.is_nullable();
+            Ok(nullable_if(AttributeType::Boolean, nullable))
+        }
+        // A query that has no record is NULL.
+        _ => Ok(attr_type.base_type().nullable()),
+    }
+}
+
 // TODO make return type a boolean expression.
 pub fn type_check_join_predicate(
     predicate: WhereClause,
@@ -91,8 +468,8 @@ pub fn type_check_join_predicate(
             "no join condition provided".to_owned(),
         )),
         WhereClause::Expr(expr) => {
-            type_check_expr(&expr, ctx).and_then(|expr_type| match expr_type {
-                AttributeType::Boolean => Ok(expr),
+            type_check_expr(&expr, ctx).and_then(|expr_type| match expr_type.base_type() {
+                AttributeType::Boolean => Ok(coerce_comparisons(expr, ctx)),
                 invalid => Err(TranslateError::TypeError(format!(
                     "invalid type {:?} join condition must be type {:?}",
                     invalid,
@@ -102,3 +479,22 @@ pub fn type_check_join_predicate(
         }
     }
 }
+
+// A where clause of a statement that modifies a table, it must be a boolean expression.
+pub fn type_check_where_clause(
+    where_clause: WhereClause,
+    ctx: &HashMap<&String, &AttributeType>,
+) -> Result<Option<Expr>> {
+    match where_clause {
+        WhereClause::None => Ok(None),
+        // A predicate that is NULL does not hold.
+        WhereClause::Expr(expr) => match type_check_expr(&expr, ctx)?.base_type() {
+            AttributeType::Boolean => Ok(Some(coerce_comparisons(expr, ctx))),
+            invalid => Err(TranslateError::TypeError(format!(
+                "invalid type {:?} where clause must be type {:?}",
+                invalid,
+                AttributeType::Boolean
+            ))),
+        },
+    }
+}